@@ -30,6 +30,622 @@ pub mod error {
         }
     }
 }
+///When the tool applies this instruction: always, when editing files matching applies_to (glob), when the agent judges it relevant from the description (agent), or only when referenced explicitly (manual)
+///
+/// <details><summary>JSON schema</summary>
+///
+/// ```json
+///{
+///  "description": "When the tool applies this instruction: always, when editing files matching applies_to (glob), when the agent judges it relevant from the description (agent), or only when referenced explicitly (manual)",
+///  "type": "string",
+///  "enum": [
+///    "always",
+///    "glob",
+///    "agent",
+///    "manual"
+///  ]
+///}
+/// ```
+/// </details>
+#[derive(
+    ::serde::Deserialize,
+    ::serde::Serialize,
+    Clone,
+    Copy,
+    Debug,
+    Eq,
+    Hash,
+    Ord,
+    PartialEq,
+    PartialOrd
+)]
+pub enum Activation {
+    #[serde(rename = "always")]
+    Always,
+    #[serde(rename = "glob")]
+    Glob,
+    #[serde(rename = "agent")]
+    Agent,
+    #[serde(rename = "manual")]
+    Manual,
+}
+impl ::std::convert::From<&Self> for Activation {
+    fn from(value: &Activation) -> Self {
+        value.clone()
+    }
+}
+impl ::std::fmt::Display for Activation {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        match *self {
+            Self::Always => write!(f, "always"),
+            Self::Glob => write!(f, "glob"),
+            Self::Agent => write!(f, "agent"),
+            Self::Manual => write!(f, "manual"),
+        }
+    }
+}
+impl ::std::str::FromStr for Activation {
+    type Err = self::error::ConversionError;
+    fn from_str(
+        value: &str,
+    ) -> ::std::result::Result<Self, self::error::ConversionError> {
+        match value {
+            "always" => Ok(Self::Always),
+            "glob" => Ok(Self::Glob),
+            "agent" => Ok(Self::Agent),
+            "manual" => Ok(Self::Manual),
+            _ => Err("invalid value".into()),
+        }
+    }
+}
+impl ::std::convert::TryFrom<&str> for Activation {
+    type Error = self::error::ConversionError;
+    fn try_from(
+        value: &str,
+    ) -> ::std::result::Result<Self, self::error::ConversionError> {
+        value.parse()
+    }
+}
+impl ::std::convert::TryFrom<&::std::string::String> for Activation {
+    type Error = self::error::ConversionError;
+    fn try_from(
+        value: &::std::string::String,
+    ) -> ::std::result::Result<Self, self::error::ConversionError> {
+        value.parse()
+    }
+}
+impl ::std::convert::TryFrom<::std::string::String> for Activation {
+    type Error = self::error::ConversionError;
+    fn try_from(
+        value: ::std::string::String,
+    ) -> ::std::result::Result<Self, self::error::ConversionError> {
+        value.parse()
+    }
+}
+///Output settings of a user-defined target
+///
+/// <details><summary>JSON schema</summary>
+///
+/// ```json
+///{
+///  "description": "Output settings of a user-defined target",
+///  "type": "object",
+///  "required": [
+///    "path"
+///  ],
+///  "properties": {
+///    "filename": {
+///      "description": "File name template of each instruction in multiple mode. {{slug}} and {{title}} are replaced",
+///      "default": "{{slug}}.md",
+///      "type": "string"
+///    },
+///    "frontmatter": {
+///      "description": "YAML frontmatter fields written at the top of each file in multiple mode. {{title}}, {{description}}, {{applies_to}} and {{activation}} in values are replaced, and fields that render empty are omitted",
+///      "type": "object",
+///      "additionalProperties": {
+///        "type": "string"
+///      }
+///    },
+///    "header": {
+///      "description": "Text written at the top of the output file (single) or of each file before the instruction heading (multiple)",
+///      "type": "string"
+///    },
+///    "indexPrefix": {
+///      "description": "In multiple mode, prefix each file name with a zero-padded index in config order (01-coding.md). Defaults to false",
+///      "type": "boolean"
+///    },
+///    "managed": {
+///      "$ref": "#/$defs/managed_mode"
+///    },
+///    "outputMode": {
+///      "description": "Whether to output to a single file or one file per instruction",
+///      "default": "single",
+///      "type": "string",
+///      "enum": [
+///        "single",
+///        "multiple"
+///      ]
+///    },
+///    "path": {
+///      "description": "Output file (single) or directory (multiple) relative to the repository root",
+///      "type": "string"
+///    }
+///  },
+///  "additionalProperties": false
+///}
+/// ```
+/// </details>
+#[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct CustomTarget {
+    ///File name template of each instruction in multiple mode. {{slug}} and {{title}} are replaced
+    #[serde(default = "defaults::custom_target_filename")]
+    pub filename: ::std::string::String,
+    ///YAML frontmatter fields written at the top of each file in multiple mode. {{title}}, {{description}}, {{applies_to}} and {{activation}} in values are replaced, and fields that render empty are omitted
+    #[serde(default, skip_serializing_if = ":: std :: collections :: HashMap::is_empty")]
+    pub frontmatter: ::std::collections::HashMap<
+        ::std::string::String,
+        ::std::string::String,
+    >,
+    ///Text written at the top of the output file (single) or of each file before the instruction heading (multiple)
+    #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+    pub header: ::std::option::Option<::std::string::String>,
+    ///In multiple mode, prefix each file name with a zero-padded index in config order (01-coding.md). Defaults to false
+    #[serde(
+        rename = "indexPrefix",
+        default,
+        skip_serializing_if = "::std::option::Option::is_none"
+    )]
+    pub index_prefix: ::std::option::Option<bool>,
+    #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+    pub managed: ::std::option::Option<ManagedMode>,
+    ///Whether to output to a single file or one file per instruction
+    #[serde(rename = "outputMode", default = "defaults::custom_target_output_mode")]
+    pub output_mode: CustomTargetOutputMode,
+    ///Output file (single) or directory (multiple) relative to the repository root
+    pub path: ::std::string::String,
+}
+impl ::std::convert::From<&CustomTarget> for CustomTarget {
+    fn from(value: &CustomTarget) -> Self {
+        value.clone()
+    }
+}
+impl CustomTarget {
+    pub fn builder() -> builder::CustomTarget {
+        Default::default()
+    }
+}
+///Name of a custom target defined under custom (must not be a built-in target name)
+///
+/// <details><summary>JSON schema</summary>
+///
+/// ```json
+///{
+///  "description": "Name of a custom target defined under custom (must not be a built-in target name)",
+///  "type": "string",
+///  "pattern": "^(?!(all|copilot|cline|cursor|junie|codex|agentsmd|claude|windsurf|gemini)$)[a-z][a-z0-9_-]*$"
+///}
+/// ```
+/// </details>
+#[derive(::serde::Serialize, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[serde(transparent)]
+pub struct CustomTargetName(::std::string::String);
+impl ::std::ops::Deref for CustomTargetName {
+    type Target = ::std::string::String;
+    fn deref(&self) -> &::std::string::String {
+        &self.0
+    }
+}
+impl ::std::convert::From<CustomTargetName> for ::std::string::String {
+    fn from(value: CustomTargetName) -> Self {
+        value.0
+    }
+}
+impl ::std::convert::From<&CustomTargetName> for CustomTargetName {
+    fn from(value: &CustomTargetName) -> Self {
+        value.clone()
+    }
+}
+impl ::std::str::FromStr for CustomTargetName {
+    type Err = self::error::ConversionError;
+    fn from_str(
+        value: &str,
+    ) -> ::std::result::Result<Self, self::error::ConversionError> {
+        static PATTERN: ::std::sync::LazyLock<::regress::Regex> = ::std::sync::LazyLock::new(||
+        {
+            ::regress::Regex::new(
+                    "^(?!(all|copilot|cline|cursor|junie|codex|agentsmd|claude|windsurf|gemini)$)[a-z][a-z0-9_-]*$",
+                )
+                .unwrap()
+        });
+        if (&*PATTERN).find(value).is_none() {
+            return Err(
+                "doesn't match pattern \"^(?!(all|copilot|cline|cursor|junie|codex|agentsmd|claude|windsurf|gemini)$)[a-z][a-z0-9_-]*$\""
+                    .into(),
+            );
+        }
+        Ok(Self(value.to_string()))
+    }
+}
+impl ::std::convert::TryFrom<&str> for CustomTargetName {
+    type Error = self::error::ConversionError;
+    fn try_from(
+        value: &str,
+    ) -> ::std::result::Result<Self, self::error::ConversionError> {
+        value.parse()
+    }
+}
+impl ::std::convert::TryFrom<&::std::string::String> for CustomTargetName {
+    type Error = self::error::ConversionError;
+    fn try_from(
+        value: &::std::string::String,
+    ) -> ::std::result::Result<Self, self::error::ConversionError> {
+        value.parse()
+    }
+}
+impl ::std::convert::TryFrom<::std::string::String> for CustomTargetName {
+    type Error = self::error::ConversionError;
+    fn try_from(
+        value: ::std::string::String,
+    ) -> ::std::result::Result<Self, self::error::ConversionError> {
+        value.parse()
+    }
+}
+impl<'de> ::serde::Deserialize<'de> for CustomTargetName {
+    fn deserialize<D>(deserializer: D) -> ::std::result::Result<Self, D::Error>
+    where
+        D: ::serde::Deserializer<'de>,
+    {
+        ::std::string::String::deserialize(deserializer)?
+            .parse()
+            .map_err(|e: self::error::ConversionError| {
+                <D::Error as ::serde::de::Error>::custom(e.to_string())
+            })
+    }
+}
+///Whether to output to a single file or one file per instruction
+///
+/// <details><summary>JSON schema</summary>
+///
+/// ```json
+///{
+///  "description": "Whether to output to a single file or one file per instruction",
+///  "default": "single",
+///  "type": "string",
+///  "enum": [
+///    "single",
+///    "multiple"
+///  ]
+///}
+/// ```
+/// </details>
+#[derive(
+    ::serde::Deserialize,
+    ::serde::Serialize,
+    Clone,
+    Copy,
+    Debug,
+    Eq,
+    Hash,
+    Ord,
+    PartialEq,
+    PartialOrd
+)]
+pub enum CustomTargetOutputMode {
+    #[serde(rename = "single")]
+    Single,
+    #[serde(rename = "multiple")]
+    Multiple,
+}
+impl ::std::convert::From<&Self> for CustomTargetOutputMode {
+    fn from(value: &CustomTargetOutputMode) -> Self {
+        value.clone()
+    }
+}
+impl ::std::fmt::Display for CustomTargetOutputMode {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        match *self {
+            Self::Single => write!(f, "single"),
+            Self::Multiple => write!(f, "multiple"),
+        }
+    }
+}
+impl ::std::str::FromStr for CustomTargetOutputMode {
+    type Err = self::error::ConversionError;
+    fn from_str(
+        value: &str,
+    ) -> ::std::result::Result<Self, self::error::ConversionError> {
+        match value {
+            "single" => Ok(Self::Single),
+            "multiple" => Ok(Self::Multiple),
+            _ => Err("invalid value".into()),
+        }
+    }
+}
+impl ::std::convert::TryFrom<&str> for CustomTargetOutputMode {
+    type Error = self::error::ConversionError;
+    fn try_from(
+        value: &str,
+    ) -> ::std::result::Result<Self, self::error::ConversionError> {
+        value.parse()
+    }
+}
+impl ::std::convert::TryFrom<&::std::string::String> for CustomTargetOutputMode {
+    type Error = self::error::ConversionError;
+    fn try_from(
+        value: &::std::string::String,
+    ) -> ::std::result::Result<Self, self::error::ConversionError> {
+        value.parse()
+    }
+}
+impl ::std::convert::TryFrom<::std::string::String> for CustomTargetOutputMode {
+    type Error = self::error::ConversionError;
+    fn try_from(
+        value: ::std::string::String,
+    ) -> ::std::result::Result<Self, self::error::ConversionError> {
+        value.parse()
+    }
+}
+impl ::std::default::Default for CustomTargetOutputMode {
+    fn default() -> Self {
+        CustomTargetOutputMode::Single
+    }
+}
+///How file names are derived from instruction titles in multiple mode: the title with characters invalid in file names replaced (title), a lowercase slug keeping non-ASCII letters (slug), or a lowercase ASCII-only slug, falling back to a hash of the title (ascii)
+///
+/// <details><summary>JSON schema</summary>
+///
+/// ```json
+///{
+///  "description": "How file names are derived from instruction titles in multiple mode: the title with characters invalid in file names replaced (title), a lowercase slug keeping non-ASCII letters (slug), or a lowercase ASCII-only slug, falling back to a hash of the title (ascii)",
+///  "default": "title",
+///  "type": "string",
+///  "enum": [
+///    "title",
+///    "slug",
+///    "ascii"
+///  ]
+///}
+/// ```
+/// </details>
+#[derive(
+    ::serde::Deserialize,
+    ::serde::Serialize,
+    Clone,
+    Copy,
+    Debug,
+    Eq,
+    Hash,
+    Ord,
+    PartialEq,
+    PartialOrd
+)]
+pub enum FilenameStyle {
+    #[serde(rename = "title")]
+    Title,
+    #[serde(rename = "slug")]
+    Slug,
+    #[serde(rename = "ascii")]
+    Ascii,
+}
+impl ::std::convert::From<&Self> for FilenameStyle {
+    fn from(value: &FilenameStyle) -> Self {
+        value.clone()
+    }
+}
+impl ::std::fmt::Display for FilenameStyle {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        match *self {
+            Self::Title => write!(f, "title"),
+            Self::Slug => write!(f, "slug"),
+            Self::Ascii => write!(f, "ascii"),
+        }
+    }
+}
+impl ::std::str::FromStr for FilenameStyle {
+    type Err = self::error::ConversionError;
+    fn from_str(
+        value: &str,
+    ) -> ::std::result::Result<Self, self::error::ConversionError> {
+        match value {
+            "title" => Ok(Self::Title),
+            "slug" => Ok(Self::Slug),
+            "ascii" => Ok(Self::Ascii),
+            _ => Err("invalid value".into()),
+        }
+    }
+}
+impl ::std::convert::TryFrom<&str> for FilenameStyle {
+    type Error = self::error::ConversionError;
+    fn try_from(
+        value: &str,
+    ) -> ::std::result::Result<Self, self::error::ConversionError> {
+        value.parse()
+    }
+}
+impl ::std::convert::TryFrom<&::std::string::String> for FilenameStyle {
+    type Error = self::error::ConversionError;
+    fn try_from(
+        value: &::std::string::String,
+    ) -> ::std::result::Result<Self, self::error::ConversionError> {
+        value.parse()
+    }
+}
+impl ::std::convert::TryFrom<::std::string::String> for FilenameStyle {
+    type Error = self::error::ConversionError;
+    fn try_from(
+        value: ::std::string::String,
+    ) -> ::std::result::Result<Self, self::error::ConversionError> {
+        value.parse()
+    }
+}
+impl ::std::default::Default for FilenameStyle {
+    fn default() -> Self {
+        FilenameStyle::Title
+    }
+}
+///File name without extension used for this instruction in multiple mode instead of one derived from the title
+///
+/// <details><summary>JSON schema</summary>
+///
+/// ```json
+///{
+///  "description": "File name without extension used for this instruction in multiple mode instead of one derived from the title",
+///  "type": "string",
+///  "pattern": "^[A-Za-z0-9_-][A-Za-z0-9._-]*$"
+///}
+/// ```
+/// </details>
+#[derive(::serde::Serialize, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[serde(transparent)]
+pub struct InstructionFilename(::std::string::String);
+impl ::std::ops::Deref for InstructionFilename {
+    type Target = ::std::string::String;
+    fn deref(&self) -> &::std::string::String {
+        &self.0
+    }
+}
+impl ::std::convert::From<InstructionFilename> for ::std::string::String {
+    fn from(value: InstructionFilename) -> Self {
+        value.0
+    }
+}
+impl ::std::convert::From<&InstructionFilename> for InstructionFilename {
+    fn from(value: &InstructionFilename) -> Self {
+        value.clone()
+    }
+}
+impl ::std::str::FromStr for InstructionFilename {
+    type Err = self::error::ConversionError;
+    fn from_str(
+        value: &str,
+    ) -> ::std::result::Result<Self, self::error::ConversionError> {
+        static PATTERN: ::std::sync::LazyLock<::regress::Regex> = ::std::sync::LazyLock::new(||
+        { ::regress::Regex::new("^[A-Za-z0-9_-][A-Za-z0-9._-]*$").unwrap() });
+        if (&*PATTERN).find(value).is_none() {
+            return Err(
+                "doesn't match pattern \"^[A-Za-z0-9_-][A-Za-z0-9._-]*$\"".into(),
+            );
+        }
+        Ok(Self(value.to_string()))
+    }
+}
+impl ::std::convert::TryFrom<&str> for InstructionFilename {
+    type Error = self::error::ConversionError;
+    fn try_from(
+        value: &str,
+    ) -> ::std::result::Result<Self, self::error::ConversionError> {
+        value.parse()
+    }
+}
+impl ::std::convert::TryFrom<&::std::string::String> for InstructionFilename {
+    type Error = self::error::ConversionError;
+    fn try_from(
+        value: &::std::string::String,
+    ) -> ::std::result::Result<Self, self::error::ConversionError> {
+        value.parse()
+    }
+}
+impl ::std::convert::TryFrom<::std::string::String> for InstructionFilename {
+    type Error = self::error::ConversionError;
+    fn try_from(
+        value: ::std::string::String,
+    ) -> ::std::result::Result<Self, self::error::ConversionError> {
+        value.parse()
+    }
+}
+impl<'de> ::serde::Deserialize<'de> for InstructionFilename {
+    fn deserialize<D>(deserializer: D) -> ::std::result::Result<Self, D::Error>
+    where
+        D: ::serde::Deserializer<'de>,
+    {
+        ::std::string::String::deserialize(deserializer)?
+            .parse()
+            .map_err(|e: self::error::ConversionError| {
+                <D::Error as ::serde::de::Error>::custom(e.to_string())
+            })
+    }
+}
+///Stable identifier of the instruction, unique in the configuration. Emitted invisibly in generated files so that renamed instructions can be tracked
+///
+/// <details><summary>JSON schema</summary>
+///
+/// ```json
+///{
+///  "description": "Stable identifier of the instruction, unique in the configuration. Emitted invisibly in generated files so that renamed instructions can be tracked",
+///  "type": "string",
+///  "pattern": "^[A-Za-z0-9][A-Za-z0-9._-]*$"
+///}
+/// ```
+/// </details>
+#[derive(::serde::Serialize, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[serde(transparent)]
+pub struct InstructionId(::std::string::String);
+impl ::std::ops::Deref for InstructionId {
+    type Target = ::std::string::String;
+    fn deref(&self) -> &::std::string::String {
+        &self.0
+    }
+}
+impl ::std::convert::From<InstructionId> for ::std::string::String {
+    fn from(value: InstructionId) -> Self {
+        value.0
+    }
+}
+impl ::std::convert::From<&InstructionId> for InstructionId {
+    fn from(value: &InstructionId) -> Self {
+        value.clone()
+    }
+}
+impl ::std::str::FromStr for InstructionId {
+    type Err = self::error::ConversionError;
+    fn from_str(
+        value: &str,
+    ) -> ::std::result::Result<Self, self::error::ConversionError> {
+        static PATTERN: ::std::sync::LazyLock<::regress::Regex> = ::std::sync::LazyLock::new(||
+        { ::regress::Regex::new("^[A-Za-z0-9][A-Za-z0-9._-]*$").unwrap() });
+        if (&*PATTERN).find(value).is_none() {
+            return Err("doesn't match pattern \"^[A-Za-z0-9][A-Za-z0-9._-]*$\"".into());
+        }
+        Ok(Self(value.to_string()))
+    }
+}
+impl ::std::convert::TryFrom<&str> for InstructionId {
+    type Error = self::error::ConversionError;
+    fn try_from(
+        value: &str,
+    ) -> ::std::result::Result<Self, self::error::ConversionError> {
+        value.parse()
+    }
+}
+impl ::std::convert::TryFrom<&::std::string::String> for InstructionId {
+    type Error = self::error::ConversionError;
+    fn try_from(
+        value: &::std::string::String,
+    ) -> ::std::result::Result<Self, self::error::ConversionError> {
+        value.parse()
+    }
+}
+impl ::std::convert::TryFrom<::std::string::String> for InstructionId {
+    type Error = self::error::ConversionError;
+    fn try_from(
+        value: ::std::string::String,
+    ) -> ::std::result::Result<Self, self::error::ConversionError> {
+        value.parse()
+    }
+}
+impl<'de> ::serde::Deserialize<'de> for InstructionId {
+    fn deserialize<D>(deserializer: D) -> ::std::result::Result<Self, D::Error>
+    where
+        D: ::serde::Deserializer<'de>,
+    {
+        ::std::string::String::deserialize(deserializer)?
+            .parse()
+            .map_err(|e: self::error::ConversionError| {
+                <D::Error as ::serde::de::Error>::custom(e.to_string())
+            })
+    }
+}
 ///`InstructionItem`
 ///
 /// <details><summary>JSON schema</summary>
@@ -58,6 +674,16 @@ pub mod error {
 ///    "title"
 ///  ],
 ///  "properties": {
+///    "activation": {
+///      "$ref": "#/$defs/activation"
+///    },
+///    "applies_to": {
+///      "description": "Glob patterns of the files this instruction applies to",
+///      "type": "array",
+///      "items": {
+///        "type": "string"
+///      }
+///    },
 ///    "body": {
 ///      "description": "Instruction body content",
 ///      "type": "string"
@@ -70,11 +696,21 @@ pub mod error {
 ///      "description": "Detailed description of the instruction",
 ///      "type": "string"
 ///    },
+///    "directory": {
+///      "description": "Directory (or glob of directories) relative to the repository root whose AGENTS.md receives this instruction instead of the root AGENTS.md (agentsmd)",
+///      "type": "string"
+///    },
 ///    "disable": {
 ///      "description": "Disable this instruction",
 ///      "default": false,
 ///      "type": "boolean"
 ///    },
+///    "filename": {
+///      "$ref": "#/$defs/instruction_filename"
+///    },
+///    "id": {
+///      "$ref": "#/$defs/instruction_id"
+///    },
 ///    "instructions": {
 ///      "description": "Nested instructions",
 ///      "type": "array",
@@ -89,7 +725,7 @@ pub mod error {
 ///          "description": "List of targets to generate this instruction for",
 ///          "type": "array",
 ///          "items": {
-///            "$ref": "#/$defs/targets"
+///            "$ref": "#/$defs/target_name"
 ///          }
 ///        },
 ///        {
@@ -112,40 +748,76 @@ pub mod error {
 #[serde(untagged, deny_unknown_fields)]
 pub enum InstructionItem {
     Variant0 {
+        #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+        activation: ::std::option::Option<Activation>,
+        ///Glob patterns of the files this instruction applies to
+        #[serde(default, skip_serializing_if = "::std::vec::Vec::is_empty")]
+        applies_to: ::std::vec::Vec<::std::string::String>,
         ///Instruction body content
         body: ::std::string::String,
         ///Detailed description of the instruction
         #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
         description: ::std::option::Option<::std::string::String>,
+        ///Directory (or glob of directories) relative to the repository root whose AGENTS.md receives this instruction instead of the root AGENTS.md (agentsmd)
+        #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+        directory: ::std::option::Option<::std::string::String>,
         ///Disable this instruction
         #[serde(default)]
         disable: bool,
+        #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+        filename: ::std::option::Option<InstructionFilename>,
+        #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+        id: ::std::option::Option<InstructionId>,
         #[serde(default = "defaults::instruction_item_variant0_targets")]
         targets: InstructionItemVariant0Targets,
         ///Title of the instruction
         title: ::std::string::String,
     },
     Variant1 {
+        #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+        activation: ::std::option::Option<Activation>,
+        ///Glob patterns of the files this instruction applies to
+        #[serde(default, skip_serializing_if = "::std::vec::Vec::is_empty")]
+        applies_to: ::std::vec::Vec<::std::string::String>,
         ///Name of file containing the instruction body
         body_file: ::std::string::String,
         ///Detailed description of the instruction
         #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
         description: ::std::option::Option<::std::string::String>,
+        ///Directory (or glob of directories) relative to the repository root whose AGENTS.md receives this instruction instead of the root AGENTS.md (agentsmd)
+        #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+        directory: ::std::option::Option<::std::string::String>,
         ///Disable this instruction
         #[serde(default)]
         disable: bool,
+        #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+        filename: ::std::option::Option<InstructionFilename>,
+        #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+        id: ::std::option::Option<InstructionId>,
         #[serde(default = "defaults::instruction_item_variant1_targets")]
         targets: InstructionItemVariant1Targets,
         ///Title of the instruction
         title: ::std::string::String,
     },
     Variant2 {
+        #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+        activation: ::std::option::Option<Activation>,
+        ///Glob patterns of the files this instruction applies to
+        #[serde(default, skip_serializing_if = "::std::vec::Vec::is_empty")]
+        applies_to: ::std::vec::Vec<::std::string::String>,
         ///Detailed description of the instruction
         #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
         description: ::std::option::Option<::std::string::String>,
+        ///Directory (or glob of directories) relative to the repository root whose AGENTS.md receives this instruction instead of the root AGENTS.md (agentsmd)
+        #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+        directory: ::std::option::Option<::std::string::String>,
         ///Disable this instruction
         #[serde(default)]
         disable: bool,
+        #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+        filename: ::std::option::Option<InstructionFilename>,
+        #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+        id: ::std::option::Option<InstructionId>,
         ///Nested instructions
         instructions: ::std::vec::Vec<InstructionItem>,
         #[serde(default = "defaults::instruction_item_variant2_targets")]
@@ -171,7 +843,7 @@ impl ::std::convert::From<&Self> for InstructionItem {
 ///      "description": "List of targets to generate this instruction for",
 ///      "type": "array",
 ///      "items": {
-///        "$ref": "#/$defs/targets"
+///        "$ref": "#/$defs/target_name"
 ///      }
 ///    },
 ///    {
@@ -186,7 +858,7 @@ impl ::std::convert::From<&Self> for InstructionItem {
 #[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug)]
 #[serde(untagged)]
 pub enum InstructionItemVariant0Targets {
-    Variant0(::std::vec::Vec<Targets>),
+    Variant0(::std::vec::Vec<TargetName>),
     Variant1(::std::string::String),
 }
 impl ::std::convert::From<&Self> for InstructionItemVariant0Targets {
@@ -199,8 +871,9 @@ impl ::std::default::Default for InstructionItemVariant0Targets {
         InstructionItemVariant0Targets::Variant1("all".to_string())
     }
 }
-impl ::std::convert::From<::std::vec::Vec<Targets>> for InstructionItemVariant0Targets {
-    fn from(value: ::std::vec::Vec<Targets>) -> Self {
+impl ::std::convert::From<::std::vec::Vec<TargetName>>
+for InstructionItemVariant0Targets {
+    fn from(value: ::std::vec::Vec<TargetName>) -> Self {
         Self::Variant0(value)
     }
 }
@@ -216,7 +889,7 @@ impl ::std::convert::From<::std::vec::Vec<Targets>> for InstructionItemVariant0T
 ///      "description": "List of targets to generate this instruction for",
 ///      "type": "array",
 ///      "items": {
-///        "$ref": "#/$defs/targets"
+///        "$ref": "#/$defs/target_name"
 ///      }
 ///    },
 ///    {
@@ -231,7 +904,7 @@ impl ::std::convert::From<::std::vec::Vec<Targets>> for InstructionItemVariant0T
 #[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug)]
 #[serde(untagged)]
 pub enum InstructionItemVariant1Targets {
-    Variant0(::std::vec::Vec<Targets>),
+    Variant0(::std::vec::Vec<TargetName>),
     Variant1(::std::string::String),
 }
 impl ::std::convert::From<&Self> for InstructionItemVariant1Targets {
@@ -244,8 +917,9 @@ impl ::std::default::Default for InstructionItemVariant1Targets {
         InstructionItemVariant1Targets::Variant1("all".to_string())
     }
 }
-impl ::std::convert::From<::std::vec::Vec<Targets>> for InstructionItemVariant1Targets {
-    fn from(value: ::std::vec::Vec<Targets>) -> Self {
+impl ::std::convert::From<::std::vec::Vec<TargetName>>
+for InstructionItemVariant1Targets {
+    fn from(value: ::std::vec::Vec<TargetName>) -> Self {
         Self::Variant0(value)
     }
 }
@@ -261,7 +935,7 @@ impl ::std::convert::From<::std::vec::Vec<Targets>> for InstructionItemVariant1T
 ///      "description": "List of targets to generate this instruction for",
 ///      "type": "array",
 ///      "items": {
-///        "$ref": "#/$defs/targets"
+///        "$ref": "#/$defs/target_name"
 ///      }
 ///    },
 ///    {
@@ -276,7 +950,7 @@ impl ::std::convert::From<::std::vec::Vec<Targets>> for InstructionItemVariant1T
 #[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug)]
 #[serde(untagged)]
 pub enum InstructionItemVariant2Targets {
-    Variant0(::std::vec::Vec<Targets>),
+    Variant0(::std::vec::Vec<TargetName>),
     Variant1(::std::string::String),
 }
 impl ::std::convert::From<&Self> for InstructionItemVariant2Targets {
@@ -289,8 +963,9 @@ impl ::std::default::Default for InstructionItemVariant2Targets {
         InstructionItemVariant2Targets::Variant1("all".to_string())
     }
 }
-impl ::std::convert::From<::std::vec::Vec<Targets>> for InstructionItemVariant2Targets {
-    fn from(value: ::std::vec::Vec<Targets>) -> Self {
+impl ::std::convert::From<::std::vec::Vec<TargetName>>
+for InstructionItemVariant2Targets {
+    fn from(value: ::std::vec::Vec<TargetName>) -> Self {
         Self::Variant0(value)
     }
 }
@@ -307,6 +982,16 @@ impl ::std::convert::From<::std::vec::Vec<Targets>> for InstructionItemVariant2T
 ///    "version"
 ///  ],
 ///  "properties": {
+///    "custom": {
+///      "description": "User-defined targets rendered from a path and templates, each as a property keyed by the target name",
+///      "type": "object",
+///      "additionalProperties": {
+///        "$ref": "#/$defs/custom_target"
+///      },
+///      "propertyNames": {
+///        "$ref": "#/$defs/custom_target_name"
+///      }
+///    },
 ///    "instructions": {
 ///      "description": "List of instructions to generate",
 ///      "type": "array",
@@ -324,6 +1009,20 @@ impl ::std::convert::From<::std::vec::Vec<Targets>> for InstructionItemVariant2T
 ///        "Japanese"
 ///      ]
 ///    },
+///    "sandbox": {
+///      "description": "Paths instrux may read body_file from and write generated files to. By default body_file must be inside .instrux/instructions/ and outputs inside the repository root",
+///      "type": "object",
+///      "properties": {
+///        "allow": {
+///          "description": "Additional files or directories (relative to the repository root, or absolute) that body_file and outputs may point to",
+///          "type": "array",
+///          "items": {
+///            "type": "string"
+///          }
+///        }
+///      },
+///      "additionalProperties": false
+///    },
 ///    "targets": {
 ///      "description": "AI coding tool formats to generate, each as a property with optional settings",
 ///      "type": "object",
@@ -331,6 +1030,20 @@ impl ::std::convert::From<::std::vec::Vec<Targets>> for InstructionItemVariant2T
 ///      "additionalProperties": {
 ///        "type": "object",
 ///        "properties": {
+///          "filenames": {
+///            "$ref": "#/$defs/filename_style"
+///          },
+///          "imports": {
+///            "description": "Use @-import lines instead of inlining content: body_file instructions are imported from .instrux/instructions/ (claude), nested instruction groups are written to separate files under .gemini/ (gemini). Defaults to false",
+///            "type": "boolean"
+///          },
+///          "indexPrefix": {
+///            "description": "In multiple mode, prefix each rule file name with a zero-padded index in config order (01-coding.md) so tools that read files alphabetically keep the intended order. Defaults to false",
+///            "type": "boolean"
+///          },
+///          "managed": {
+///            "$ref": "#/$defs/managed_mode"
+///          },
 ///          "outputMode": {
 ///            "description": "Whether to output to a single file or multiple files for this target",
 ///            "default": "single",
@@ -339,6 +1052,10 @@ impl ::std::convert::From<::std::vec::Vec<Targets>> for InstructionItemVariant2T
 ///              "single",
 ///              "multiple"
 ///            ]
+///          },
+///          "path": {
+///            "description": "Output file (single) or directory (multiple) relative to the repository root, replacing the tool's default location. With imports, the directory of the imported group files (gemini)",
+///            "type": "string"
 ///          }
 ///        },
 ///        "additionalProperties": false
@@ -347,6 +1064,23 @@ impl ::std::convert::From<::std::vec::Vec<Targets>> for InstructionItemVariant2T
 ///        "$ref": "#/$defs/targets"
 ///      }
 ///    },
+///    "validation": {
+///      "description": "Settings for the validate command",
+///      "type": "object",
+///      "properties": {
+///        "rules": {
+///          "description": "Severity of each semantic validation rule, keyed by rule ID",
+///          "type": "object",
+///          "additionalProperties": {
+///            "$ref": "#/$defs/rule_severity"
+///          },
+///          "propertyNames": {
+///            "$ref": "#/$defs/validation_rule"
+///          }
+///        }
+///      },
+///      "additionalProperties": false
+///    },
 ///    "version": {
 ///      "description": "Semantic version of this config file",
 ///      "type": "string",
@@ -360,14 +1094,21 @@ impl ::std::convert::From<::std::vec::Vec<Targets>> for InstructionItemVariant2T
 #[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct InstruxConfiguration {
+    ///User-defined targets rendered from a path and templates, each as a property keyed by the target name
+    #[serde(default, skip_serializing_if = ":: std :: collections :: HashMap::is_empty")]
+    pub custom: ::std::collections::HashMap<CustomTargetName, CustomTarget>,
     ///List of instructions to generate
     #[serde(default, skip_serializing_if = "::std::vec::Vec::is_empty")]
     pub instructions: ::std::vec::Vec<InstructionItem>,
     ///Human language for generated instructions
     #[serde(default = "defaults::instrux_configuration_language")]
     pub language: InstruxConfigurationLanguage,
+    #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+    pub sandbox: ::std::option::Option<InstruxConfigurationSandbox>,
     ///AI coding tool formats to generate, each as a property with optional settings
     pub targets: ::std::collections::HashMap<Targets, InstruxConfigurationTargetsValue>,
+    #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+    pub validation: ::std::option::Option<InstruxConfigurationValidation>,
     ///Semantic version of this config file
     pub version: InstruxConfigurationVersion,
 }
@@ -467,6 +1208,49 @@ impl ::std::default::Default for InstruxConfigurationLanguage {
         InstruxConfigurationLanguage::English
     }
 }
+///Paths instrux may read body_file from and write generated files to. By default body_file must be inside .instrux/instructions/ and outputs inside the repository root
+///
+/// <details><summary>JSON schema</summary>
+///
+/// ```json
+///{
+///  "description": "Paths instrux may read body_file from and write generated files to. By default body_file must be inside .instrux/instructions/ and outputs inside the repository root",
+///  "type": "object",
+///  "properties": {
+///    "allow": {
+///      "description": "Additional files or directories (relative to the repository root, or absolute) that body_file and outputs may point to",
+///      "type": "array",
+///      "items": {
+///        "type": "string"
+///      }
+///    }
+///  },
+///  "additionalProperties": false
+///}
+/// ```
+/// </details>
+#[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct InstruxConfigurationSandbox {
+    ///Additional files or directories (relative to the repository root, or absolute) that body_file and outputs may point to
+    #[serde(default, skip_serializing_if = "::std::vec::Vec::is_empty")]
+    pub allow: ::std::vec::Vec<::std::string::String>,
+}
+impl ::std::convert::From<&InstruxConfigurationSandbox> for InstruxConfigurationSandbox {
+    fn from(value: &InstruxConfigurationSandbox) -> Self {
+        value.clone()
+    }
+}
+impl ::std::default::Default for InstruxConfigurationSandbox {
+    fn default() -> Self {
+        Self { allow: Default::default() }
+    }
+}
+impl InstruxConfigurationSandbox {
+    pub fn builder() -> builder::InstruxConfigurationSandbox {
+        Default::default()
+    }
+}
 ///`InstruxConfigurationTargetsValue`
 ///
 /// <details><summary>JSON schema</summary>
@@ -475,6 +1259,20 @@ impl ::std::default::Default for InstruxConfigurationLanguage {
 ///{
 ///  "type": "object",
 ///  "properties": {
+///    "filenames": {
+///      "$ref": "#/$defs/filename_style"
+///    },
+///    "imports": {
+///      "description": "Use @-import lines instead of inlining content: body_file instructions are imported from .instrux/instructions/ (claude), nested instruction groups are written to separate files under .gemini/ (gemini). Defaults to false",
+///      "type": "boolean"
+///    },
+///    "indexPrefix": {
+///      "description": "In multiple mode, prefix each rule file name with a zero-padded index in config order (01-coding.md) so tools that read files alphabetically keep the intended order. Defaults to false",
+///      "type": "boolean"
+///    },
+///    "managed": {
+///      "$ref": "#/$defs/managed_mode"
+///    },
 ///    "outputMode": {
 ///      "description": "Whether to output to a single file or multiple files for this target",
 ///      "default": "single",
@@ -483,6 +1281,10 @@ impl ::std::default::Default for InstruxConfigurationLanguage {
 ///        "single",
 ///        "multiple"
 ///      ]
+///    },
+///    "path": {
+///      "description": "Output file (single) or directory (multiple) relative to the repository root, replacing the tool's default location. With imports, the directory of the imported group files (gemini)",
+///      "type": "string"
 ///    }
 ///  },
 ///  "additionalProperties": false
@@ -492,12 +1294,29 @@ impl ::std::default::Default for InstruxConfigurationLanguage {
 #[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct InstruxConfigurationTargetsValue {
+    #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+    pub filenames: ::std::option::Option<FilenameStyle>,
+    ///Use @-import lines instead of inlining content: body_file instructions are imported from .instrux/instructions/ (claude), nested instruction groups are written to separate files under .gemini/ (gemini). Defaults to false
+    #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+    pub imports: ::std::option::Option<bool>,
+    ///In multiple mode, prefix each rule file name with a zero-padded index in config order (01-coding.md) so tools that read files alphabetically keep the intended order. Defaults to false
+    #[serde(
+        rename = "indexPrefix",
+        default,
+        skip_serializing_if = "::std::option::Option::is_none"
+    )]
+    pub index_prefix: ::std::option::Option<bool>,
+    #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+    pub managed: ::std::option::Option<ManagedMode>,
     ///Whether to output to a single file or multiple files for this target
     #[serde(
         rename = "outputMode",
         default = "defaults::instrux_configuration_targets_value_output_mode"
     )]
     pub output_mode: InstruxConfigurationTargetsValueOutputMode,
+    ///Output file (single) or directory (multiple) relative to the repository root, replacing the tool's default location. With imports, the directory of the imported group files (gemini)
+    #[serde(default, skip_serializing_if = "::std::option::Option::is_none")]
+    pub path: ::std::option::Option<::std::string::String>,
 }
 impl ::std::convert::From<&InstruxConfigurationTargetsValue>
 for InstruxConfigurationTargetsValue {
@@ -508,7 +1327,12 @@ for InstruxConfigurationTargetsValue {
 impl ::std::default::Default for InstruxConfigurationTargetsValue {
     fn default() -> Self {
         Self {
+            filenames: Default::default(),
+            imports: Default::default(),
+            index_prefix: Default::default(),
+            managed: Default::default(),
             output_mode: defaults::instrux_configuration_targets_value_output_mode(),
+            path: Default::default(),
         }
     }
 }
@@ -607,6 +1431,53 @@ impl ::std::default::Default for InstruxConfigurationTargetsValueOutputMode {
         InstruxConfigurationTargetsValueOutputMode::Single
     }
 }
+///Settings for the validate command
+///
+/// <details><summary>JSON schema</summary>
+///
+/// ```json
+///{
+///  "description": "Settings for the validate command",
+///  "type": "object",
+///  "properties": {
+///    "rules": {
+///      "description": "Severity of each semantic validation rule, keyed by rule ID",
+///      "type": "object",
+///      "additionalProperties": {
+///        "$ref": "#/$defs/rule_severity"
+///      },
+///      "propertyNames": {
+///        "$ref": "#/$defs/validation_rule"
+///      }
+///    }
+///  },
+///  "additionalProperties": false
+///}
+/// ```
+/// </details>
+#[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct InstruxConfigurationValidation {
+    ///Severity of each semantic validation rule, keyed by rule ID
+    #[serde(default, skip_serializing_if = ":: std :: collections :: HashMap::is_empty")]
+    pub rules: ::std::collections::HashMap<ValidationRule, RuleSeverity>,
+}
+impl ::std::convert::From<&InstruxConfigurationValidation>
+for InstruxConfigurationValidation {
+    fn from(value: &InstruxConfigurationValidation) -> Self {
+        value.clone()
+    }
+}
+impl ::std::default::Default for InstruxConfigurationValidation {
+    fn default() -> Self {
+        Self { rules: Default::default() }
+    }
+}
+impl InstruxConfigurationValidation {
+    pub fn builder() -> builder::InstruxConfigurationValidation {
+        Default::default()
+    }
+}
 ///Semantic version of this config file
 ///
 /// <details><summary>JSON schema</summary>
@@ -690,6 +1561,220 @@ impl<'de> ::serde::Deserialize<'de> for InstruxConfigurationVersion {
             })
     }
 }
+///How much of each output file instrux manages: the whole file (file), or only the content between <!-- instrux:begin --> and <!-- instrux:end --> markers, inserting the block if missing and leaving the rest of a hand-written file untouched (block)
+///
+/// <details><summary>JSON schema</summary>
+///
+/// ```json
+///{
+///  "description": "How much of each output file instrux manages: the whole file (file), or only the content between <!-- instrux:begin --> and <!-- instrux:end --> markers, inserting the block if missing and leaving the rest of a hand-written file untouched (block)",
+///  "default": "file",
+///  "type": "string",
+///  "enum": [
+///    "file",
+///    "block"
+///  ]
+///}
+/// ```
+/// </details>
+#[derive(
+    ::serde::Deserialize,
+    ::serde::Serialize,
+    Clone,
+    Copy,
+    Debug,
+    Eq,
+    Hash,
+    Ord,
+    PartialEq,
+    PartialOrd
+)]
+pub enum ManagedMode {
+    #[serde(rename = "file")]
+    File,
+    #[serde(rename = "block")]
+    Block,
+}
+impl ::std::convert::From<&Self> for ManagedMode {
+    fn from(value: &ManagedMode) -> Self {
+        value.clone()
+    }
+}
+impl ::std::fmt::Display for ManagedMode {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        match *self {
+            Self::File => write!(f, "file"),
+            Self::Block => write!(f, "block"),
+        }
+    }
+}
+impl ::std::str::FromStr for ManagedMode {
+    type Err = self::error::ConversionError;
+    fn from_str(
+        value: &str,
+    ) -> ::std::result::Result<Self, self::error::ConversionError> {
+        match value {
+            "file" => Ok(Self::File),
+            "block" => Ok(Self::Block),
+            _ => Err("invalid value".into()),
+        }
+    }
+}
+impl ::std::convert::TryFrom<&str> for ManagedMode {
+    type Error = self::error::ConversionError;
+    fn try_from(
+        value: &str,
+    ) -> ::std::result::Result<Self, self::error::ConversionError> {
+        value.parse()
+    }
+}
+impl ::std::convert::TryFrom<&::std::string::String> for ManagedMode {
+    type Error = self::error::ConversionError;
+    fn try_from(
+        value: &::std::string::String,
+    ) -> ::std::result::Result<Self, self::error::ConversionError> {
+        value.parse()
+    }
+}
+impl ::std::convert::TryFrom<::std::string::String> for ManagedMode {
+    type Error = self::error::ConversionError;
+    fn try_from(
+        value: ::std::string::String,
+    ) -> ::std::result::Result<Self, self::error::ConversionError> {
+        value.parse()
+    }
+}
+impl ::std::default::Default for ManagedMode {
+    fn default() -> Self {
+        ManagedMode::File
+    }
+}
+///Severity of a validation rule
+///
+/// <details><summary>JSON schema</summary>
+///
+/// ```json
+///{
+///  "description": "Severity of a validation rule",
+///  "type": "string",
+///  "enum": [
+///    "error",
+///    "warning",
+///    "off"
+///  ]
+///}
+/// ```
+/// </details>
+#[derive(
+    ::serde::Deserialize,
+    ::serde::Serialize,
+    Clone,
+    Copy,
+    Debug,
+    Eq,
+    Hash,
+    Ord,
+    PartialEq,
+    PartialOrd
+)]
+pub enum RuleSeverity {
+    #[serde(rename = "error")]
+    Error,
+    #[serde(rename = "warning")]
+    Warning,
+    #[serde(rename = "off")]
+    Off,
+}
+impl ::std::convert::From<&Self> for RuleSeverity {
+    fn from(value: &RuleSeverity) -> Self {
+        value.clone()
+    }
+}
+impl ::std::fmt::Display for RuleSeverity {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        match *self {
+            Self::Error => write!(f, "error"),
+            Self::Warning => write!(f, "warning"),
+            Self::Off => write!(f, "off"),
+        }
+    }
+}
+impl ::std::str::FromStr for RuleSeverity {
+    type Err = self::error::ConversionError;
+    fn from_str(
+        value: &str,
+    ) -> ::std::result::Result<Self, self::error::ConversionError> {
+        match value {
+            "error" => Ok(Self::Error),
+            "warning" => Ok(Self::Warning),
+            "off" => Ok(Self::Off),
+            _ => Err("invalid value".into()),
+        }
+    }
+}
+impl ::std::convert::TryFrom<&str> for RuleSeverity {
+    type Error = self::error::ConversionError;
+    fn try_from(
+        value: &str,
+    ) -> ::std::result::Result<Self, self::error::ConversionError> {
+        value.parse()
+    }
+}
+impl ::std::convert::TryFrom<&::std::string::String> for RuleSeverity {
+    type Error = self::error::ConversionError;
+    fn try_from(
+        value: &::std::string::String,
+    ) -> ::std::result::Result<Self, self::error::ConversionError> {
+        value.parse()
+    }
+}
+impl ::std::convert::TryFrom<::std::string::String> for RuleSeverity {
+    type Error = self::error::ConversionError;
+    fn try_from(
+        value: ::std::string::String,
+    ) -> ::std::result::Result<Self, self::error::ConversionError> {
+        value.parse()
+    }
+}
+///Built-in target or the name of a custom target
+///
+/// <details><summary>JSON schema</summary>
+///
+/// ```json
+///{
+///  "description": "Built-in target or the name of a custom target",
+///  "oneOf": [
+///    {
+///      "$ref": "#/$defs/targets"
+///    },
+///    {
+///      "$ref": "#/$defs/custom_target_name"
+///    }
+///  ]
+///}
+/// ```
+/// </details>
+#[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug)]
+#[serde(untagged)]
+pub enum TargetName {
+    Targets(Targets),
+    CustomTargetName(CustomTargetName),
+}
+impl ::std::convert::From<&Self> for TargetName {
+    fn from(value: &TargetName) -> Self {
+        value.clone()
+    }
+}
+impl ::std::convert::From<Targets> for TargetName {
+    fn from(value: Targets) -> Self {
+        Self::Targets(value)
+    }
+}
+impl ::std::convert::From<CustomTargetName> for TargetName {
+    fn from(value: CustomTargetName) -> Self {
+        Self::CustomTargetName(value)
+    }
+}
 ///AI coding tool format to generate
 ///
 /// <details><summary>JSON schema</summary>
@@ -704,7 +1789,10 @@ impl<'de> ::serde::Deserialize<'de> for InstruxConfigurationVersion {
 ///    "cursor",
 ///    "junie",
 ///    "codex",
-///    "agentsmd"
+///    "agentsmd",
+///    "claude",
+///    "windsurf",
+///    "gemini"
 ///  ]
 ///}
 /// ```
@@ -734,41 +1822,175 @@ pub enum Targets {
     Codex,
     #[serde(rename = "agentsmd")]
     Agentsmd,
+    #[serde(rename = "claude")]
+    Claude,
+    #[serde(rename = "windsurf")]
+    Windsurf,
+    #[serde(rename = "gemini")]
+    Gemini,
+}
+impl ::std::convert::From<&Self> for Targets {
+    fn from(value: &Targets) -> Self {
+        value.clone()
+    }
+}
+impl ::std::fmt::Display for Targets {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        match *self {
+            Self::Copilot => write!(f, "copilot"),
+            Self::Cline => write!(f, "cline"),
+            Self::Cursor => write!(f, "cursor"),
+            Self::Junie => write!(f, "junie"),
+            Self::Codex => write!(f, "codex"),
+            Self::Agentsmd => write!(f, "agentsmd"),
+            Self::Claude => write!(f, "claude"),
+            Self::Windsurf => write!(f, "windsurf"),
+            Self::Gemini => write!(f, "gemini"),
+        }
+    }
+}
+impl ::std::str::FromStr for Targets {
+    type Err = self::error::ConversionError;
+    fn from_str(
+        value: &str,
+    ) -> ::std::result::Result<Self, self::error::ConversionError> {
+        match value {
+            "copilot" => Ok(Self::Copilot),
+            "cline" => Ok(Self::Cline),
+            "cursor" => Ok(Self::Cursor),
+            "junie" => Ok(Self::Junie),
+            "codex" => Ok(Self::Codex),
+            "agentsmd" => Ok(Self::Agentsmd),
+            "claude" => Ok(Self::Claude),
+            "windsurf" => Ok(Self::Windsurf),
+            "gemini" => Ok(Self::Gemini),
+            _ => Err("invalid value".into()),
+        }
+    }
+}
+impl ::std::convert::TryFrom<&str> for Targets {
+    type Error = self::error::ConversionError;
+    fn try_from(
+        value: &str,
+    ) -> ::std::result::Result<Self, self::error::ConversionError> {
+        value.parse()
+    }
+}
+impl ::std::convert::TryFrom<&::std::string::String> for Targets {
+    type Error = self::error::ConversionError;
+    fn try_from(
+        value: &::std::string::String,
+    ) -> ::std::result::Result<Self, self::error::ConversionError> {
+        value.parse()
+    }
+}
+impl ::std::convert::TryFrom<::std::string::String> for Targets {
+    type Error = self::error::ConversionError;
+    fn try_from(
+        value: ::std::string::String,
+    ) -> ::std::result::Result<Self, self::error::ConversionError> {
+        value.parse()
+    }
+}
+///ID of a semantic validation rule
+///
+/// <details><summary>JSON schema</summary>
+///
+/// ```json
+///{
+///  "description": "ID of a semantic validation rule",
+///  "type": "string",
+///  "enum": [
+///    "missing-body-file",
+///    "duplicate-title",
+///    "unreachable-instruction",
+///    "deprecated-target",
+///    "unknown-target",
+///    "duplicate-output-path",
+///    "duplicate-filename",
+///    "path-outside-sandbox",
+///    "duplicate-id",
+///    "unmatched-directory"
+///  ]
+///}
+/// ```
+/// </details>
+#[derive(
+    ::serde::Deserialize,
+    ::serde::Serialize,
+    Clone,
+    Copy,
+    Debug,
+    Eq,
+    Hash,
+    Ord,
+    PartialEq,
+    PartialOrd
+)]
+pub enum ValidationRule {
+    #[serde(rename = "missing-body-file")]
+    MissingBodyFile,
+    #[serde(rename = "duplicate-title")]
+    DuplicateTitle,
+    #[serde(rename = "unreachable-instruction")]
+    UnreachableInstruction,
+    #[serde(rename = "deprecated-target")]
+    DeprecatedTarget,
+    #[serde(rename = "unknown-target")]
+    UnknownTarget,
+    #[serde(rename = "duplicate-output-path")]
+    DuplicateOutputPath,
+    #[serde(rename = "duplicate-filename")]
+    DuplicateFilename,
+    #[serde(rename = "path-outside-sandbox")]
+    PathOutsideSandbox,
+    #[serde(rename = "duplicate-id")]
+    DuplicateId,
+    #[serde(rename = "unmatched-directory")]
+    UnmatchedDirectory,
 }
-impl ::std::convert::From<&Self> for Targets {
-    fn from(value: &Targets) -> Self {
+impl ::std::convert::From<&Self> for ValidationRule {
+    fn from(value: &ValidationRule) -> Self {
         value.clone()
     }
 }
-impl ::std::fmt::Display for Targets {
+impl ::std::fmt::Display for ValidationRule {
     fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
         match *self {
-            Self::Copilot => write!(f, "copilot"),
-            Self::Cline => write!(f, "cline"),
-            Self::Cursor => write!(f, "cursor"),
-            Self::Junie => write!(f, "junie"),
-            Self::Codex => write!(f, "codex"),
-            Self::Agentsmd => write!(f, "agentsmd"),
+            Self::MissingBodyFile => write!(f, "missing-body-file"),
+            Self::DuplicateTitle => write!(f, "duplicate-title"),
+            Self::UnreachableInstruction => write!(f, "unreachable-instruction"),
+            Self::DeprecatedTarget => write!(f, "deprecated-target"),
+            Self::UnknownTarget => write!(f, "unknown-target"),
+            Self::DuplicateOutputPath => write!(f, "duplicate-output-path"),
+            Self::DuplicateFilename => write!(f, "duplicate-filename"),
+            Self::PathOutsideSandbox => write!(f, "path-outside-sandbox"),
+            Self::DuplicateId => write!(f, "duplicate-id"),
+            Self::UnmatchedDirectory => write!(f, "unmatched-directory"),
         }
     }
 }
-impl ::std::str::FromStr for Targets {
+impl ::std::str::FromStr for ValidationRule {
     type Err = self::error::ConversionError;
     fn from_str(
         value: &str,
     ) -> ::std::result::Result<Self, self::error::ConversionError> {
         match value {
-            "copilot" => Ok(Self::Copilot),
-            "cline" => Ok(Self::Cline),
-            "cursor" => Ok(Self::Cursor),
-            "junie" => Ok(Self::Junie),
-            "codex" => Ok(Self::Codex),
-            "agentsmd" => Ok(Self::Agentsmd),
+            "missing-body-file" => Ok(Self::MissingBodyFile),
+            "duplicate-title" => Ok(Self::DuplicateTitle),
+            "unreachable-instruction" => Ok(Self::UnreachableInstruction),
+            "deprecated-target" => Ok(Self::DeprecatedTarget),
+            "unknown-target" => Ok(Self::UnknownTarget),
+            "duplicate-output-path" => Ok(Self::DuplicateOutputPath),
+            "duplicate-filename" => Ok(Self::DuplicateFilename),
+            "path-outside-sandbox" => Ok(Self::PathOutsideSandbox),
+            "duplicate-id" => Ok(Self::DuplicateId),
+            "unmatched-directory" => Ok(Self::UnmatchedDirectory),
             _ => Err("invalid value".into()),
         }
     }
 }
-impl ::std::convert::TryFrom<&str> for Targets {
+impl ::std::convert::TryFrom<&str> for ValidationRule {
     type Error = self::error::ConversionError;
     fn try_from(
         value: &str,
@@ -776,7 +1998,7 @@ impl ::std::convert::TryFrom<&str> for Targets {
         value.parse()
     }
 }
-impl ::std::convert::TryFrom<&::std::string::String> for Targets {
+impl ::std::convert::TryFrom<&::std::string::String> for ValidationRule {
     type Error = self::error::ConversionError;
     fn try_from(
         value: &::std::string::String,
@@ -784,7 +2006,7 @@ impl ::std::convert::TryFrom<&::std::string::String> for Targets {
         value.parse()
     }
 }
-impl ::std::convert::TryFrom<::std::string::String> for Targets {
+impl ::std::convert::TryFrom<::std::string::String> for ValidationRule {
     type Error = self::error::ConversionError;
     fn try_from(
         value: ::std::string::String,
@@ -794,8 +2016,165 @@ impl ::std::convert::TryFrom<::std::string::String> for Targets {
 }
 /// Types for composing complex structures.
 pub mod builder {
+    #[derive(Clone, Debug)]
+    pub struct CustomTarget {
+        filename: ::std::result::Result<::std::string::String, ::std::string::String>,
+        frontmatter: ::std::result::Result<
+            ::std::collections::HashMap<::std::string::String, ::std::string::String>,
+            ::std::string::String,
+        >,
+        header: ::std::result::Result<
+            ::std::option::Option<::std::string::String>,
+            ::std::string::String,
+        >,
+        index_prefix: ::std::result::Result<
+            ::std::option::Option<bool>,
+            ::std::string::String,
+        >,
+        managed: ::std::result::Result<
+            ::std::option::Option<super::ManagedMode>,
+            ::std::string::String,
+        >,
+        output_mode: ::std::result::Result<
+            super::CustomTargetOutputMode,
+            ::std::string::String,
+        >,
+        path: ::std::result::Result<::std::string::String, ::std::string::String>,
+    }
+    impl ::std::default::Default for CustomTarget {
+        fn default() -> Self {
+            Self {
+                filename: Ok(super::defaults::custom_target_filename()),
+                frontmatter: Ok(Default::default()),
+                header: Ok(Default::default()),
+                index_prefix: Ok(Default::default()),
+                managed: Ok(Default::default()),
+                output_mode: Ok(super::defaults::custom_target_output_mode()),
+                path: Err("no value supplied for path".to_string()),
+            }
+        }
+    }
+    impl CustomTarget {
+        pub fn filename<T>(mut self, value: T) -> Self
+        where
+            T: ::std::convert::TryInto<::std::string::String>,
+            T::Error: ::std::fmt::Display,
+        {
+            self.filename = value
+                .try_into()
+                .map_err(|e| {
+                    format!("error converting supplied value for filename: {}", e)
+                });
+            self
+        }
+        pub fn frontmatter<T>(mut self, value: T) -> Self
+        where
+            T: ::std::convert::TryInto<
+                ::std::collections::HashMap<::std::string::String, ::std::string::String>,
+            >,
+            T::Error: ::std::fmt::Display,
+        {
+            self.frontmatter = value
+                .try_into()
+                .map_err(|e| {
+                    format!("error converting supplied value for frontmatter: {}", e)
+                });
+            self
+        }
+        pub fn header<T>(mut self, value: T) -> Self
+        where
+            T: ::std::convert::TryInto<::std::option::Option<::std::string::String>>,
+            T::Error: ::std::fmt::Display,
+        {
+            self.header = value
+                .try_into()
+                .map_err(|e| {
+                    format!("error converting supplied value for header: {}", e)
+                });
+            self
+        }
+        pub fn index_prefix<T>(mut self, value: T) -> Self
+        where
+            T: ::std::convert::TryInto<::std::option::Option<bool>>,
+            T::Error: ::std::fmt::Display,
+        {
+            self.index_prefix = value
+                .try_into()
+                .map_err(|e| {
+                    format!("error converting supplied value for index_prefix: {}", e)
+                });
+            self
+        }
+        pub fn managed<T>(mut self, value: T) -> Self
+        where
+            T: ::std::convert::TryInto<::std::option::Option<super::ManagedMode>>,
+            T::Error: ::std::fmt::Display,
+        {
+            self.managed = value
+                .try_into()
+                .map_err(|e| {
+                    format!("error converting supplied value for managed: {}", e)
+                });
+            self
+        }
+        pub fn output_mode<T>(mut self, value: T) -> Self
+        where
+            T: ::std::convert::TryInto<super::CustomTargetOutputMode>,
+            T::Error: ::std::fmt::Display,
+        {
+            self.output_mode = value
+                .try_into()
+                .map_err(|e| {
+                    format!("error converting supplied value for output_mode: {}", e)
+                });
+            self
+        }
+        pub fn path<T>(mut self, value: T) -> Self
+        where
+            T: ::std::convert::TryInto<::std::string::String>,
+            T::Error: ::std::fmt::Display,
+        {
+            self.path = value
+                .try_into()
+                .map_err(|e| format!("error converting supplied value for path: {}", e));
+            self
+        }
+    }
+    impl ::std::convert::TryFrom<CustomTarget> for super::CustomTarget {
+        type Error = super::error::ConversionError;
+        fn try_from(
+            value: CustomTarget,
+        ) -> ::std::result::Result<Self, super::error::ConversionError> {
+            Ok(Self {
+                filename: value.filename?,
+                frontmatter: value.frontmatter?,
+                header: value.header?,
+                index_prefix: value.index_prefix?,
+                managed: value.managed?,
+                output_mode: value.output_mode?,
+                path: value.path?,
+            })
+        }
+    }
+    impl ::std::convert::From<super::CustomTarget> for CustomTarget {
+        fn from(value: super::CustomTarget) -> Self {
+            Self {
+                filename: Ok(value.filename),
+                frontmatter: Ok(value.frontmatter),
+                header: Ok(value.header),
+                index_prefix: Ok(value.index_prefix),
+                managed: Ok(value.managed),
+                output_mode: Ok(value.output_mode),
+                path: Ok(value.path),
+            }
+        }
+    }
     #[derive(Clone, Debug)]
     pub struct InstruxConfiguration {
+        custom: ::std::result::Result<
+            ::std::collections::HashMap<super::CustomTargetName, super::CustomTarget>,
+            ::std::string::String,
+        >,
         instructions: ::std::result::Result<
             ::std::vec::Vec<super::InstructionItem>,
             ::std::string::String,
@@ -804,6 +2183,10 @@ pub mod builder {
             super::InstruxConfigurationLanguage,
             ::std::string::String,
         >,
+        sandbox: ::std::result::Result<
+            ::std::option::Option<super::InstruxConfigurationSandbox>,
+            ::std::string::String,
+        >,
         targets: ::std::result::Result<
             ::std::collections::HashMap<
                 super::Targets,
@@ -811,6 +2194,10 @@ pub mod builder {
             >,
             ::std::string::String,
         >,
+        validation: ::std::result::Result<
+            ::std::option::Option<super::InstruxConfigurationValidation>,
+            ::std::string::String,
+        >,
         version: ::std::result::Result<
             super::InstruxConfigurationVersion,
             ::std::string::String,
@@ -819,14 +2206,31 @@ pub mod builder {
     impl ::std::default::Default for InstruxConfiguration {
         fn default() -> Self {
             Self {
+                custom: Ok(Default::default()),
                 instructions: Ok(Default::default()),
                 language: Ok(super::defaults::instrux_configuration_language()),
+                sandbox: Ok(Default::default()),
                 targets: Err("no value supplied for targets".to_string()),
+                validation: Ok(Default::default()),
                 version: Err("no value supplied for version".to_string()),
             }
         }
     }
     impl InstruxConfiguration {
+        pub fn custom<T>(mut self, value: T) -> Self
+        where
+            T: ::std::convert::TryInto<
+                ::std::collections::HashMap<super::CustomTargetName, super::CustomTarget>,
+            >,
+            T::Error: ::std::fmt::Display,
+        {
+            self.custom = value
+                .try_into()
+                .map_err(|e| {
+                    format!("error converting supplied value for custom: {}", e)
+                });
+            self
+        }
         pub fn instructions<T>(mut self, value: T) -> Self
         where
             T: ::std::convert::TryInto<::std::vec::Vec<super::InstructionItem>>,
@@ -851,6 +2255,20 @@ pub mod builder {
                 });
             self
         }
+        pub fn sandbox<T>(mut self, value: T) -> Self
+        where
+            T: ::std::convert::TryInto<
+                ::std::option::Option<super::InstruxConfigurationSandbox>,
+            >,
+            T::Error: ::std::fmt::Display,
+        {
+            self.sandbox = value
+                .try_into()
+                .map_err(|e| {
+                    format!("error converting supplied value for sandbox: {}", e)
+                });
+            self
+        }
         pub fn targets<T>(mut self, value: T) -> Self
         where
             T: ::std::convert::TryInto<
@@ -868,6 +2286,20 @@ pub mod builder {
                 });
             self
         }
+        pub fn validation<T>(mut self, value: T) -> Self
+        where
+            T: ::std::convert::TryInto<
+                ::std::option::Option<super::InstruxConfigurationValidation>,
+            >,
+            T::Error: ::std::fmt::Display,
+        {
+            self.validation = value
+                .try_into()
+                .map_err(|e| {
+                    format!("error converting supplied value for validation: {}", e)
+                });
+            self
+        }
         pub fn version<T>(mut self, value: T) -> Self
         where
             T: ::std::convert::TryInto<super::InstruxConfigurationVersion>,
@@ -887,9 +2319,12 @@ pub mod builder {
             value: InstruxConfiguration,
         ) -> ::std::result::Result<Self, super::error::ConversionError> {
             Ok(Self {
+                custom: value.custom?,
                 instructions: value.instructions?,
                 language: value.language?,
+                sandbox: value.sandbox?,
                 targets: value.targets?,
+                validation: value.validation?,
                 version: value.version?,
             })
         }
@@ -897,30 +2332,149 @@ pub mod builder {
     impl ::std::convert::From<super::InstruxConfiguration> for InstruxConfiguration {
         fn from(value: super::InstruxConfiguration) -> Self {
             Self {
+                custom: Ok(value.custom),
                 instructions: Ok(value.instructions),
                 language: Ok(value.language),
+                sandbox: Ok(value.sandbox),
                 targets: Ok(value.targets),
+                validation: Ok(value.validation),
                 version: Ok(value.version),
             }
         }
     }
     #[derive(Clone, Debug)]
+    pub struct InstruxConfigurationSandbox {
+        allow: ::std::result::Result<
+            ::std::vec::Vec<::std::string::String>,
+            ::std::string::String,
+        >,
+    }
+    impl ::std::default::Default for InstruxConfigurationSandbox {
+        fn default() -> Self {
+            Self {
+                allow: Ok(Default::default()),
+            }
+        }
+    }
+    impl InstruxConfigurationSandbox {
+        pub fn allow<T>(mut self, value: T) -> Self
+        where
+            T: ::std::convert::TryInto<::std::vec::Vec<::std::string::String>>,
+            T::Error: ::std::fmt::Display,
+        {
+            self.allow = value
+                .try_into()
+                .map_err(|e| {
+                    format!("error converting supplied value for allow: {}", e)
+                });
+            self
+        }
+    }
+    impl ::std::convert::TryFrom<InstruxConfigurationSandbox>
+    for super::InstruxConfigurationSandbox {
+        type Error = super::error::ConversionError;
+        fn try_from(
+            value: InstruxConfigurationSandbox,
+        ) -> ::std::result::Result<Self, super::error::ConversionError> {
+            Ok(Self { allow: value.allow? })
+        }
+    }
+    impl ::std::convert::From<super::InstruxConfigurationSandbox>
+    for InstruxConfigurationSandbox {
+        fn from(value: super::InstruxConfigurationSandbox) -> Self {
+            Self { allow: Ok(value.allow) }
+        }
+    }
+    #[derive(Clone, Debug)]
     pub struct InstruxConfigurationTargetsValue {
+        filenames: ::std::result::Result<
+            ::std::option::Option<super::FilenameStyle>,
+            ::std::string::String,
+        >,
+        imports: ::std::result::Result<
+            ::std::option::Option<bool>,
+            ::std::string::String,
+        >,
+        index_prefix: ::std::result::Result<
+            ::std::option::Option<bool>,
+            ::std::string::String,
+        >,
+        managed: ::std::result::Result<
+            ::std::option::Option<super::ManagedMode>,
+            ::std::string::String,
+        >,
         output_mode: ::std::result::Result<
             super::InstruxConfigurationTargetsValueOutputMode,
             ::std::string::String,
         >,
+        path: ::std::result::Result<
+            ::std::option::Option<::std::string::String>,
+            ::std::string::String,
+        >,
     }
     impl ::std::default::Default for InstruxConfigurationTargetsValue {
         fn default() -> Self {
             Self {
+                filenames: Ok(Default::default()),
+                imports: Ok(Default::default()),
+                index_prefix: Ok(Default::default()),
+                managed: Ok(Default::default()),
                 output_mode: Ok(
                     super::defaults::instrux_configuration_targets_value_output_mode(),
                 ),
+                path: Ok(Default::default()),
             }
         }
     }
     impl InstruxConfigurationTargetsValue {
+        pub fn filenames<T>(mut self, value: T) -> Self
+        where
+            T: ::std::convert::TryInto<::std::option::Option<super::FilenameStyle>>,
+            T::Error: ::std::fmt::Display,
+        {
+            self.filenames = value
+                .try_into()
+                .map_err(|e| {
+                    format!("error converting supplied value for filenames: {}", e)
+                });
+            self
+        }
+        pub fn imports<T>(mut self, value: T) -> Self
+        where
+            T: ::std::convert::TryInto<::std::option::Option<bool>>,
+            T::Error: ::std::fmt::Display,
+        {
+            self.imports = value
+                .try_into()
+                .map_err(|e| {
+                    format!("error converting supplied value for imports: {}", e)
+                });
+            self
+        }
+        pub fn index_prefix<T>(mut self, value: T) -> Self
+        where
+            T: ::std::convert::TryInto<::std::option::Option<bool>>,
+            T::Error: ::std::fmt::Display,
+        {
+            self.index_prefix = value
+                .try_into()
+                .map_err(|e| {
+                    format!("error converting supplied value for index_prefix: {}", e)
+                });
+            self
+        }
+        pub fn managed<T>(mut self, value: T) -> Self
+        where
+            T: ::std::convert::TryInto<::std::option::Option<super::ManagedMode>>,
+            T::Error: ::std::fmt::Display,
+        {
+            self.managed = value
+                .try_into()
+                .map_err(|e| {
+                    format!("error converting supplied value for managed: {}", e)
+                });
+            self
+        }
         pub fn output_mode<T>(mut self, value: T) -> Self
         where
             T: ::std::convert::TryInto<
@@ -935,6 +2489,16 @@ pub mod builder {
                 });
             self
         }
+        pub fn path<T>(mut self, value: T) -> Self
+        where
+            T: ::std::convert::TryInto<::std::option::Option<::std::string::String>>,
+            T::Error: ::std::fmt::Display,
+        {
+            self.path = value
+                .try_into()
+                .map_err(|e| format!("error converting supplied value for path: {}", e));
+            self
+        }
     }
     impl ::std::convert::TryFrom<InstruxConfigurationTargetsValue>
     for super::InstruxConfigurationTargetsValue {
@@ -943,7 +2507,12 @@ pub mod builder {
             value: InstruxConfigurationTargetsValue,
         ) -> ::std::result::Result<Self, super::error::ConversionError> {
             Ok(Self {
+                filenames: value.filenames?,
+                imports: value.imports?,
+                index_prefix: value.index_prefix?,
+                managed: value.managed?,
                 output_mode: value.output_mode?,
+                path: value.path?,
             })
         }
     }
@@ -951,13 +2520,69 @@ pub mod builder {
     for InstruxConfigurationTargetsValue {
         fn from(value: super::InstruxConfigurationTargetsValue) -> Self {
             Self {
+                filenames: Ok(value.filenames),
+                imports: Ok(value.imports),
+                index_prefix: Ok(value.index_prefix),
+                managed: Ok(value.managed),
                 output_mode: Ok(value.output_mode),
+                path: Ok(value.path),
+            }
+        }
+    }
+    #[derive(Clone, Debug)]
+    pub struct InstruxConfigurationValidation {
+        rules: ::std::result::Result<
+            ::std::collections::HashMap<super::ValidationRule, super::RuleSeverity>,
+            ::std::string::String,
+        >,
+    }
+    impl ::std::default::Default for InstruxConfigurationValidation {
+        fn default() -> Self {
+            Self {
+                rules: Ok(Default::default()),
             }
         }
     }
+    impl InstruxConfigurationValidation {
+        pub fn rules<T>(mut self, value: T) -> Self
+        where
+            T: ::std::convert::TryInto<
+                ::std::collections::HashMap<super::ValidationRule, super::RuleSeverity>,
+            >,
+            T::Error: ::std::fmt::Display,
+        {
+            self.rules = value
+                .try_into()
+                .map_err(|e| {
+                    format!("error converting supplied value for rules: {}", e)
+                });
+            self
+        }
+    }
+    impl ::std::convert::TryFrom<InstruxConfigurationValidation>
+    for super::InstruxConfigurationValidation {
+        type Error = super::error::ConversionError;
+        fn try_from(
+            value: InstruxConfigurationValidation,
+        ) -> ::std::result::Result<Self, super::error::ConversionError> {
+            Ok(Self { rules: value.rules? })
+        }
+    }
+    impl ::std::convert::From<super::InstruxConfigurationValidation>
+    for InstruxConfigurationValidation {
+        fn from(value: super::InstruxConfigurationValidation) -> Self {
+            Self { rules: Ok(value.rules) }
+        }
+    }
 }
 /// Generation of default values for serde.
 pub mod defaults {
+    pub(super) fn custom_target_filename() -> ::std::string::String {
+        "{{slug}}.md".to_string()
+    }
+    pub(super) fn custom_target_output_mode() -> super::CustomTargetOutputMode {
+        super::CustomTargetOutputMode::Single
+    }
     pub(super) fn instruction_item_variant0_targets() -> super::InstructionItemVariant0Targets {
         super::InstructionItemVariant0Targets::Variant1("all".to_string())
     }
@@ -1,44 +1,87 @@
 use crate::diff;
+use crate::error::{InstruxError, Result};
+use crate::formats::{self, ToFormat};
+use crate::manifest::{DEFAULT_MANIFEST_PATH, Manifest};
+use crate::model::types::{CustomTargetName, TargetName};
 use crate::model::{parser, types::Targets};
+use crate::sandbox::Sandbox;
 use std::path::Path;
 
 /// diffコマンドの基本処理
-pub fn run(tool: Option<String>) {
+pub fn run(tool: Option<String>) -> Result<()> {
     let config_path = Path::new(".instrux/instrux.yaml");
-    let config = match parser::parse_instrux_yaml(config_path) {
-        Ok(c) => c,
-        Err(e) => {
-            eprintln!("[diff] 設定ファイルの読み込みに失敗: {}", e);
-            return;
-        }
-    };
+    let config = parser::parse_instrux_yaml(config_path)?;
+    Sandbox::current(&config).check_body_files(&config.instructions)?;
 
-    let targets: Vec<Targets> = if let Some(name) = tool {
-        match name.to_lowercase().as_str() {
-            "copilot" => vec![Targets::Copilot],
-            "cline" => vec![Targets::Cline],
-            "cursor" => vec![Targets::Cursor],
-            "junie" => vec![Targets::Junie],
-            "codex" => vec![Targets::Codex],
-            other => {
-                eprintln!("[diff] 未知のツール形式: {}", other);
-                return;
-            }
+    let all_targets = tool.is_none();
+    let targets: Vec<(TargetName, Box<dyn ToFormat>)> = if let Some(name) = tool {
+        let builtin = match name.to_lowercase().as_str() {
+            "copilot" => Some(Targets::Copilot),
+            "cline" => Some(Targets::Cline),
+            "cursor" => Some(Targets::Cursor),
+            "junie" => Some(Targets::Junie),
+            "agentsmd" => Some(Targets::Agentsmd),
+            "claude" => Some(Targets::Claude),
+            "windsurf" => Some(Targets::Windsurf),
+            "gemini" => Some(Targets::Gemini),
+            "codex" => return Err(InstruxError::DeprecatedTarget(Targets::Codex)),
+            _ => None,
+        };
+        if let Some(target) = builtin {
+            let default_config = Default::default();
+            let target_config = config.targets.get(&target).unwrap_or(&default_config);
+            vec![(
+                TargetName::Targets(target),
+                formats::get_converter(&target, target_config),
+            )]
+        } else {
+            // 組み込みでなければcustomのターゲット名として探す
+            let custom = CustomTargetName::try_from(name.as_str())
+                .ok()
+                .and_then(|name| config.custom.get_key_value(&name));
+            let Some((name, target)) = custom else {
+                return Err(InstruxError::UnknownTool(name.to_lowercase()));
+            };
+            vec![(
+                TargetName::CustomTargetName(name.clone()),
+                formats::get_custom_converter(name, target),
+            )]
         }
     } else {
-        config.targets.keys().cloned().collect()
+        formats::converters(&config)
     };
 
-    for target in targets {
-        let default_config = Default::default();
-        let target_config = config.targets.get(&target).unwrap_or(&default_config);
-        match diff::diff_from_config(&config, target, target_config) {
-            Ok(d) => {
-                println!("--- {:?} ---\n{}", target, d);
-            }
-            Err(e) => {
-                eprintln!("[diff] {}", e);
-            }
+    for (target, converter) in &targets {
+        let d = diff::diff_from_config(&config, target, converter.as_ref())?;
+        println!("--- {} ---\n{}", heading(target), d);
+    }
+
+    // 設定から外れたターゲットの生成済みファイルは削除対象として表示
+    if all_targets {
+        let mut removed: Vec<TargetName> = Manifest::load(DEFAULT_MANIFEST_PATH)
+            .unwrap_or_default()
+            .files
+            .values()
+            .map(|entry| entry.target.clone())
+            .filter(|target| targets.iter().all(|(enabled, _)| enabled != target))
+            .collect();
+        removed.sort();
+        removed.dedup();
+        for target in removed {
+            println!(
+                "--- {} ---\n{}",
+                heading(&target),
+                diff::orphan_diff(Some(&target), &[])
+            );
         }
     }
+    Ok(())
+}
+
+/// 差分の見出しに表示するターゲット名
+fn heading(target: &TargetName) -> String {
+    match target {
+        TargetName::Targets(target) => format!("{:?}", target),
+        TargetName::CustomTargetName(name) => name.to_string(),
+    }
 }
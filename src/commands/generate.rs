@@ -1,14 +1,58 @@
+use crate::diff::{self, StaleKind};
+use crate::error::{InstruxError, Result};
 use crate::formats;
-use crate::model::parser::parse_instrux_yaml;
-use crate::model::types::Targets;
+use crate::manifest::{self, DEFAULT_MANIFEST_PATH, FileStatus, Manifest};
+use crate::model::parser::{parse_instrux_yaml, parse_instrux_yaml_str, read_instrux_yaml};
+use crate::model::types::TargetName;
+use crate::sandbox::Sandbox;
+use crate::transaction::Transaction;
 use std::fs;
-use std::path::Path;
+use std::io;
+use std::path::{Path, PathBuf};
 use std::sync::mpsc::channel;
 use std::time::Duration;
 
 use notify::{Config, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
 
 const DEFAULT_INSTRUX_CONFIG_PATH: &str = ".instrux/instrux.yaml";
+/// 書き込む内容を一時的に置くディレクトリ (generateの終了時に削除する)
+const STAGING_DIR: &str = ".instrux/tmp";
+
+/// 1回の生成処理で共有する状態
+struct GenerateState {
+    /// 生成ファイルの記録
+    manifest: Manifest,
+    /// 生成元のinstrux.yamlのハッシュ
+    config_hash: String,
+    /// 手動で変更されていたため上書きしなかったファイル
+    refused: Vec<PathBuf>,
+    /// 今回の生成で出力対象となったファイル
+    produced: Vec<PathBuf>,
+    /// 書き込み・削除をまとめて適用するトランザクション
+    tx: Transaction,
+    /// トランザクションの適用後に表示するメッセージ (ファイルごとの処理結果)
+    messages: Vec<String>,
+}
+
+impl GenerateState {
+    /// ファイルごとの処理結果を記録する (書き込みが完了してから表示する)
+    fn report(&mut self, message: String) {
+        self.messages.push(message);
+    }
+
+    /// 出力したファイルをマニフェストに記録する
+    ///
+    /// `managed` は管理ブロックだけを出力したファイル (`managed: block`) か
+    fn record(&mut self, target: &TargetName, path: &Path, content: &str, managed: bool) {
+        if managed {
+            self.manifest
+                .record_block(path, target.clone(), &self.config_hash, content);
+        } else {
+            self.manifest
+                .record(path, target.clone(), &self.config_hash, content);
+        }
+    }
+}
 
 /// generateコマンドの基本処理
 ///
@@ -17,7 +61,7 @@ const DEFAULT_INSTRUX_CONFIG_PATH: &str = ".instrux/instrux.yaml";
 /// * `overwrite` - 既存ファイルを上書きし、.bakバックアップを作成
 /// * `force` - バックアップを作成せず強制上書き
 /// * `watch` - ファイル変更を監視して自動生成
-pub fn run(dry_run: bool, overwrite: bool, force: bool, watch: bool) -> Result<(), String> {
+pub fn run(dry_run: bool, overwrite: bool, force: bool, watch: bool) -> Result<()> {
     println!(
         "[generate] dry_run: {}, overwrite: {}, force: {}, watch: {}",
         dry_run, overwrite, force, watch
@@ -44,13 +88,13 @@ pub fn run(dry_run: bool, overwrite: bool, force: bool, watch: bool) -> Result<(
             // 変更イベントを待つ
             match rx.recv_timeout(Duration::from_secs(1)) {
                 Ok(event) => {
-                    if let Ok(ev) = event {
-                        if matches!(ev.kind, EventKind::Modify(_)) {
-                            println!("[generate] 構成ファイルが変更されました。再生成します...");
-                            if let Err(e) = generate_once(dry_run, overwrite, force) {
-                                eprintln!("[generate] エラー: {}", e);
-                                // watchモードなのでエラーでも継続するが、エラーがあったことは通知
-                            }
+                    if let Ok(ev) = event
+                        && matches!(ev.kind, EventKind::Modify(_))
+                    {
+                        println!("[generate] 構成ファイルが変更されました。再生成します...");
+                        if let Err(e) = generate_once(dry_run, overwrite, force) {
+                            eprintln!("[generate] エラー: {}", e);
+                            // watchモードなのでエラーでも継続するが、エラーがあったことは通知
                         }
                     }
                 }
@@ -66,111 +110,296 @@ pub fn run(dry_run: bool, overwrite: bool, force: bool, watch: bool) -> Result<(
     // Ok(()) // watchモード以外はgenerate_onceの結果をそのまま返す
 }
 
+/// generate --check の処理
+///
+/// ファイルを生成せずに全ターゲットの生成結果をディスク上のファイルと比較し、
+/// 不一致 (未生成・内容の差分・削除されていない孤立ファイル) があればエラーを返す。
+pub fn check() -> Result<()> {
+    let config = parse_instrux_yaml(DEFAULT_INSTRUX_CONFIG_PATH)?;
+    Sandbox::current(&config).check_body_files(&config.instructions)?;
+
+    let stale = diff::stale_files(&config)?;
+    if stale.is_empty() {
+        println!("[generate] 生成結果はすべて最新です");
+        return Ok(());
+    }
+    for file in &stale {
+        let kind = match file.kind {
+            StaleKind::Missing => "未生成",
+            StaleKind::Changed => "差分あり",
+            StaleKind::Orphaned => "削除対象",
+        };
+        println!("  {}: {} ({})", kind, file.path.display(), file.target);
+    }
+    Err(InstruxError::Stale(stale.len()))
+}
+
 /// 1回だけファイル生成処理を行う関数
-fn generate_once(dry_run: bool, overwrite: bool, force: bool) -> Result<(), String> {
+fn generate_once(dry_run: bool, overwrite: bool, force: bool) -> Result<()> {
     // instrux.yamlから内部モデルを読み込む
-    let config_path = DEFAULT_INSTRUX_CONFIG_PATH;
-    let config = parse_instrux_yaml(config_path)
-        .map_err(|e| format!("[generate] 設定ファイルの読み込みに失敗: {}", e))?;
+    let config_path = Path::new(DEFAULT_INSTRUX_CONFIG_PATH);
+    let config_content = read_instrux_yaml(config_path)?;
+    let config = parse_instrux_yaml_str(&config_content, config_path)?;
 
     // dry-run: diffコマンドのロジックを呼び出して終了
     if dry_run {
-        crate::commands::diff::run(None); // diff::runもResultを返すように変更が必要かも
-        return Ok(());
+        return crate::commands::diff::run(None);
     }
 
-    // 各ターゲットごとにファイル生成
-    // 各ターゲットごとにkey, valueをget_converterに渡してファイル生成
-    for (target, value) in &config.targets {
-        // converterを取得（key, value両方を渡す）
-        let converter = formats::get_converter(target, value);
-        let format_result = converter
-            .to_format(&config)
-            .map_err(|e| format!("[generate] {}形式への変換に失敗: {}", target, e))?;
+    // body_fileは .instrux/instructions/ 配下、出力先はリポジトリのルート配下に限る
+    let sandbox = Sandbox::current(&config);
+    sandbox.check_body_files(&config.instructions)?;
+
+    // 全ターゲット (組み込み・custom) を先に変換し、変換に失敗した場合は何も書き込まない
+    let mut rendered = Vec::new();
+    for target in config.targets.keys().filter(|t| formats::is_deprecated(t)) {
+        eprintln!(
+            "[generate] 警告: {} は非推奨のため出力しません。agentsmd (AGENTS.md) を使用してください",
+            target
+        );
+    }
+    for (target, converter) in formats::converters(&config) {
+        let format_result = converter.to_format(&config)?;
+        format_result.check_paths(&target)?;
+        rendered.push((target, converter, format_result));
+    }
+
+    let mut state = GenerateState {
+        manifest: Manifest::load(DEFAULT_MANIFEST_PATH)?,
+        config_hash: manifest::hash_content(&config_content),
+        refused: Vec::new(),
+        produced: Vec::new(),
+        tx: Transaction::new(format!("{}-{}", STAGING_DIR, std::process::id())),
+        messages: Vec::new(),
+    };
+
+    // 各ターゲットの書き込みをトランザクションに登録する
+    for (target, converter, format_result) in rendered {
+        let target = &target;
 
         // FormatResultに応じて処理を分岐
         match format_result {
             formats::FormatResult::Single(output) => {
                 // 単一ファイルの場合
                 let out_path = converter.get_default_path();
-                process_single_file(target, &out_path, &output, overwrite, force)?;
+                sandbox.check_output(&out_path)?;
+                warn_if_too_long(target, converter.max_chars(), &out_path, &output);
+                process_single_file(
+                    target,
+                    &out_path,
+                    &output,
+                    converter.is_block_managed(),
+                    overwrite,
+                    force,
+                    &mut state,
+                )?;
             }
             formats::FormatResult::Multiple(files) => {
                 // 複数ファイルの場合
                 let base_path = converter.get_default_path();
-
-                // force/overwrite指定時、既存のbase_pathがファイルなら削除してからディレクトリ作成
-                if (force || overwrite) && base_path.exists() && base_path.is_file() {
-                    if overwrite {
-                        match backup_and_remove_file(&base_path) {
-                            Ok(bak_path) => {
-                                println!(
-                                    "[generate] {} をバックアップしました",
-                                    bak_path.display()
-                                );
-                            }
-                            Err(msg) => {
-                                eprintln!("[generate] {}", msg);
-                                return Err(msg);
-                            }
-                        }
-                    } else {
-                        match std::fs::remove_file(&base_path) {
-                            Ok(_) => {
-                                println!(
-                                    "[generate] {} (ファイル) を削除しました",
-                                    base_path.display()
-                                );
-                            }
-                            Err(e) => {
-                                let msg = format!(
-                                    "[generate] 既存ファイルの削除に失敗: {}: {}",
-                                    base_path.display(),
-                                    e
-                                );
-                                eprintln!("[generate] {}", msg);
-                                return Err(msg);
-                            }
-                        }
-                    }
+                sandbox.check_output(&base_path)?;
+                for (file_path, _) in &files {
+                    sandbox.check_output(Path::new(file_path))?;
                 }
 
-                // ベースディレクトリの作成 (存在しない場合)
-                if !base_path.exists() {
-                    std::fs::create_dir_all(&base_path).map_err(|e| {
-                        format!(
-                            "[generate] ディレクトリ作成に失敗: {}: {}",
-                            base_path.display(),
-                            e
-                        )
-                    })?;
+                // base_pathも出力するファイルの1つである場合 (ルートのAGENTS.mdなど) は
+                // ディレクトリとして扱わない
+                let base_is_output = files
+                    .iter()
+                    .any(|(path, _)| Path::new(path) == base_path.as_path());
+                if !base_is_output {
+                    prepare_base_dir(&base_path, overwrite, force, &mut state)?;
                 }
 
-                // 各ファイルに書き込み
+                // 各ファイルに書き込み (親ディレクトリはコミット時に作成する)
                 for (file_path, content) in files {
                     let file_path = Path::new(&file_path);
-                    // 親ディレクトリの作成 (存在しない場合)
-                    if let Some(parent) = file_path.parent() {
-                        if !parent.exists() {
-                            std::fs::create_dir_all(parent).map_err(|e| {
-                                format!(
-                                    "[generate] ディレクトリ作成に失敗: {}: {}",
-                                    parent.display(),
-                                    e
-                                )
-                            })?;
-                        }
-                    }
-                    process_single_file(target, file_path, &content, overwrite, force)?;
+                    warn_if_too_long(target, converter.max_chars(), file_path, &content);
+                    process_single_file(
+                        target,
+                        file_path,
+                        &content,
+                        converter.is_block_managed(),
+                        overwrite,
+                        force,
+                        &mut state,
+                    )?;
                 }
             }
         }
     }
+
+    // 前回生成したが今回は出力しないファイルを削除
+    let mut removed = Vec::new();
+    for (orphan, _) in state.manifest.orphans(&state.produced) {
+        // マニフェストが書き換えられていても範囲外のファイルは削除しない
+        if !sandbox.allows_output(&orphan) {
+            eprintln!(
+                "[generate] {} はリポジトリのルートの外にあるため削除しません",
+                orphan.display()
+            );
+            state.manifest.remove(&orphan);
+            continue;
+        }
+        if remove_orphan(&orphan, overwrite, force, &mut state)? {
+            removed.push(orphan);
+        }
+    }
+
+    let manifest_json = state.manifest.to_json();
+    state
+        .tx
+        .write(Path::new(DEFAULT_MANIFEST_PATH), &manifest_json)?;
+
+    // すべての書き込みを適用する (途中で失敗した場合は生成前の状態に戻す)
+    let paths = state.tx.paths();
+    if let Err(e) = state.tx.commit() {
+        eprintln!("[generate] 書き込みに失敗したため、すべての変更を元に戻しました");
+        for path in paths {
+            eprintln!("  元に戻したファイル: {}", path.display());
+        }
+        return Err(e);
+    }
+    for message in &state.messages {
+        println!("{}", message);
+    }
+
+    // 空になったディレクトリも削除する (空でなければ失敗するので無視)
+    for path in removed {
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            let _ = fs::remove_dir(parent);
+        }
+    }
+
+    if !state.refused.is_empty() {
+        return Err(InstruxError::ModifiedFiles(state.refused));
+    }
     Ok(())
 }
 
-/// 指定ファイルを .bak でバックアップし、元ファイルを削除する
-fn backup_and_remove_file(path: &Path) -> Result<std::path::PathBuf, String> {
+/// 複数ファイル形式の出力先ディレクトリを準備する
+///
+/// 単一ファイル形式で生成済み・未変更のファイルがあれば、出力モードの切り替えとして削除する。
+fn prepare_base_dir(
+    base_path: &Path,
+    overwrite: bool,
+    force: bool,
+    state: &mut GenerateState,
+) -> Result<()> {
+    // 単一ファイル形式で生成済み・未変更のbase_pathは、出力モードの切り替えとして削除する
+    if base_path.is_file() {
+        let existing_content = fs::read_to_string(base_path)
+            .map_err(|e| InstruxError::io("既存ファイルの読み込み", base_path, e))?;
+        if state.manifest.status(base_path, &existing_content) == FileStatus::Unmodified {
+            state.tx.remove(base_path);
+            state.manifest.remove(base_path);
+            state.report(format!(
+                "[generate] {} (ファイル) を削除しました",
+                base_path.display()
+            ));
+        } else if overwrite {
+            // overwrite指定時、既存のbase_pathはバックアップしてからディレクトリ作成
+            let bak_path = backup_and_remove_file(base_path, state)?;
+            state.report(format!(
+                "[generate] {} をバックアップしました",
+                bak_path.display()
+            ));
+        } else if force {
+            // force指定時、既存のbase_pathは削除してからディレクトリ作成
+            state.tx.remove(base_path);
+            state.report(format!(
+                "[generate] {} (ファイル) を削除しました",
+                base_path.display()
+            ));
+        }
+    }
+
+    // ベースディレクトリの作成 (存在しない場合)
+    if !base_path.is_dir() {
+        state.tx.create_dir_all(base_path);
+    }
+    Ok(())
+}
+
+/// ツールが読み込む最大文字数を超えるファイルを警告する (出力は行う)
+fn warn_if_too_long(target: &TargetName, max_chars: Option<usize>, path: &Path, content: &str) {
+    let chars = content.chars().count();
+    if let Some(max_chars) = max_chars
+        && chars > max_chars
+    {
+        eprintln!(
+            "[generate] 警告: {} は{}文字で、{}の上限 ({}文字) を超えています。超えた部分は読み込まれない可能性があります",
+            path.display(),
+            chars,
+            target,
+            max_chars
+        );
+    }
+}
+
+/// 前回生成したが今回は出力されないファイル (孤立ファイル) を削除する
+///
+/// 生成後に手動で変更されたファイルは --overwrite (バックアップして削除) / --force 指定時のみ削除する。
+/// ファイルごと削除した場合は `true` を返す。
+fn remove_orphan(
+    path: &Path,
+    overwrite: bool,
+    force: bool,
+    state: &mut GenerateState,
+) -> Result<bool> {
+    if !path.is_file() {
+        state.manifest.remove(path);
+        return Ok(false);
+    }
+    let managed = state
+        .manifest
+        .entry(path)
+        .is_some_and(|entry| entry.managed);
+    let existing_content = fs::read_to_string(path)
+        .map_err(|e| InstruxError::io("既存ファイルの読み込み", path, e))?;
+    if state.manifest.status(path, &existing_content) == FileStatus::Modified
+        && !overwrite
+        && !force
+    {
+        eprintln!(
+            "[generate] {} は出力されなくなりましたが、生成後に手動で変更されています (--overwrite でバックアップ削除, --force で強制削除)",
+            path.display()
+        );
+        state.refused.push(path.to_path_buf());
+        return Ok(false);
+    }
+    // 管理ブロックだけを出力したファイルは、手書きの部分を残してブロックのみ削除する
+    let rest = formats::remove_block(&existing_content);
+    if managed && !rest.trim().is_empty() {
+        state.tx.write(path, &rest)?;
+        state.report(format!(
+            "[generate] {} は出力されなくなったため管理ブロックを削除しました",
+            path.display()
+        ));
+        state.manifest.remove(path);
+        return Ok(false);
+    }
+    if overwrite {
+        let bak_path = backup_and_remove_file(path, state)?;
+        state.report(format!(
+            "[generate] {} をバックアップしました",
+            bak_path.display()
+        ));
+    } else {
+        state.tx.remove(path);
+    }
+    state.report(format!(
+        "[generate] {} は出力されなくなったため削除しました",
+        path.display()
+    ));
+    state.manifest.remove(path);
+    Ok(true)
+}
+
+/// 指定ファイル (またはディレクトリ) を .bak に移動してバックアップする
+fn backup_and_remove_file(path: &Path, state: &mut GenerateState) -> Result<PathBuf> {
     let bak_path = {
         let mut bak = path.to_path_buf();
         let bak_os = bak
@@ -180,71 +409,92 @@ fn backup_and_remove_file(path: &Path) -> Result<std::path::PathBuf, String> {
                 s.push(".bak");
                 s
             })
-            .ok_or("ファイル名の取得に失敗")?;
+            .ok_or_else(|| {
+                InstruxError::io(
+                    "バックアップ作成",
+                    path,
+                    io::Error::new(io::ErrorKind::InvalidInput, "ファイル名の取得に失敗"),
+                )
+            })?;
         bak.set_file_name(bak_os);
         bak
     };
-    std::fs::copy(path, &bak_path).map_err(|e| format!("バックアップ作成に失敗: {}", e))?;
-    std::fs::remove_file(path).map_err(|e| format!("既存ファイルの削除に失敗: {}", e))?;
+    state.tx.rename(path, &bak_path);
     Ok(bak_path)
 }
 
 /// 単一ファイルの出力処理
+///
+/// マニフェストに記録された内容から変更されていないファイルは、instruxが生成したものとして
+/// そのまま更新する。生成後に手動で変更されたファイルは --overwrite / --force 指定時のみ上書きする。
+/// `managed` (`managed: block`) の場合は管理ブロックの内容だけで判定し、手書きのファイルにはブロックを追加する。
 fn process_single_file(
-    target: &Targets,
+    target: &TargetName,
     out_path: &Path,
     output: &str,
+    managed: bool,
     overwrite: bool,
     force: bool,
-) -> Result<(), String> {
-    // 出力ディレクトリの作成
-    if let Some(parent) = out_path.parent() {
-        if !parent.exists() {
-            std::fs::create_dir_all(parent).map_err(|e| {
-                format!(
-                    "[generate] ディレクトリ作成に失敗: {}: {}",
-                    parent.display(),
-                    e
-                )
-            })?;
-        }
-    }
+    state: &mut GenerateState,
+) -> Result<()> {
+    state.produced.push(out_path.to_path_buf());
 
     let file_exists = out_path.exists();
 
-    // overwrite: バックアップ作成して上書き
-    if file_exists && overwrite {
-        // ディレクトリが存在する場合はバックアップ・削除
-        if out_path.is_dir() {
-            match backup_and_remove_file(out_path) {
-                Ok(bak_path) => {
-                    println!("[generate] {} をバックアップしました", bak_path.display());
-                }
-                Err(msg) => {
-                    return Err(format!("[generate] バックアップ・削除に失敗: {}", msg));
-                }
-            }
-        }
-
-        // 既存ファイルと新規内容が異なる場合のみバックアップ・上書き
+    if out_path.is_file() {
         let existing_content = fs::read_to_string(out_path)
-            .map_err(|e| format!("[generate] 既存ファイルの読み込みに失敗: {}", e))?;
+            .map_err(|e| InstruxError::io("既存ファイルの読み込み", out_path, e))?;
         if existing_content == output {
             // 差分がなければ何もせずスキップ
             println!("[generate] {} に差分なし。スキップ", out_path.display());
+            state.record(target, out_path, output, managed);
             return Ok(());
-        } else {
-            match backup_and_remove_file(out_path) {
-                Ok(bak_path) => {
-                    println!("[generate] {} をバックアップしました", bak_path.display());
-                }
-                Err(msg) => {
-                    return Err(format!("[generate] バックアップ・削除に失敗: {}", msg));
-                }
+        }
+        match state.manifest.status(out_path, &existing_content) {
+            FileStatus::Unmodified => {
+                state.tx.write(out_path, output)?;
+                state.report(format!("[generate] {} を更新しました", out_path.display()));
+                state.record(target, out_path, output, managed);
+                return Ok(());
+            }
+            // 手書きのファイルに管理ブロックを追加する (ブロックの外は変更しない)
+            FileStatus::Untracked
+                if managed && formats::managed_block(&existing_content).is_none() =>
+            {
+                state.tx.write(out_path, output)?;
+                state.report(format!(
+                    "[generate] {} に管理ブロックを追加しました",
+                    out_path.display()
+                ));
+                state.record(target, out_path, output, managed);
+                return Ok(());
             }
+            FileStatus::Modified if !overwrite && !force => {
+                eprintln!(
+                    "[generate] {} は生成後に手動で変更されています (--overwrite でバックアップ上書き, --force で強制上書き)",
+                    out_path.display()
+                );
+                state.refused.push(out_path.to_path_buf());
+                return Ok(());
+            }
+            _ => {}
         }
-        fs::write(out_path, output).map_err(|e| format!("[generate] ファイル出力に失敗: {}", e))?;
-        println!("[generate] {} を上書きしました", out_path.display());
+    }
+
+    // overwrite: バックアップ作成して上書き
+    if file_exists && overwrite {
+        // 既存のファイル・ディレクトリをバックアップしてから上書き (同一内容は上で処理済み)
+        let bak_path = backup_and_remove_file(out_path, state)?;
+        state.report(format!(
+            "[generate] {} をバックアップしました",
+            bak_path.display()
+        ));
+        state.tx.write(out_path, output)?;
+        state.report(format!(
+            "[generate] {} を上書きしました",
+            out_path.display()
+        ));
+        state.record(target, out_path, output, managed);
         return Ok(());
     }
 
@@ -252,22 +502,14 @@ fn process_single_file(
     if file_exists && force {
         // ディレクトリが存在する場合は削除
         if out_path.is_dir() {
-            std::fs::remove_dir_all(out_path).map_err(|e| {
-                format!(
-                    "[generate] ディレクトリ削除に失敗: {}: {}",
-                    out_path.display(),
-                    e
-                )
-            })?;
+            state.tx.remove(out_path);
         }
-        fs::write(out_path, output).map_err(|e| {
-            format!(
-                "[generate] ファイル出力に失敗: {}: {}",
-                out_path.display(),
-                e
-            )
-        })?;
-        println!("[generate] {} を強制上書きしました", out_path.display());
+        state.tx.write(out_path, output)?;
+        state.report(format!(
+            "[generate] {} を強制上書きしました",
+            out_path.display()
+        ));
+        state.record(target, out_path, output, managed);
         return Ok(());
     }
 
@@ -281,17 +523,12 @@ fn process_single_file(
     }
 
     // 新規ファイル出力
-    fs::write(out_path, output).map_err(|e| {
-        format!(
-            "[generate] ファイル出力に失敗: {}: {}",
-            out_path.display(),
-            e
-        )
-    })?;
-    println!(
+    state.tx.write(out_path, output)?;
+    state.report(format!(
         "[generate] {}形式の出力を生成: {}",
         target,
         out_path.display()
-    );
+    ));
+    state.record(target, out_path, output, managed);
     Ok(())
 }
@@ -1,65 +1,548 @@
+use crate::error::{InstruxError, Result};
 use crate::formats;
-use crate::model::types::{InstruxConfigurationTargetsValue, Targets};
+use crate::model::types::{
+    InstructionItem, InstructionItemVariant0Targets, InstructionItemVariant2Targets,
+    InstruxConfiguration, InstruxConfigurationLanguage, InstruxConfigurationTargetsValue,
+    InstruxConfigurationTargetsValueOutputMode, Targets,
+};
+use similar::TextDiff;
+use std::collections::HashMap;
 use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
 
-// initコマンドの基本処理
-pub fn run(from: Option<String>) {
+const DEFAULT_INSTRUX_CONFIG_PATH: &str = ".instrux/instrux.yaml";
+const INSTRUCTIONS_DIR: &str = ".instrux/instructions";
+
+/// 生成するinstrux.yamlの先頭に付与するスキーマ指定 (yaml-language-server用)
+const SCHEMA_COMMENT: &str = "# yaml-language-server: $schema=https://raw.githubusercontent.com/epli2/instrux/main/schema/instrux.schema.json\n";
+
+/// 検出対象のツール (非推奨のcodexを除く)
+const TOOL_TARGETS: [Targets; 8] = [
+    Targets::Agentsmd,
+    Targets::Claude,
+    Targets::Cline,
+    Targets::Copilot,
+    Targets::Cursor,
+    Targets::Gemini,
+    Targets::Junie,
+    Targets::Windsurf,
+];
+
+/// 同じタイトルのセクションを同一とみなす本文の類似度
+const SAME_TITLE_SIMILARITY: f32 = 0.8;
+/// タイトルが異なるセクションを同一とみなす本文の類似度
+const SIMILARITY: f32 = 0.95;
+
+/// ひな形で出力するサンプルの本文ファイル (ファイル名, 内容)
+const SAMPLE_BODY_FILES: [(&str, &str); 2] = [
+    (
+        "coding-style.md",
+        "- Follow the existing code style and naming conventions of the project.\n- Keep functions small and focused on a single responsibility.\n",
+    ),
+    (
+        "testing.md",
+        "- Add or update tests for every behavior change.\n- Run the full test suite before finishing a task.\n",
+    ),
+];
+
+/// ひな形のinstructions (サンプルの本文ファイルを参照する)
+const SAMPLE_INSTRUCTIONS: &str = r#"instructions:
+  - title: Project Overview
+    body: |-
+      Describe what this project does and the main technologies it uses.
+  - title: Coding Style
+    body_file: coding-style.md
+  - title: Testing
+    body_file: testing.md
+"#;
+
+/// initコマンドの基本処理
+///
+/// # 引数
+/// * `from` - 設定の生成元のツール名。`all` の場合は検出した全ツールから、未指定時はひな形を生成する
+/// * `force` - 既存の設定ファイルを上書きする
+pub fn run(from: Option<String>, force: bool) -> Result<()> {
     println!("[init] from: {:?}", from);
-    if let Some(tool) = from {
-        // ツール名をTargetsに変換
-        let target = match tool.to_lowercase().as_str() {
-            "copilot" => Targets::Copilot,
-            "cline" => Targets::Cline,
-            "cursor" => Targets::Cursor,
-            "junie" => Targets::Junie,
-            "codex" => Targets::Codex,
-            _ => {
-                eprintln!("[init] 未知のツール形式: {}", tool);
-                return;
-            }
-        };
-        // 入力ファイルパスを決定
-        let in_path = formats::get_converter(&target, &InstruxConfigurationTargetsValue::default())
-            .get_default_path();
-        let content = match fs::read_to_string(&in_path) {
-            Ok(s) => s,
-            Err(e) => {
-                eprintln!("[init] 入力ファイルの読み込みに失敗: {}", e);
-                return;
+    let config_path = Path::new(DEFAULT_INSTRUX_CONFIG_PATH);
+    if config_path.exists() && !force {
+        return Err(InstruxError::AlreadyExists(config_path.to_path_buf()));
+    }
+    match from {
+        Some(tool) if tool.eq_ignore_ascii_case("all") => init_from_all(),
+        Some(tool) => init_from(&tool),
+        None => scaffold(),
+    }
+}
+
+/// 指定形式のinstructionファイルから設定を生成する
+fn init_from(tool: &str) -> Result<()> {
+    // ツール名をTargetsに変換
+    let target = match tool.to_lowercase().as_str() {
+        "copilot" => Targets::Copilot,
+        "cline" => Targets::Cline,
+        "cursor" => Targets::Cursor,
+        "junie" => Targets::Junie,
+        "agentsmd" => Targets::Agentsmd,
+        "claude" => Targets::Claude,
+        "windsurf" => Targets::Windsurf,
+        "gemini" => Targets::Gemini,
+        "codex" => return Err(InstruxError::DeprecatedTarget(Targets::Codex)),
+        _ => return Err(InstruxError::UnknownTool(tool.to_string())),
+    };
+    // ルールディレクトリがあれば複数ファイル形式として読み込む
+    let value = match formats::rule_dir(&target) {
+        Some(dir) if dir.is_dir() => InstruxConfigurationTargetsValue {
+            output_mode: InstruxConfigurationTargetsValueOutputMode::Multiple,
+            ..Default::default()
+        },
+        _ => InstruxConfigurationTargetsValue::default(),
+    };
+    // ツール形式→内部モデル
+    let instructions = import(&target, &value)?;
+    // InstruxConfigurationを組み立て
+    let config = InstruxConfiguration {
+        instructions,
+        custom: HashMap::new(),
+        language: InstruxConfigurationLanguage::English,
+        targets: HashMap::from([(target, value)]),
+        sandbox: None,
+        validation: None,
+        version: "0.1.0".parse().unwrap(),
+    };
+    write_yaml_config(&config)
+}
+
+/// ツールのファイルを読み込み、instructionに変換する
+///
+/// 単一ファイルはセクションごとに、ルールディレクトリ配下のファイルは1ファイルを
+/// 1つのinstructionとして読み込む。multipleでも単一ファイル (copilot-instructions.md など) が
+/// ルールディレクトリの外にあれば併せて読み込む。
+fn import(
+    target: &Targets,
+    value: &InstruxConfigurationTargetsValue,
+) -> Result<Vec<InstructionItem>> {
+    let multiple = value.output_mode == InstruxConfigurationTargetsValueOutputMode::Multiple;
+    // pathが指定されていれば、単一ファイル形式ではそのファイル、複数ファイル形式ではそのディレクトリを読み込む
+    let in_path = match &value.path {
+        Some(path) if !multiple => PathBuf::from(path),
+        _ => formats::get_converter(target, &InstruxConfigurationTargetsValue::default())
+            .get_default_path(),
+    };
+    let dir = match &value.path {
+        Some(path) if multiple => Some(PathBuf::from(path)),
+        _ => formats::rule_dir(target).filter(|_| multiple),
+    };
+    let read_single = match &dir {
+        Some(dir) => !in_path.starts_with(dir) && in_path.is_file(),
+        None => true,
+    };
+    let mut items = Vec::new();
+    if read_single {
+        let content = fs::read_to_string(&in_path)
+            .map_err(|e| InstruxError::io("入力ファイルの読み込み", &in_path, e))?;
+        items = formats::from_file(target, &content, &in_path)?;
+    }
+    if let Some(dir) = dir
+        && let Some(dir_items) = formats::from_dir(target, &dir)
+    {
+        items.extend(dir_items?);
+    }
+    Ok(items)
+}
+
+/// ツールのファイルから読み込んだ、本文を持つセクション
+struct Section {
+    target: Targets,
+    /// セクションを含むグループ (外側から順に、子のinstructionを除いたもの)
+    groups: Vec<InstructionItem>,
+    /// セクション (InstructionItem::Variant0)
+    item: InstructionItem,
+}
+
+/// 同一または類似したセクションをまとめたもの
+#[derive(Debug)]
+struct Cluster {
+    /// 先に現れたセクションを含むグループ (外側から順に)
+    groups: Vec<InstructionItem>,
+    /// 先に現れたセクション (InstructionItem::Variant0)
+    item: InstructionItem,
+    /// セクションが含まれていたツール
+    targets: Vec<Targets>,
+}
+
+/// 検出した全ツールのinstructionファイルから設定を生成する
+///
+/// 同一または類似したセクションは1つのinstructionにまとめ、含まれていた全ツールをtargetsとする。
+/// 1つのツールにのみ含まれるセクションはそのツールのみを対象とする。
+fn init_from_all() -> Result<()> {
+    let detected = detect_targets();
+    if detected.is_empty() {
+        println!("[init] ツールのファイルが見つからないため、ひな形を生成します");
+        return scaffold();
+    }
+
+    let mut sections = Vec::new();
+    for (target, value) in &detected {
+        let before = sections.len();
+        flatten_sections(*target, import(target, value)?, &[], &mut sections);
+        println!(
+            "[init] {} から{}件のセクションを読み込みました",
+            target,
+            sections.len() - before
+        );
+    }
+
+    let section_count = sections.len();
+    let clusters = cluster_sections(sections);
+    println!(
+        "[init] {}件のセクションを{}件の instruction にまとめました",
+        section_count,
+        clusters.len()
+    );
+    // セクションを元のグループに戻し、グループの入れ子を復元する
+    let mut instructions = Vec::new();
+    for cluster in clusters {
+        let mut item = cluster.item;
+        if let InstructionItem::Variant0 { targets, .. } = &mut item {
+            *targets = if cluster.targets.len() == detected.len() {
+                InstructionItemVariant0Targets::Variant1("all".to_string())
+            } else {
+                InstructionItemVariant0Targets::Variant0(
+                    cluster.targets.iter().copied().map(Into::into).collect(),
+                )
+            };
+        }
+        insert_section(&mut instructions, &cluster.groups, &cluster.targets, item);
+    }
+    set_all_targets(&mut instructions, detected.len());
+    let config = InstruxConfiguration {
+        instructions,
+        custom: HashMap::new(),
+        language: InstruxConfigurationLanguage::English,
+        targets: detected.into_iter().collect(),
+        sandbox: None,
+        validation: None,
+        version: "0.1.0".parse().unwrap(),
+    };
+    write_yaml_config(&config)
+}
+
+/// 入れ子のinstructionを平坦化し、本文を持つセクションを含むグループとともに追加する
+fn flatten_sections(
+    target: Targets,
+    items: Vec<InstructionItem>,
+    groups: &[InstructionItem],
+    sections: &mut Vec<Section>,
+) {
+    for item in items {
+        match item {
+            InstructionItem::Variant0 { .. } => sections.push(Section {
+                target,
+                groups: groups.to_vec(),
+                item,
+            }),
+            InstructionItem::Variant2 {
+                instructions,
+                activation,
+                applies_to,
+                description,
+                directory,
+                disable,
+                filename,
+                id,
+                title,
+                ..
+            } => {
+                let mut groups = groups.to_vec();
+                groups.push(InstructionItem::Variant2 {
+                    activation,
+                    applies_to,
+                    description,
+                    directory,
+                    disable,
+                    filename,
+                    id,
+                    instructions: Vec::new(),
+                    targets: InstructionItemVariant2Targets::Variant0(Vec::new()),
+                    title,
+                });
+                flatten_sections(target, instructions, &groups, sections)
             }
-        };
-        // ツール形式→内部モデル
-        let instructions = crate::formats::from_format(&target, &content);
-        let instructions = match instructions {
-            Ok(items) => items,
-            Err(e) => {
-                eprintln!("[init] {}形式からのパースに失敗: {}", tool, e);
-                return;
+            InstructionItem::Variant1 { .. } => {}
+        }
+    }
+}
+
+/// 同じタイトルか (前後の空白・英字の大文字小文字を区別しない)
+fn same_title(a: &str, b: &str) -> bool {
+    a.trim().eq_ignore_ascii_case(b.trim())
+}
+
+/// グループ (外側から順に) のタイトルがすべて一致するか
+fn same_groups(a: &[InstructionItem], b: &[InstructionItem]) -> bool {
+    a.len() == b.len()
+        && a.iter()
+            .zip(b)
+            .all(|(a, b)| same_title(title_and_body(a).0, title_and_body(b).0))
+}
+
+/// セクションを `groups` のグループの中に追加する (グループがなければ作成する)
+///
+/// グループのtargetsには、含まれるセクションのツールを追加する。
+fn insert_section(
+    items: &mut Vec<InstructionItem>,
+    groups: &[InstructionItem],
+    targets: &[Targets],
+    item: InstructionItem,
+) {
+    let Some((group, rest)) = groups.split_first() else {
+        items.push(item);
+        return;
+    };
+    let title = title_and_body(group).0;
+    let index = items
+        .iter()
+        .position(|existing| {
+            matches!(existing, InstructionItem::Variant2 { title: t, .. } if same_title(t, title))
+        })
+        .unwrap_or_else(|| {
+            items.push(group.clone());
+            items.len() - 1
+        });
+    if let InstructionItem::Variant2 {
+        instructions,
+        targets: InstructionItemVariant2Targets::Variant0(group_targets),
+        ..
+    } = &mut items[index]
+    {
+        for target in targets {
+            let target = (*target).into();
+            if !group_targets.contains(&target) {
+                group_targets.push(target);
             }
-        };
-        // InstruxConfigurationを組み立て
-        let config = crate::model::types::InstruxConfiguration {
+        }
+        insert_section(instructions, rest, targets, item);
+    }
+}
+
+/// 検出した全ツールのセクションを含むグループのtargetsを `all` にする
+fn set_all_targets(items: &mut [InstructionItem], detected: usize) {
+    for item in items {
+        if let InstructionItem::Variant2 {
             instructions,
-            language: crate::model::types::InstruxConfigurationLanguage::English,
-            targets: {
-                let mut map = std::collections::HashMap::new();
-                map.insert(target, InstruxConfigurationTargetsValue::default());
-                map
-            },
-            version: "0.1.0".parse().unwrap(),
-        };
-        // YAMLとして出力
-        let yaml = match serde_yaml::to_string(&config) {
-            Ok(s) => s,
-            Err(e) => {
-                eprintln!("[init] YAMLシリアライズに失敗: {}", e);
-                return;
+            targets,
+            ..
+        } = item
+        {
+            if matches!(targets, InstructionItemVariant2Targets::Variant0(t) if t.len() == detected)
+            {
+                *targets = InstructionItemVariant2Targets::Variant1("all".to_string());
+            }
+            set_all_targets(instructions, detected);
+        }
+    }
+}
+
+/// セクションのタイトルと本文
+fn title_and_body(item: &InstructionItem) -> (&str, &str) {
+    match item {
+        InstructionItem::Variant0 { title, body, .. } => (title, body),
+        InstructionItem::Variant1 { title, .. } | InstructionItem::Variant2 { title, .. } => {
+            (title, "")
+        }
+    }
+}
+
+/// セクションを同一または類似したものごとにまとめる
+///
+/// 先に現れたセクションのタイトル・本文・メタデータを採用し、出現順を保つ。
+/// 異なるグループに含まれるセクションはまとめない。
+fn cluster_sections(sections: Vec<Section>) -> Vec<Cluster> {
+    let mut clusters: Vec<Cluster> = Vec::new();
+    for Section {
+        target,
+        groups,
+        item,
+    } in sections
+    {
+        let (title, body) = title_and_body(&item);
+        let found = clusters.iter_mut().find(|cluster| {
+            // 同じツール内のセクション・異なるグループのセクションは別のinstructionとして扱う
+            if cluster.targets.contains(&target) || !same_groups(&cluster.groups, &groups) {
+                return false;
+            }
+            let (cluster_title, cluster_body) = title_and_body(&cluster.item);
+            let ratio = TextDiff::from_chars(cluster_body, body).ratio();
+            if same_title(cluster_title, title) {
+                ratio >= SAME_TITLE_SIMILARITY
+            } else {
+                !body.trim().is_empty() && ratio >= SIMILARITY
+            }
+        });
+        match found {
+            Some(cluster) => cluster.targets.push(target),
+            None => clusters.push(Cluster {
+                groups,
+                item,
+                targets: vec![target],
+            }),
+        }
+    }
+    clusters
+}
+
+/// ひな形の設定とサンプルの本文ファイルを生成する
+///
+/// targetsにはリポジトリ内で検出したツールを設定する。
+/// 検出できなかった場合はAGENTS.mdを出力対象とする。
+fn scaffold() -> Result<()> {
+    let mut targets = detect_targets();
+    for (target, _) in &targets {
+        println!("[init] {} のファイルを検出しました", target);
+    }
+    if targets.is_empty() {
+        println!("[init] ツールのファイルが見つからないため、agentsmd を出力対象にします");
+        targets.push((Targets::Agentsmd, Default::default()));
+    }
+
+    let mut yaml = String::from("version: 0.1.0\nlanguage: English\ntargets:\n");
+    for (target, value) in &targets {
+        match value.output_mode {
+            InstruxConfigurationTargetsValueOutputMode::Single => {
+                yaml.push_str(&format!("  {}: {{}}\n", target));
+            }
+            InstruxConfigurationTargetsValueOutputMode::Multiple => {
+                yaml.push_str(&format!("  {}:\n    outputMode: multiple\n", target));
+            }
+        }
+    }
+    yaml.push_str(SAMPLE_INSTRUCTIONS);
+    write_config(&yaml)?;
+
+    for (file_name, content) in SAMPLE_BODY_FILES {
+        let path = Path::new(INSTRUCTIONS_DIR).join(file_name);
+        if path.exists() {
+            println!("[init] {} は既に存在するためスキップ", path.display());
+            continue;
+        }
+        fs::write(&path, content).map_err(|e| InstruxError::io("ファイル出力", &path, e))?;
+        println!("[init] {} を出力しました", path.display());
+    }
+    Ok(())
+}
+
+/// リポジトリ内のツールのファイルを検出し、ターゲットと出力モードを返す
+///
+/// 単一ファイル形式のパスがファイルとして存在すればsingle、
+/// ルールディレクトリ (.clinerules/ など) が存在すればmultipleとする。
+fn detect_targets() -> Vec<(Targets, InstruxConfigurationTargetsValue)> {
+    let single = InstruxConfigurationTargetsValue::default();
+    let multiple = InstruxConfigurationTargetsValue {
+        output_mode: InstruxConfigurationTargetsValueOutputMode::Multiple,
+        ..Default::default()
+    };
+    TOOL_TARGETS
+        .iter()
+        .filter_map(|target| {
+            if formats::get_converter(target, &single)
+                .get_default_path()
+                .is_file()
+            {
+                Some((*target, single.clone()))
+            } else if formats::rule_dir(target).is_some_and(|dir| dir.is_dir()) {
+                Some((*target, multiple.clone()))
+            } else {
+                None
             }
+        })
+        .collect()
+}
+
+/// 設定をYAMLにシリアライズして出力する
+fn write_yaml_config(config: &InstruxConfiguration) -> Result<()> {
+    let yaml = serde_yaml::to_string(config).map_err(|e| {
+        InstruxError::io(
+            "YAMLシリアライズ",
+            DEFAULT_INSTRUX_CONFIG_PATH,
+            io::Error::other(e),
+        )
+    })?;
+    write_config(&yaml)
+}
+
+/// スキーマ指定のコメントを付けて設定ファイルを出力する
+///
+/// .instrux/ と instructions/ ディレクトリが無ければ作成する。
+fn write_config(yaml: &str) -> Result<()> {
+    fs::create_dir_all(INSTRUCTIONS_DIR)
+        .map_err(|e| InstruxError::io("ディレクトリ作成", INSTRUCTIONS_DIR, e))?;
+    fs::write(
+        DEFAULT_INSTRUX_CONFIG_PATH,
+        format!("{}{}", SCHEMA_COMMENT, yaml),
+    )
+    .map_err(|e| InstruxError::io("instrux.yamlの出力", DEFAULT_INSTRUX_CONFIG_PATH, e))?;
+    println!("[init] instrux.yaml を出力しました");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Section, cluster_sections};
+    use crate::model::types::{InstructionItem, InstructionItemVariant0Targets, Targets};
+
+    fn section(target: Targets, title: &str, body: &str) -> Section {
+        let item = InstructionItem::Variant0 {
+            title: title.to_string(),
+            body: body.to_string(),
+            description: None,
+            applies_to: Vec::new(),
+            activation: None,
+            directory: None,
+            filename: None,
+            id: None,
+            disable: false,
+            targets: InstructionItemVariant0Targets::Variant0(vec![target.into()]),
         };
-        if let Err(e) = fs::write(".instrux/instrux.yaml", yaml) {
-            eprintln!("[init] instrux.yamlの出力に失敗: {}", e);
-        } else {
-            println!("[init] instrux.yaml を出力しました");
+        Section {
+            target,
+            groups: Vec::new(),
+            item,
         }
     }
+
+    #[test]
+    fn test_cluster_sections_merges_near_duplicates() {
+        let sections = vec![
+            section(Targets::Copilot, "Style", "Use rustfmt before committing."),
+            section(
+                Targets::Copilot,
+                "Copilot only",
+                "Prefer short completions.",
+            ),
+            section(Targets::Agentsmd, "style", "Use rustfmt before committing!"),
+            section(
+                Targets::Cline,
+                "Formatting",
+                "Use rustfmt before committing.",
+            ),
+            section(Targets::Cline, "Style", "Write everything in Go."),
+        ];
+        let clusters = cluster_sections(sections);
+        let summary: Vec<(&str, Vec<Targets>)> = clusters
+            .iter()
+            .map(|c| (super::title_and_body(&c.item).0, c.targets.clone()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    "Style",
+                    vec![Targets::Copilot, Targets::Agentsmd, Targets::Cline]
+                ),
+                ("Copilot only", vec![Targets::Copilot]),
+                ("Style", vec![Targets::Cline]),
+            ]
+        );
+    }
 }
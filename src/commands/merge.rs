@@ -1,5 +1,836 @@
-// mergeコマンドの基本処理
-pub fn run(from: Option<String>) {
+use crate::diff::make_diff;
+use crate::error::{InstruxError, Result};
+use crate::formats::{self, FormatResult};
+use crate::manifest::{DEFAULT_MANIFEST_PATH, Manifest};
+use crate::model::parser;
+use crate::model::types::{
+    InstructionId, InstructionItem, InstructionItemVariant0Targets, InstructionItemVariant1Targets,
+    InstruxConfiguration, Targets,
+};
+use crate::sandbox::Sandbox;
+use crate::validation::yaml::{self, Value};
+use pulldown_cmark::{Event, Parser as MarkdownParser, Tag};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const DEFAULT_INSTRUX_CONFIG_PATH: &str = ".instrux/instrux.yaml";
+const INSTRUCTIONS_DIR: &str = ".instrux/instructions";
+
+/// ツールファイルから抽出したセクション
+struct Section {
+    /// 見出しに埋め込まれたinstructionのID
+    id: Option<InstructionId>,
+    title: String,
+    body: String,
+}
+
+impl Section {
+    /// 同じinstructionのセクションかどうか
+    ///
+    /// 両方にIDがあればIDで照合し (見出しの変更を追跡する)、なければタイトルで照合する。
+    fn matches(&self, other: &Section) -> bool {
+        match (&self.id, &other.id) {
+            (Some(id), Some(other)) => id == other,
+            _ => self.title == other.title,
+        }
+    }
+}
+
+/// 両側で変更されたために自動マージできなかったセクション
+struct Conflict {
+    target: Targets,
+    title: String,
+    ours: String,
+    theirs: String,
+}
+
+/// mergeコマンドの基本処理
+///
+/// ツールファイル側の変更をinstrux設定へ3-wayマージする。
+/// マージ基準(base)はgitのHEADにコミットされているinstrux.yamlから生成した内容で、
+/// 取得できない場合は現在の設定を基準とする(ツール側の変更を優先)。
+///
+/// # 引数
+/// * `from` - マージ元のツール名。未指定時は設定済みの全ターゲット
+pub fn run(from: Option<String>) -> Result<()> {
     println!("[merge] from: {:?}", from);
-    // TODO: 実装
+    let mut config = parser::parse_instrux_yaml(DEFAULT_INSTRUX_CONFIG_PATH)?;
+    // 読み書きするbody_file・ツールファイルは許可された範囲内に限る
+    let sandbox = Sandbox::current(&config);
+    sandbox.check_body_files(&config.instructions)?;
+
+    let targets: Vec<Targets> = if let Some(name) = from {
+        match name.to_lowercase().as_str() {
+            "copilot" => vec![Targets::Copilot],
+            "cline" => vec![Targets::Cline],
+            "cursor" => vec![Targets::Cursor],
+            "junie" => vec![Targets::Junie],
+            "agentsmd" => vec![Targets::Agentsmd],
+            "claude" => vec![Targets::Claude],
+            "windsurf" => vec![Targets::Windsurf],
+            "gemini" => vec![Targets::Gemini],
+            "codex" => return Err(InstruxError::DeprecatedTarget(Targets::Codex)),
+            other => return Err(InstruxError::UnknownTool(other.to_string())),
+        }
+    } else {
+        let mut keys: Vec<Targets> = config.targets.keys().cloned().collect();
+        keys.sort();
+        for target in keys.iter().filter(|target| formats::is_deprecated(target)) {
+            eprintln!(
+                "[merge] 警告: {} は非推奨のためスキップします。agentsmd (AGENTS.md) を使用してください",
+                target
+            );
+        }
+        keys.retain(|target| !formats::is_deprecated(target));
+        keys
+    };
+
+    let base = load_base_config().unwrap_or_else(|| {
+        println!("[merge] 基準となる設定をgitから取得できないため、現在の設定を基準にします");
+        config.clone()
+    });
+
+    let original = config.clone();
+    let mut merged_files = Vec::new();
+    let mut changed = false;
+    let mut conflicts = Vec::new();
+    for target in targets {
+        let mut target_config = config.targets.get(&target).cloned().unwrap_or_default();
+        // インポート行は読み込み時に参照先の内容へ展開されるため、埋め込んだ出力と比較する
+        target_config.imports = None;
+        let converter = formats::get_converter(&target, &target_config);
+        sandbox.check_output(&converter.get_default_path())?;
+
+        let ours_result = converter.to_format(&config)?;
+        let base_result = converter.to_format(&base)?;
+
+        let (ours, tool_files) = match &ours_result {
+            FormatResult::Single(content) => {
+                let path = converter.get_default_path();
+                (
+                    sections_of(&target, content, &path, false)?,
+                    vec![path].into_iter().filter(|p| p.is_file()).collect(),
+                )
+            }
+            FormatResult::Multiple(files) => {
+                let mut sections = Vec::new();
+                for (path, content) in files {
+                    sections.extend(sections_of(&target, content, Path::new(path), true)?);
+                }
+                // 出力先ディレクトリに追加されたファイルと、ディレクトリ外に出力したファイル
+                // (ディレクトリごとのAGENTS.mdなど) を読み込む
+                let mut paths = list_rule_files(&target, &converter.get_default_path());
+                for path in files.iter().map(|(path, _)| PathBuf::from(path)) {
+                    if path.is_file() && !paths.contains(&path) {
+                        paths.push(path);
+                    }
+                }
+                paths.sort();
+                (sections, paths)
+            }
+        };
+        let base_sections = match &base_result {
+            FormatResult::Single(content) => {
+                sections_of(&target, content, &converter.get_default_path(), false)?
+            }
+            FormatResult::Multiple(files) => {
+                let mut sections = Vec::new();
+                for (path, content) in files {
+                    sections.extend(sections_of(&target, content, Path::new(path), true)?);
+                }
+                sections
+            }
+        };
+
+        if tool_files.is_empty() {
+            println!("[merge] {}形式のファイルが見つからないためスキップ", target);
+            continue;
+        }
+        let multiple = matches!(ours_result, FormatResult::Multiple(_));
+        let mut theirs = Vec::new();
+        for path in &tool_files {
+            let content = fs::read_to_string(path)
+                .map_err(|e| InstruxError::io("ファイル読み込み", path, e))?;
+            theirs.extend(sections_of(&target, &content, path, multiple)?);
+        }
+        merged_files.extend(tool_files);
+
+        for theirs_section in &theirs {
+            let base_section = base_sections.iter().find(|s| s.matches(theirs_section));
+            let base_body = base_section.map(|s| &s.body);
+            let theirs_body = &theirs_section.body;
+            let Some(ours_section) = ours.iter().find(|s| s.matches(theirs_section)) else {
+                match base_body {
+                    // 設定側で削除済み、ツール側は未変更
+                    Some(base_body) if base_body == theirs_body => {}
+                    Some(_) => conflicts.push(Conflict {
+                        target,
+                        title: theirs_section.title.clone(),
+                        ours: String::new(),
+                        theirs: theirs_body.clone(),
+                    }),
+                    None => {
+                        config.instructions.push(InstructionItem::Variant0 {
+                            title: theirs_section.title.clone(),
+                            body: theirs_body.clone(),
+                            description: None,
+                            applies_to: Vec::new(),
+                            activation: None,
+                            directory: None,
+                            filename: None,
+                            id: theirs_section.id.clone(),
+                            disable: false,
+                            targets: InstructionItemVariant0Targets::Variant0(vec![target.into()]),
+                        });
+                        println!(
+                            "[merge] 新しいセクション {} を追加しました ({})",
+                            theirs_section.title, target
+                        );
+                        changed = true;
+                    }
+                }
+                continue;
+            };
+
+            let ours_body = &ours_section.body;
+            if ours_body != theirs_body && base_body != Some(theirs_body) {
+                if base_body.is_some() && base_body != Some(ours_body) {
+                    conflicts.push(Conflict {
+                        target,
+                        title: theirs_section.title.clone(),
+                        ours: ours_body.clone(),
+                        theirs: theirs_body.clone(),
+                    });
+                } else {
+                    apply_body(&mut config.instructions, &target, ours_section, theirs_body)?;
+                    println!(
+                        "[merge] {} の変更を取り込みました ({})",
+                        ours_section.title, target
+                    );
+                    changed = true;
+                }
+            }
+            // 見出しの変更 (設定側のみ変更した場合はgenerateで反映されるため何もしない)
+            let base_title = base_section.map(|s| &s.title);
+            if ours_section.title != theirs_section.title
+                && base_title != Some(&theirs_section.title)
+            {
+                if base_title.is_some() && base_title != Some(&ours_section.title) {
+                    conflicts.push(Conflict {
+                        target,
+                        title: theirs_section.title.clone(),
+                        ours: ours_section.title.clone(),
+                        theirs: theirs_section.title.clone(),
+                    });
+                } else {
+                    rename_instruction(
+                        &mut config.instructions,
+                        &target,
+                        ours_section,
+                        &theirs_section.title,
+                    )?;
+                    println!(
+                        "[merge] {} の見出しを {} に変更しました ({})",
+                        ours_section.title, theirs_section.title, target
+                    );
+                    changed = true;
+                }
+            }
+        }
+        for ours_section in &ours {
+            if !theirs.iter().any(|s| s.matches(ours_section)) {
+                println!(
+                    "[merge] {} は{}形式のファイルにありません (設定には残します)",
+                    ours_section.title, target
+                );
+            }
+        }
+    }
+
+    if !changed {
+        println!("[merge] 取り込む変更はありません");
+    } else if serde_json::to_value(&config).ok() != serde_json::to_value(&original).ok() {
+        // body_fileだけを更新した場合は設定ファイルを書き換えない
+        write_config(&config)?;
+        println!("[merge] {} を更新しました", DEFAULT_INSTRUX_CONFIG_PATH);
+    }
+
+    if !conflicts.is_empty() {
+        for conflict in &conflicts {
+            eprintln!(
+                "[merge] 競合: {} ({}) は設定とツールファイルの両方で変更されています",
+                conflict.title, conflict.target
+            );
+            eprintln!("{}", make_diff(&conflict.ours, &conflict.theirs));
+        }
+        return Err(InstruxError::Conflict(conflicts.len()));
+    }
+    // 競合がなければツールファイルの変更はすべて設定に取り込まれている
+    refresh_manifest(&merged_files)
+}
+
+/// 変更を取り込んだツールファイルの内容をマニフェストに記録する
+///
+/// 次のgenerateでこれらのファイルを手動で変更されたものとして扱わず、設定から再生成できるようにする。
+fn refresh_manifest(paths: &[PathBuf]) -> Result<()> {
+    let mut manifest = Manifest::load(DEFAULT_MANIFEST_PATH)?;
+    let mut refreshed = false;
+    for path in paths {
+        let content =
+            fs::read_to_string(path).map_err(|e| InstruxError::io("ファイル読み込み", path, e))?;
+        refreshed |= manifest.refresh(path, &content);
+    }
+    if refreshed {
+        fs::write(DEFAULT_MANIFEST_PATH, manifest.to_json())
+            .map_err(|e| InstruxError::io("マニフェストの出力", DEFAULT_MANIFEST_PATH, e))?;
+        println!("[merge] {} を更新しました", DEFAULT_MANIFEST_PATH);
+    }
+    Ok(())
+}
+
+/// gitのHEADにコミットされているinstrux.yamlをマージ基準として読み込む
+///
+/// body_fileもHEADの内容を本文として埋め込む (作業ツリーの未コミットの変更を基準に含めない)。
+fn load_base_config() -> Option<InstruxConfiguration> {
+    let content = git_show(DEFAULT_INSTRUX_CONFIG_PATH)?;
+    let mut config =
+        parser::parse_instrux_yaml_str(&content, Path::new(DEFAULT_INSTRUX_CONFIG_PATH)).ok()?;
+    inline_base_body_files(&mut config.instructions);
+    Some(config)
+}
+
+/// gitのHEADにコミットされているファイルの内容を読み込む
+fn git_show(path: &str) -> Option<String> {
+    let output = Command::new("git")
+        .args(["show", &format!("HEAD:./{}", path)])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8(output.stdout).ok()
+}
+
+/// body_fileを参照するinstructionを、HEADにコミットされている内容を本文とするinstructionに置き換える
+///
+/// HEADにないbody_fileは作業ツリーの内容を基準とする。
+fn inline_base_body_files(items: &mut [InstructionItem]) {
+    for item in items.iter_mut() {
+        match item {
+            InstructionItem::Variant1 {
+                activation,
+                applies_to,
+                body_file,
+                description,
+                directory,
+                disable,
+                filename,
+                id,
+                targets,
+                title,
+            } => {
+                let path = Path::new(INSTRUCTIONS_DIR).join(body_file.as_str());
+                let Some(body) = git_show(&path.to_string_lossy()) else {
+                    continue;
+                };
+                let targets = match targets {
+                    InstructionItemVariant1Targets::Variant0(names) => {
+                        InstructionItemVariant0Targets::Variant0(names.clone())
+                    }
+                    InstructionItemVariant1Targets::Variant1(name) => {
+                        InstructionItemVariant0Targets::Variant1(name.clone())
+                    }
+                };
+                *item = InstructionItem::Variant0 {
+                    activation: *activation,
+                    applies_to: applies_to.clone(),
+                    body,
+                    description: description.clone(),
+                    directory: directory.clone(),
+                    disable: *disable,
+                    filename: filename.clone(),
+                    id: id.clone(),
+                    targets,
+                    title: title.clone(),
+                };
+            }
+            InstructionItem::Variant2 { instructions, .. } => inline_base_body_files(instructions),
+            InstructionItem::Variant0 { .. } => {}
+        }
+    }
+}
+
+/// ディレクトリ直下のターゲットのルールファイル (Cursorの `.mdc` など) をパス順に列挙する
+fn list_rule_files(target: &Targets, dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = match fs::read_dir(dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|p| p.is_file() && formats::is_rule_file(target, p))
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    files.sort();
+    files
+}
+
+/// ツールファイルの内容をFromFormatパーサでセクションに分解する
+///
+/// 複数ファイル形式では各ファイルがレベル1見出しで始まるため、
+/// frontmatterを除去し見出しを1段下げてからパースする。
+/// インポートは読み込んだファイル `path` のディレクトリから解決する。
+fn sections_of(
+    target: &Targets,
+    content: &str,
+    path: &Path,
+    multiple: bool,
+) -> Result<Vec<Section>> {
+    let content = if multiple {
+        demote_headings(strip_frontmatter(content))
+    } else {
+        content.to_string()
+    };
+    let items = match formats::from_file(target, &content, path) {
+        Ok(items) => items,
+        // セクションが1つもないファイルは空として扱う
+        Err(_) => return Ok(Vec::new()),
+    };
+    let mut sections = Vec::new();
+    flatten_sections(items, &mut sections);
+    Ok(sections)
+}
+
+/// 入れ子のInstructionItemから本文を持つセクションを順に取り出す
+fn flatten_sections(items: Vec<InstructionItem>, sections: &mut Vec<Section>) {
+    for item in items {
+        match item {
+            InstructionItem::Variant0 {
+                id, title, body, ..
+            } => sections.push(Section { id, title, body }),
+            InstructionItem::Variant2 { instructions, .. } => {
+                flatten_sections(instructions, sections)
+            }
+            InstructionItem::Variant1 { .. } => {}
+        }
+    }
+}
+
+/// 先頭のYAML frontmatterを取り除く
+fn strip_frontmatter(content: &str) -> &str {
+    if let Some(rest) = content.strip_prefix("---\n")
+        && let Some(end) = rest.find("\n---\n")
+    {
+        return &rest[end + "\n---\n".len()..];
+    }
+    content
+}
+
+/// 見出しレベルを1段下げる
+///
+/// コードブロック内の `#` で始まる行は見出しではないため変更しない。
+fn demote_headings(content: &str) -> String {
+    let mut demoted = String::with_capacity(content.len());
+    let mut copied = 0;
+    for (event, range) in MarkdownParser::new(content).into_offset_iter() {
+        if let Event::Start(Tag::Heading { .. }) = event {
+            // ATX見出し (`#` で始まるもの) のみ。Setext見出しはそのまま残す
+            let indent = content[range.start..].len() - content[range.start..].trim_start().len();
+            let hash = range.start + indent;
+            if content[hash..].starts_with('#') {
+                demoted.push_str(&content[copied..hash]);
+                demoted.push('#');
+                copied = hash;
+            }
+        }
+    }
+    demoted.push_str(&content[copied..]);
+    demoted
+}
+
+/// セクションに対応するInstructionItemにツール側の本文を反映する
+fn apply_body(
+    items: &mut [InstructionItem],
+    target: &Targets,
+    section: &Section,
+    body: &str,
+) -> Result<()> {
+    let item = find_instruction_mut(items, target, section)?;
+    match item {
+        InstructionItem::Variant0 { body: current, .. } => {
+            *current = body.to_string();
+            Ok(())
+        }
+        InstructionItem::Variant1 { body_file, .. } => {
+            let path = Path::new(INSTRUCTIONS_DIR).join(body_file.as_str());
+            fs::write(&path, format!("{}\n", body))
+                .map_err(|e| InstruxError::io("ファイル出力", &path, e))?;
+            println!("[merge] {} を更新しました", path.display());
+            Ok(())
+        }
+        InstructionItem::Variant2 { .. } => Err(InstruxError::Conversion {
+            target: *target,
+            message: format!(
+                "{} はネストされた見出しのため本文をマージできません",
+                section.title
+            ),
+        }),
+    }
+}
+
+/// セクションに対応するInstructionItemのタイトルをツール側の見出しに変更する
+fn rename_instruction(
+    items: &mut [InstructionItem],
+    target: &Targets,
+    section: &Section,
+    title: &str,
+) -> Result<()> {
+    match find_instruction_mut(items, target, section)? {
+        InstructionItem::Variant0 { title: current, .. }
+        | InstructionItem::Variant1 { title: current, .. }
+        | InstructionItem::Variant2 { title: current, .. } => *current = title.to_string(),
+    }
+    Ok(())
+}
+
+/// セクションに対応するInstructionItemを検索する
+fn find_instruction_mut<'a>(
+    items: &'a mut [InstructionItem],
+    target: &Targets,
+    section: &Section,
+) -> Result<&'a mut InstructionItem> {
+    find_item_mut(items, section).ok_or_else(|| InstruxError::Conversion {
+        target: *target,
+        message: format!("{} が設定に見つかりません", section.title),
+    })
+}
+
+/// IDがあればIDで、なければタイトルでInstructionItemを再帰的に検索する
+fn find_item_mut<'a>(
+    items: &'a mut [InstructionItem],
+    section: &Section,
+) -> Option<&'a mut InstructionItem> {
+    for item in items.iter_mut() {
+        let matched = match item {
+            InstructionItem::Variant0 { id, title, .. }
+            | InstructionItem::Variant1 { id, title, .. }
+            | InstructionItem::Variant2 { id, title, .. } => match &section.id {
+                Some(section_id) => id.as_ref() == Some(section_id),
+                None => *title == section.title,
+            },
+        };
+        if matched {
+            return Some(item);
+        }
+        if let InstructionItem::Variant2 { instructions, .. } = item
+            && let Some(found) = find_item_mut(instructions, section)
+        {
+            return Some(found);
+        }
+    }
+    None
+}
+
+/// 設定ファイルを書き出す
+///
+/// 既存のinstrux.yamlのうち、変更した本文と追加したinstructionの箇所だけを書き換える。
+/// キーの順序・コメント・書式はそのまま残す。
+fn write_config(config: &InstruxConfiguration) -> Result<()> {
+    let path = Path::new(DEFAULT_INSTRUX_CONFIG_PATH);
+    let existing =
+        fs::read_to_string(path).map_err(|e| InstruxError::io("ファイル読み込み", path, e))?;
+    let updated = patch_config(&existing, config).ok_or_else(|| InstruxError::Parse {
+        path: path.to_path_buf(),
+        message: "変更箇所を特定できないため書き換えられません (instructions はブロック形式で記述してください)"
+            .to_string(),
+    })?;
+    fs::write(path, updated).map_err(|e| InstruxError::io("instrux.yamlの出力", path, e))
+}
+
+/// 既存の設定ファイルの内容に、設定の変更 (本文の更新・末尾へのinstructionの追加) を反映する
+///
+/// 書き換えた結果を読み込み直して設定と一致しない場合は `None` を返す。
+fn patch_config(existing: &str, config: &InstruxConfiguration) -> Option<String> {
+    let original =
+        parser::parse_instrux_yaml_str(existing, Path::new(DEFAULT_INSTRUX_CONFIG_PATH)).ok()?;
+    let root = yaml::load(existing).ok()?;
+    let mut lines: Vec<String> = existing.lines().map(str::to_string).collect();
+
+    // 末尾に追加したinstruction (位置がずれないよう、後ろの箇所から書き換える)
+    let added = config.instructions.get(original.instructions.len()..)?;
+    if !added.is_empty() {
+        let instructions = yaml::locate(&root, "instructions")?;
+        let Value::Array(items) = &instructions.value else {
+            return None;
+        };
+        let first = items.first()?;
+        let dash_line = &lines[first.line - 1];
+        let dash = dash_line.len() - dash_line.trim_start().len();
+        if !dash_line.trim_start().starts_with('-') {
+            return None;
+        }
+        let end = block_end(&lines, items.last()?.line, dash, true);
+        let rendered: Vec<String> = added
+            .iter()
+            .map(|item| render_item(item, dash))
+            .collect::<Option<_>>()?;
+        lines.splice(end..end, rendered.join("").lines().map(str::to_string));
+    }
+
+    // タイトル・本文を変更したinstruction
+    let mut edits = Vec::new();
+    scalar_edits(
+        "instructions",
+        &original.instructions,
+        &config.instructions,
+        &mut edits,
+    );
+    let mut scalars = Vec::new();
+    for (item_path, name, value) in edits {
+        let Value::Object(entries) = &yaml::locate(&root, &item_path)?.value else {
+            return None;
+        };
+        let (key, _) = entries
+            .iter()
+            .find(|(key, _)| matches!(&key.value, Value::String(k) if k == name))?;
+        scalars.push((key.line, key.column, name, value));
+    }
+    scalars.sort_by_key(|(line, _, _, _)| std::cmp::Reverse(*line));
+    for (line, column, name, value) in scalars {
+        let key_line = &lines[line - 1];
+        let offset = key_line
+            .char_indices()
+            .nth(column - 1)
+            .map_or(key_line.len(), |(i, _)| i);
+        let indent = key_line[..offset].chars().count();
+        let value = format!(
+            "{}{}: {}",
+            &key_line[..offset],
+            name,
+            yaml_scalar(value, indent + 2)
+        );
+        let end = block_end(&lines, line, indent, false);
+        lines.splice(line - 1..end, value.lines().map(str::to_string));
+    }
+
+    let mut updated = lines.join("\n");
+    updated.push('\n');
+    let reparsed =
+        parser::parse_instrux_yaml_str(&updated, Path::new(DEFAULT_INSTRUX_CONFIG_PATH)).ok()?;
+    (serde_json::to_value(&reparsed).ok()? == serde_json::to_value(config).ok()?).then_some(updated)
+}
+
+/// タイトル・本文が変更されたinstructionのYAMLパス (`instructions[2].instructions[0]` 形式) と
+/// キー名、新しい値を集める
+fn scalar_edits<'a>(
+    path: &str,
+    before: &[InstructionItem],
+    after: &'a [InstructionItem],
+    edits: &mut Vec<(String, &'static str, &'a str)>,
+) {
+    for (i, (before, after)) in before.iter().zip(after).enumerate() {
+        let item_path = format!("{}[{}]", path, i);
+        if title_of(before) != title_of(after) {
+            edits.push((item_path.clone(), "title", title_of(after)));
+        }
+        match (before, after) {
+            (
+                InstructionItem::Variant0 { body: old, .. },
+                InstructionItem::Variant0 { body, .. },
+            ) if old != body => {
+                edits.push((item_path, "body", body));
+            }
+            (
+                InstructionItem::Variant2 {
+                    instructions: old, ..
+                },
+                InstructionItem::Variant2 { instructions, .. },
+            ) => scalar_edits(
+                &format!("{}.instructions", item_path),
+                old,
+                instructions,
+                edits,
+            ),
+            _ => {}
+        }
+    }
+}
+
+/// InstructionItemのタイトル
+fn title_of(item: &InstructionItem) -> &str {
+    match item {
+        InstructionItem::Variant0 { title, .. }
+        | InstructionItem::Variant1 { title, .. }
+        | InstructionItem::Variant2 { title, .. } => title,
+    }
+}
+
+/// `line` 行目 (1始まり) から始まるブロックの終わり (続く行の0始まりの位置) を返す
+///
+/// 空行と `indent` より深くインデントされた行をブロックに含める。
+/// `sequence` の場合は同じインデントの `-` で始まる行 (後続の要素) も含める。
+fn block_end(lines: &[String], line: usize, indent: usize, sequence: bool) -> usize {
+    let mut end = line;
+    for (i, text) in lines.iter().enumerate().skip(line) {
+        let trimmed = text.trim_start();
+        let depth = text.len() - trimmed.len();
+        if trimmed.is_empty() {
+            continue;
+        }
+        if depth > indent || (sequence && depth == indent && trimmed.starts_with('-')) {
+            end = i + 1;
+        } else {
+            break;
+        }
+    }
+    end
+}
+
+/// 追加するinstructionをシーケンスの要素として書き出す (`dash` は `-` のインデント)
+fn render_item(item: &InstructionItem, dash: usize) -> Option<String> {
+    let InstructionItem::Variant0 {
+        title,
+        body,
+        id,
+        targets: InstructionItemVariant0Targets::Variant0(targets),
+        ..
+    } = item
+    else {
+        return None;
+    };
+    let pad = " ".repeat(dash);
+    let targets: Vec<String> = targets.iter().map(|t| t.to_string()).collect();
+    let id = id
+        .as_ref()
+        .map(|id| format!("{pad}  id: {}\n", yaml_scalar(id, dash + 4)))
+        .unwrap_or_default();
+    Some(format!(
+        "{pad}- title: {}\n{id}{pad}  body: {}\n{pad}  targets: [{}]\n",
+        yaml_scalar(title, dash + 4),
+        yaml_scalar(body, dash + 4),
+        targets.join(", "),
+    ))
+}
+
+/// 文字列をYAMLのスカラーとして書き出す
+///
+/// 複数行はインデント `indent` のリテラルブロック、それ以外は必要に応じて引用符で囲む。
+/// 末尾に複数の改行がある場合はダブルクォート (JSON文字列はYAMLとしても有効) で書き出す。
+fn yaml_scalar(value: &str, indent: usize) -> String {
+    let quoted = || serde_json::to_string(value).unwrap_or_default();
+    let content = value.trim_end_matches('\n');
+    let chomp = match value.len() - content.len() {
+        0 => "-",
+        1 => "",
+        _ => return quoted(),
+    };
+    if !content.contains('\n') && chomp == "-" {
+        return serde_yaml::to_string(value)
+            .map(|yaml| yaml.trim_end().to_string())
+            .unwrap_or_else(|_| quoted());
+    }
+    // 最初の行が空白で始まる場合はインデントを明示する (親のキーより2つ深い)
+    let first = content.lines().find(|line| !line.is_empty()).unwrap_or("");
+    let indicator = if first.starts_with(' ') { "2" } else { "" };
+    let pad = " ".repeat(indent);
+    let lines: Vec<String> = content
+        .split('\n')
+        .map(|line| {
+            if line.is_empty() {
+                String::new()
+            } else {
+                format!("{}{}", pad, line)
+            }
+        })
+        .collect();
+    format!("|{}{}\n{}", indicator, chomp, lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{demote_headings, patch_config, strip_frontmatter};
+    use crate::model::parser;
+    use crate::model::types::{InstructionItem, InstructionItemVariant0Targets, Targets};
+    use std::path::Path;
+
+    #[test]
+    fn test_strip_frontmatter_and_demote_headings() {
+        let content = "---\ndescription: 説明\n---\n\n# タイトル\n\n本文\n";
+        let out = demote_headings(strip_frontmatter(content));
+        assert_eq!(out, "\n## タイトル\n\n本文\n");
+
+        // コードブロック内の `#` で始まる行は見出しではない
+        let content = "# Build\n\n```sh\n# install deps\nmake all\n```\n\n## Notes\n";
+        assert_eq!(
+            demote_headings(content),
+            "## Build\n\n```sh\n# install deps\nmake all\n```\n\n### Notes\n"
+        );
+    }
+
+    #[test]
+    fn test_patch_config_keeps_comments_and_key_order() {
+        let existing = r#"# 共通の設定
+version: '1.0.0'
+targets:
+  copilot: {}
+  cline: {}
+instructions:
+  # コーディング規約
+  - title: Style
+    body: |
+      Use rustfmt.
+    targets: [copilot]
+  - title: Group
+    instructions:
+      - body: 'Run tests.'
+        title: Testing
+# 末尾のコメント
+"#;
+        let mut config =
+            parser::parse_instrux_yaml_str(existing, Path::new("instrux.yaml")).unwrap();
+        if let InstructionItem::Variant2 { instructions, .. } = &mut config.instructions[1]
+            && let InstructionItem::Variant0 { body, .. } = &mut instructions[0]
+        {
+            *body = "Run tests.\nRun clippy.".to_string();
+        }
+        config.instructions.push(InstructionItem::Variant0 {
+            title: "Release: notes".to_string(),
+            body: "Write a changelog.".to_string(),
+            description: None,
+            applies_to: Vec::new(),
+            activation: None,
+            directory: None,
+            filename: None,
+            id: None,
+            disable: false,
+            targets: InstructionItemVariant0Targets::Variant0(vec![Targets::Cline.into()]),
+        });
+
+        // 変更した本文と追加したinstructionだけを書き換える
+        let updated = patch_config(existing, &config).unwrap();
+        assert_eq!(
+            updated,
+            r#"# 共通の設定
+version: '1.0.0'
+targets:
+  copilot: {}
+  cline: {}
+instructions:
+  # コーディング規約
+  - title: Style
+    body: |
+      Use rustfmt.
+    targets: [copilot]
+  - title: Group
+    instructions:
+      - body: |-
+          Run tests.
+          Run clippy.
+        title: Testing
+  - title: 'Release: notes'
+    body: Write a changelog.
+    targets: [cline]
+# 末尾のコメント
+"#
+        );
+    }
 }
@@ -1,39 +1,171 @@
-use crate::formats::{FormatResult, get_converter};
-use crate::model::types::{InstruxConfiguration, InstruxConfigurationTargetsValue, Targets};
+use crate::error::Result;
+use crate::formats::{self, FormatResult, ToFormat};
+use crate::manifest::{DEFAULT_MANIFEST_PATH, Manifest};
+use crate::model::types::{InstructionId, InstruxConfiguration, TargetName};
 use similar::{ChangeTag, TextDiff};
 use std::fs;
+use std::path::PathBuf;
 
 /// 指定されたターゲット形式との内容差分を取得
 pub fn diff_from_config(
     config: &InstruxConfiguration,
-    target: Targets,
-    target_config: &InstruxConfigurationTargetsValue,
-) -> Result<String, String> {
-    let converter = get_converter(&target, target_config);
+    target: &TargetName,
+    converter: &dyn ToFormat,
+) -> Result<String> {
     let expected_result = converter.to_format(config)?;
 
     match expected_result {
         FormatResult::Single(expected) => {
             let path = converter.get_default_path();
-            let current = fs::read_to_string(path).unwrap_or_default();
-            Ok(make_diff(&current, &expected))
+            let current = fs::read_to_string(&path).unwrap_or_default();
+            let mut result = make_diff(&current, &expected);
+            result.push_str(&orphan_diff(Some(target), &[path]));
+            Ok(result)
         }
         FormatResult::Multiple(files) => {
             // 複数ファイルの場合は一つの差分にまとめる
             let mut result = String::new();
+            let mut produced: Vec<PathBuf> =
+                files.iter().map(|(path, _)| PathBuf::from(path)).collect();
+            let mut renamed = renamed_orphans(target, &produced);
             for (file_path, expected_content) in files {
+                // 同じIDの孤立ファイルがあれば、タイトルの変更などによる名前の変更として表示する
+                let old = match fs::read_to_string(&file_path) {
+                    Ok(_) => None,
+                    Err(_) => formats::first_id(&expected_content)
+                        .and_then(|id| renamed.iter().position(|(orphan_id, _)| *orphan_id == id))
+                        .map(|index| renamed.remove(index).1),
+                };
+                if let Some(old) = old {
+                    let current = fs::read_to_string(&old).unwrap_or_default();
+                    result.push_str(&format!(
+                        "--- {} -> {} (名前の変更)\n",
+                        old.display(),
+                        file_path
+                    ));
+                    result.push_str(&make_diff(&current, &expected_content));
+                    result.push_str("\n\n");
+                    produced.push(old);
+                    continue;
+                }
                 let current = fs::read_to_string(&file_path).unwrap_or_default();
                 result.push_str(&format!("--- {}\n", file_path));
                 result.push_str(&make_diff(&current, &expected_content));
                 result.push_str("\n\n");
             }
+            result.push_str(&orphan_diff(Some(target), &produced));
             Ok(result)
         }
     }
 }
 
+/// 生成結果とディスク上のファイルの不一致の種類
+#[derive(Debug, PartialEq)]
+pub enum StaleKind {
+    /// ファイルが存在しない
+    Missing,
+    /// 内容が異なる
+    Changed,
+    /// 出力されなくなったファイルが残っている
+    Orphaned,
+}
+
+/// 最新でないファイル
+#[derive(Debug)]
+pub struct StaleFile {
+    pub path: PathBuf,
+    pub target: TargetName,
+    pub kind: StaleKind,
+}
+
+/// 全ターゲットを生成し、ディスク上のファイルと一致しないものを列挙する
+pub fn stale_files(config: &InstruxConfiguration) -> Result<Vec<StaleFile>> {
+    let mut stale = Vec::new();
+    let mut produced = Vec::new();
+    for (target, converter) in formats::converters(config) {
+        let result = converter.to_format(config)?;
+        result.check_paths(&target)?;
+        let files = match result {
+            FormatResult::Single(content) => vec![(converter.get_default_path(), content)],
+            FormatResult::Multiple(files) => files
+                .into_iter()
+                .map(|(path, content)| (PathBuf::from(path), content))
+                .collect(),
+        };
+        for (path, expected) in files {
+            let kind = match fs::read_to_string(&path) {
+                Err(_) => Some(StaleKind::Missing),
+                Ok(current) if current != expected => Some(StaleKind::Changed),
+                Ok(_) => None,
+            };
+            if let Some(kind) = kind {
+                stale.push(StaleFile {
+                    path: path.clone(),
+                    target: target.clone(),
+                    kind,
+                });
+            }
+            produced.push(path);
+        }
+    }
+
+    let manifest = Manifest::load(DEFAULT_MANIFEST_PATH)?;
+    for (path, target) in manifest.orphans(&produced) {
+        if path.is_file() {
+            stale.push(StaleFile {
+                path,
+                target,
+                kind: StaleKind::Orphaned,
+            });
+        }
+    }
+    Ok(stale)
+}
+
+/// 前回生成したが今回は出力されないファイル (generateで削除される) の差分を生成
+///
+/// `target` が `None` の場合は全ターゲットの記録を対象とする。
+pub fn orphan_diff(target: Option<&TargetName>, produced: &[PathBuf]) -> String {
+    let manifest = Manifest::load(DEFAULT_MANIFEST_PATH).unwrap_or_default();
+    let mut result = String::new();
+    for (path, orphan_target) in manifest.orphans(produced) {
+        if target.is_some_and(|t| *t != orphan_target) || !path.is_file() {
+            continue;
+        }
+        let current = fs::read_to_string(&path).unwrap_or_default();
+        // 管理ブロックだけを出力したファイルはブロックのみ削除される
+        if manifest.entry(&path).is_some_and(|entry| entry.managed) {
+            let rest = formats::remove_block(&current);
+            if !rest.trim().is_empty() {
+                result.push_str(&format!("--- {} (管理ブロックの削除)\n", path.display()));
+                result.push_str(&make_diff(&current, &rest));
+                result.push_str("\n\n");
+                continue;
+            }
+        }
+        result.push_str(&format!("--- {} (削除)\n", path.display()));
+        result.push_str(&make_diff(&current, ""));
+        result.push_str("\n\n");
+    }
+    result
+}
+
+/// 前回生成したが今回は出力されないファイルのうち、instructionのIDを含むもの (IDとパス)
+fn renamed_orphans(target: &TargetName, produced: &[PathBuf]) -> Vec<(InstructionId, PathBuf)> {
+    let manifest = Manifest::load(DEFAULT_MANIFEST_PATH).unwrap_or_default();
+    manifest
+        .orphans(produced)
+        .into_iter()
+        .filter(|(_, orphan_target)| orphan_target == target)
+        .filter_map(|(path, _)| {
+            let id = formats::first_id(&fs::read_to_string(&path).ok()?)?;
+            Some((id, path))
+        })
+        .collect()
+}
+
 /// 文字列同士の差分をANSIカラー付きで生成
-fn make_diff(current: &str, expected: &str) -> String {
+pub fn make_diff(current: &str, expected: &str) -> String {
     let diff = TextDiff::from_lines(current, expected);
     let mut out = String::new();
     for change in diff.iter_all_changes() {
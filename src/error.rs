@@ -0,0 +1,130 @@
+use crate::model::types::{TargetName, Targets};
+use std::io;
+use std::path::PathBuf;
+use thiserror::Error;
+
+/// 終了コード: 分類されないエラー (変換エラーなど)
+pub const EXIT_FAILURE: i32 = 1;
+/// 終了コード: コマンドライン引数の誤り (clapの引数エラーと同じ)
+pub const EXIT_USAGE: i32 = 2;
+/// 終了コード: 設定ファイルが存在しない・不正
+pub const EXIT_INVALID_CONFIG: i32 = 3;
+/// 終了コード: 生成結果が最新ではない (generate --check)
+pub const EXIT_STALE: i32 = 4;
+/// 終了コード: 競合・手動で変更されたファイル・既存ファイルがあり、処理を完了できない
+pub const EXIT_CONFLICT: i32 = 5;
+/// 終了コード: ファイルの読み書きに失敗
+pub const EXIT_IO: i32 = 6;
+
+/// instruxのエラー
+///
+/// 表示にはコマンド名の接頭辞 (`[generate]` など) を含まない。
+/// 接頭辞はmainでエラーを表示する際に付与する。
+#[derive(Debug, Error)]
+pub enum InstruxError {
+    /// 設定ファイルが存在しない
+    #[error("設定ファイルの読み込みに失敗: {} が見つかりません", .0.display())]
+    ConfigNotFound(PathBuf),
+
+    /// 設定ファイルのパースに失敗
+    #[error("設定ファイルの読み込みに失敗: {}: {message}", .path.display())]
+    Parse { path: PathBuf, message: String },
+
+    /// スキーマ検証・意味検証でエラーが見つかった
+    #[error("{errors}件のエラー、{warnings}件の警告があります")]
+    Schema { errors: usize, warnings: usize },
+
+    /// マニフェスト (.instrux/manifest.json) が不正
+    #[error("マニフェストの読み込みに失敗: {}: {message}", .path.display())]
+    Manifest { path: PathBuf, message: String },
+
+    /// ファイルの読み書きに失敗
+    #[error("{action}に失敗: {}: {source}", .path.display())]
+    Io {
+        /// 失敗した操作 (例: "ファイル出力")
+        action: &'static str,
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+
+    /// body_fileや出力先が許可された範囲 (sandbox) の外を指している
+    #[error("{} は {base} の外を指しています (sandbox.allow で許可できます)", .path.display())]
+    OutsideSandbox { path: PathBuf, base: String },
+
+    /// ターゲット形式との変換に失敗
+    #[error("{target}形式の変換に失敗: {message}")]
+    Conversion { target: Targets, message: String },
+
+    /// 1つのターゲットの複数のinstructionが同じファイルに出力される
+    #[error(
+        "{target}形式の出力先 {} に複数のinstructionが出力されます (タイトルまたは filename を変更してください)",
+        .path.display()
+    )]
+    DuplicateOutput { target: TargetName, path: PathBuf },
+
+    /// 上書きしないファイルが既に存在する
+    #[error("{} は既に存在します (--force で上書き)", .0.display())]
+    AlreadyExists(PathBuf),
+
+    /// 未知のツール名が指定された
+    #[error("未知のツール形式: {0}")]
+    UnknownTool(String),
+
+    /// 非推奨で変換できないターゲットが指定された
+    #[error("{0} は非推奨のため変換できません。agentsmd (AGENTS.md) を使用してください")]
+    DeprecatedTarget(Targets),
+
+    /// mergeで設定とツールファイルの両方が変更されていた
+    #[error("{0}件の競合があります。設定ファイルを手動で修正してください")]
+    Conflict(usize),
+
+    /// 手動で変更されたファイルを上書き・削除しなかった
+    #[error("手動で変更されたファイルを上書きしませんでした: {}", display_paths(.0))]
+    ModifiedFiles(Vec<PathBuf>),
+
+    /// generate --check で最新でないファイルが見つかった
+    #[error("{0}件のファイルが最新ではありません (instrux generate で再生成してください)")]
+    Stale(usize),
+}
+
+impl InstruxError {
+    /// ファイル操作のエラーを作成する
+    pub fn io(action: &'static str, path: impl Into<PathBuf>, source: io::Error) -> Self {
+        InstruxError::Io {
+            action,
+            path: path.into(),
+            source,
+        }
+    }
+
+    /// エラーの種類に応じた終了コード
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            InstruxError::ConfigNotFound(_)
+            | InstruxError::Parse { .. }
+            | InstruxError::Schema { .. }
+            | InstruxError::Manifest { .. }
+            | InstruxError::OutsideSandbox { .. }
+            | InstruxError::DuplicateOutput { .. } => EXIT_INVALID_CONFIG,
+            InstruxError::Io { .. } => EXIT_IO,
+            InstruxError::Conversion { .. } => EXIT_FAILURE,
+            InstruxError::UnknownTool(_) | InstruxError::DeprecatedTarget(_) => EXIT_USAGE,
+            InstruxError::Conflict(_)
+            | InstruxError::ModifiedFiles(_)
+            | InstruxError::AlreadyExists(_) => EXIT_CONFLICT,
+            InstruxError::Stale(_) => EXIT_STALE,
+        }
+    }
+}
+
+fn display_paths(paths: &[PathBuf]) -> String {
+    paths
+        .iter()
+        .map(|p| p.display().to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// instruxの処理結果
+pub type Result<T> = std::result::Result<T, InstruxError>;
@@ -1,44 +1,146 @@
 use super::{FormatResult, FromFormat, ToFormat, common};
+use crate::error::Result;
 use crate::formats::common::TargetsChecker;
 use crate::model::types::{InstructionItem, InstruxConfiguration, Targets};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
+const AGENTS_MD: &str = "AGENTS.md";
+
 /// Converter for AGENTS.md format
+///
+/// `directory` を指定したinstructionはそのディレクトリの AGENTS.md に、
+/// それ以外はルートの AGENTS.md に出力する。
 pub struct AgentsMdConverter {}
 
-impl ToFormat for AgentsMdConverter {
-    fn to_format(&self, config: &InstruxConfiguration) -> Result<FormatResult, String> {
+impl AgentsMdConverter {
+    fn is_target(item: &InstructionItem) -> bool {
+        match item {
+            InstructionItem::Variant0 { targets, .. } => targets.is_for_target(Targets::Agentsmd),
+            InstructionItem::Variant1 { targets, .. } => targets.is_for_target(Targets::Agentsmd),
+            InstructionItem::Variant2 { targets, .. } => targets.is_for_target(Targets::Agentsmd),
+        }
+    }
+
+    /// 1つの AGENTS.md の内容
+    fn render(instructions: &[InstructionItem]) -> Result<String> {
         let mut output = String::new();
         output.push_str("# Agents Instructions\n\n");
-        common::process_instructions_common(
-            &mut output,
-            &config.instructions,
-            0,
-            |item| match item {
-                InstructionItem::Variant0 { targets, .. } => {
-                    targets.is_for_target(Targets::Agentsmd)
-                }
-                InstructionItem::Variant1 { targets, .. } => {
-                    targets.is_for_target(Targets::Agentsmd)
+        common::process_instructions_common(&mut output, instructions, 0, Self::is_target)?;
+        Ok(output)
+    }
+
+    /// instructionを出力先ディレクトリ (ルートの場合は空文字列) ごとにまとめる
+    ///
+    /// `directory` のないinstructionは親 (グループ) の出力先を引き継ぐ。
+    /// グループは出力先ごとに、そこへ出力する子だけを持つグループとして複製する。
+    fn distribute(
+        instructions: &[InstructionItem],
+        inherited: &[String],
+        by_directory: &mut BTreeMap<String, Vec<InstructionItem>>,
+    ) {
+        for instruction in instructions {
+            let directories = match directory_of(instruction) {
+                Some(directory) => expand_directory(directory),
+                None => inherited.to_vec(),
+            };
+            // 一致するディレクトリがないglobはルートの AGENTS.md に出力する
+            let directories = if directories.is_empty() {
+                vec![String::new()]
+            } else {
+                directories
+            };
+            match instruction {
+                InstructionItem::Variant2 { instructions, .. } => {
+                    let mut children = BTreeMap::new();
+                    Self::distribute(instructions, &directories, &mut children);
+                    for (directory, children) in children {
+                        let mut group = instruction.clone();
+                        if let InstructionItem::Variant2 { instructions, .. } = &mut group {
+                            *instructions = children;
+                        }
+                        by_directory.entry(directory).or_default().push(group);
+                    }
                 }
-                InstructionItem::Variant2 { targets, .. } => {
-                    targets.is_for_target(Targets::Agentsmd)
+                _ => {
+                    for directory in directories {
+                        by_directory
+                            .entry(directory)
+                            .or_default()
+                            .push(instruction.clone());
+                    }
                 }
-            },
-        )?;
-        Ok(FormatResult::Single(output))
+            }
+        }
     }
+}
+
+impl ToFormat for AgentsMdConverter {
+    fn to_format(&self, config: &InstruxConfiguration) -> Result<FormatResult> {
+        // 出力先ディレクトリごとにinstructionをまとめる (設定での順序を保つ)
+        let mut by_directory = BTreeMap::new();
+        Self::distribute(&config.instructions, &[String::new()], &mut by_directory);
+        let root = by_directory.remove("").unwrap_or_default();
+        // ディレクトリ指定がなければ従来どおりルートの AGENTS.md のみ
+        if by_directory.is_empty() {
+            return Ok(FormatResult::Single(Self::render(&root)?));
+        }
 
+        let mut files = vec![(AGENTS_MD.to_string(), Self::render(&root)?)];
+        for (directory, instructions) in by_directory {
+            if !instructions.iter().any(Self::is_target) {
+                continue;
+            }
+            files.push((
+                format!("{}/{}", directory, AGENTS_MD),
+                Self::render(&instructions)?,
+            ));
+        }
+        Ok(FormatResult::Multiple(files))
+    }
+
+    /// ルートの AGENTS.md (ディレクトリごとに出力する場合も同じ)
     fn get_default_path(&self) -> PathBuf {
-        PathBuf::from("AGENTS.md")
+        PathBuf::from(AGENTS_MD)
+    }
+}
+
+fn directory_of(instruction: &InstructionItem) -> Option<&String> {
+    match instruction {
+        InstructionItem::Variant0 { directory, .. }
+        | InstructionItem::Variant1 { directory, .. }
+        | InstructionItem::Variant2 { directory, .. } => directory.as_ref(),
     }
 }
 
+/// `directory` の出力先ディレクトリ
+///
+/// globの場合は一致する既存のディレクトリすべてを返す (一致しなければ空)。
+pub fn expand_directory(directory: &str) -> Vec<String> {
+    let directory = directory.trim_matches('/');
+    if !directory.contains(['*', '?', '[']) {
+        return vec![directory.to_string()];
+    }
+    let mut directories: Vec<String> = glob::glob(directory)
+        .map(|paths| {
+            paths
+                .filter_map(|path| path.ok())
+                .filter(|path| path.is_dir())
+                .map(|path| path.to_string_lossy().replace('\\', "/"))
+                .collect()
+        })
+        .unwrap_or_default();
+    directories.sort();
+    directories
+}
+
 /// Parser for AgentsMD format
 pub struct AgentsMdParser {}
 
 impl FromFormat for AgentsMdParser {
-    fn from_format(content: &str) -> Result<Vec<InstructionItem>, String> {
-        common::parse_markdown_instructions(content, Targets::Agentsmd)
+    const TARGET: Targets = Targets::Agentsmd;
+
+    fn from_format(content: &str) -> Result<Vec<InstructionItem>> {
+        common::parse_markdown_instructions(content, Self::TARGET)
     }
 }
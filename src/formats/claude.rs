@@ -0,0 +1,92 @@
+use super::{FormatResult, FromFormat, ToFormat, common};
+use crate::error::Result;
+use crate::formats::common::TargetsChecker;
+use crate::model::types::{InstructionItem, InstruxConfiguration, Targets};
+use std::path::{Component, Path, PathBuf};
+
+/// Converter for Claude Code format (CLAUDE.md)
+pub struct ClaudeConverter {
+    /// body_fileを内容の代わりに `@.instrux/instructions/<file>` のインポート行として出力する
+    pub imports: bool,
+    /// 出力するファイルのディレクトリ (インポートのパスはここからの相対パス)
+    pub output_dir: PathBuf,
+}
+
+impl ClaudeConverter {
+    /// 既定の出力先 (リポジトリのルートのCLAUDE.md)
+    fn default_path() -> PathBuf {
+        PathBuf::from("CLAUDE.md")
+    }
+
+    /// 出力するファイルから見た `.instrux/instructions` のパス
+    fn instructions_dir(&self) -> String {
+        let mut path = PathBuf::new();
+        for component in self.output_dir.components() {
+            match component {
+                Component::Normal(_) => path.push(".."),
+                Component::ParentDir => {
+                    path.pop();
+                }
+                _ => {}
+            }
+        }
+        path.join(".instrux/instructions")
+            .to_string_lossy()
+            .replace('\\', "/")
+    }
+}
+
+impl ToFormat for ClaudeConverter {
+    fn to_format(&self, config: &InstruxConfiguration) -> Result<FormatResult> {
+        let mut output = String::new();
+        output.push_str("# CLAUDE.md\n\n");
+        let is_target = |item: &InstructionItem| match item {
+            InstructionItem::Variant0 { targets, .. } => targets.is_for_target(Targets::Claude),
+            InstructionItem::Variant1 { targets, .. } => targets.is_for_target(Targets::Claude),
+            InstructionItem::Variant2 { targets, .. } => targets.is_for_target(Targets::Claude),
+        };
+        if self.imports {
+            common::process_instructions_with_imports(
+                &mut output,
+                &config.instructions,
+                0,
+                is_target,
+                &self.instructions_dir(),
+            )?;
+        } else {
+            common::process_instructions_common(&mut output, &config.instructions, 0, is_target)?;
+        }
+        Ok(FormatResult::Single(output))
+    }
+
+    fn get_default_path(&self) -> PathBuf {
+        Self::default_path()
+    }
+}
+
+/// Parser for Claude Code format
+///
+/// `@パス` のインポートは参照先ファイルの内容に展開してから読み込む。
+pub struct ClaudeParser {}
+
+impl FromFormat for ClaudeParser {
+    const TARGET: Targets = Targets::Claude;
+
+    fn from_format(content: &str) -> Result<Vec<InstructionItem>> {
+        Self::from_file(content, &ClaudeConverter::default_path())
+    }
+}
+
+impl ClaudeParser {
+    /// `path` に置かれたCLAUDE.mdを読み込む
+    ///
+    /// インポートは `path` のディレクトリから解決し、リポジトリ (カレントディレクトリ) の外は読み込まない。
+    pub fn from_file(content: &str, path: &Path) -> Result<Vec<InstructionItem>> {
+        let dir = path
+            .parent()
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .unwrap_or(Path::new("."));
+        let content = common::resolve_imports_from(content, Path::new("."), dir);
+        common::parse_markdown_instructions(&content, Self::TARGET)
+    }
+}
@@ -1,13 +1,14 @@
 use super::common::{self, TargetsChecker};
 use super::{FormatResult, FromFormat, ToFormat};
+use crate::error::Result;
 use crate::model::types::{InstructionItem, InstruxConfiguration, Targets};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Converter for Cline format (.clinerules)
 pub struct ClineConverter {}
 
 impl ToFormat for ClineConverter {
-    fn to_format(&self, config: &InstruxConfiguration) -> Result<FormatResult, String> {
+    fn to_format(&self, config: &InstruxConfiguration) -> Result<FormatResult> {
         let mut output = String::new();
 
         // Header for Cline format
@@ -37,7 +38,14 @@ impl ToFormat for ClineConverter {
 pub struct ClineParser {}
 
 impl FromFormat for ClineParser {
-    fn from_format(content: &str) -> Result<Vec<InstructionItem>, String> {
-        common::parse_markdown_instructions(content, Targets::Cline)
+    const TARGET: Targets = Targets::Cline;
+
+    fn from_format(content: &str) -> Result<Vec<InstructionItem>> {
+        common::parse_markdown_instructions(content, Self::TARGET)
+    }
+
+    fn is_rule_file(path: &Path) -> bool {
+        path.extension()
+            .is_some_and(|ext| ext == "md" || ext == "txt")
     }
 }
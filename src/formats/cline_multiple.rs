@@ -1,64 +1,21 @@
-use crate::formats::common::TargetsChecker;
+use crate::error::Result;
+use crate::formats::common::{self, TargetsChecker};
 use crate::formats::{FormatResult, ToFormat};
-use crate::model::types::{InstructionItem, InstruxConfiguration, Targets};
-use std::collections::HashMap;
+use crate::model::types::{FilenameStyle, InstructionItem, InstruxConfiguration, Targets};
 use std::path::PathBuf;
 
 /// ClineMultipleConverter
 /// outputMode=multipleの場合、各InstructionItemを個別ファイル(.clinerules/*.md)として出力するコンバータ
-pub struct ClineMultipleConverter {}
-
-impl ClineMultipleConverter {
-    /// ネストされたInstructionItemを再帰的にMarkdown化
-    fn instruction_to_md(instruction: &InstructionItem, level: usize) -> String {
-        match instruction {
-            InstructionItem::Variant0 { title, body, .. } => {
-                format!(
-                    "{hashes} {title}\n\n{body}\n",
-                    hashes = "#".repeat(level),
-                    title = title,
-                    body = body
-                )
-            }
-            InstructionItem::Variant1 {
-                title, body_file, ..
-            } => {
-                let path = format!(".instrux/instructions/{}", body_file);
-                let file_content = std::fs::read_to_string(&path).unwrap_or_else(|_| {
-                    format!("<!-- Content from file: {body_file} (not found) -->\n")
-                });
-                format!(
-                    "{hashes} {title}\n\n{content}\n",
-                    hashes = "#".repeat(level),
-                    title = title,
-                    content = file_content
-                )
-            }
-            InstructionItem::Variant2 {
-                title,
-                instructions: nested,
-                ..
-            } => {
-                let nested_md: String = nested
-                    .iter()
-                    .map(|nested_item| Self::instruction_to_md(nested_item, level + 1))
-                    .collect();
-                format!(
-                    "{hashes} {title}\n\n{nested}\n",
-                    hashes = "#".repeat(level),
-                    title = title,
-                    nested = nested_md
-                )
-            }
-        }
-    }
+pub struct ClineMultipleConverter {
+    /// タイトルからファイル名を決める方法
+    pub filenames: FilenameStyle,
 }
 
 impl ToFormat for ClineMultipleConverter {
     /// 複数ファイル形式で出力する
     /// 戻り値: FormatResult::Multiple(ファイルパス→内容のマップ)
-    fn to_format(&self, config: &InstruxConfiguration) -> Result<FormatResult, String> {
-        let mut files = HashMap::new();
+    fn to_format(&self, config: &InstruxConfiguration) -> Result<FormatResult> {
+        let mut files = Vec::new();
         for instruction in &config.instructions {
             // Clineターゲットのみ対象
             let is_cline = match instruction {
@@ -75,14 +32,21 @@ impl ToFormat for ClineMultipleConverter {
             if !is_cline {
                 continue;
             }
-            let title = match instruction {
-                InstructionItem::Variant0 { title, .. } => title,
-                InstructionItem::Variant1 { title, .. } => title,
-                InstructionItem::Variant2 { title, .. } => title,
+            let stem = common::file_stem(instruction, self.filenames);
+            // 対象ファイルはClineの条件付きルール (paths) としてfrontmatterに出力する
+            let (_, applies_to, _) = common::metadata(instruction);
+            let frontmatter = if applies_to.is_empty() {
+                String::new()
+            } else {
+                let paths: String = applies_to
+                    .iter()
+                    .map(|glob| format!("  - \"{}\"\n", glob))
+                    .collect();
+                format!("---\npaths:\n{}---\n\n", paths)
             };
-            let content = Self::instruction_to_md(instruction, 1);
-            let file_path = format!(".clinerules/{}.md", title);
-            files.insert(file_path, content);
+            let content = common::instruction_to_md(instruction, 1);
+            let file_path = format!(".clinerules/{}.md", stem);
+            files.push((file_path, format!("{}{}", frontmatter, content)));
         }
         Ok(FormatResult::Multiple(files))
     }
@@ -1,47 +1,106 @@
+use crate::error::{InstruxError, Result};
+use crate::manifest::hash_content;
 use crate::model::types::{
-    InstructionItem, InstructionItemVariant0Targets, InstructionItemVariant1Targets,
-    InstructionItemVariant2Targets, Targets,
+    Activation, FilenameStyle, InstructionId, InstructionItem, InstructionItemVariant0Targets,
+    InstructionItemVariant1Targets, InstructionItemVariant2Targets, TargetName, Targets,
 };
+use crate::sandbox;
+use pulldown_cmark::{Event, HeadingLevel, Options, Parser, Tag, TagEnd};
+use serde::Deserialize;
+use std::fs;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
 
 /// 各ターゲットバリアント型に対するターゲット判定用トレイト
 /// 任意のターゲットに対して有効かどうかを判定する
 pub trait TargetsChecker {
     /// 指定ターゲット向けか判定
-    fn is_for_target(&self, target: Targets) -> bool;
+    fn is_for_target(&self, target: Targets) -> bool {
+        self.is_for(&TargetName::Targets(target))
+    }
+
+    /// 組み込みターゲット・カスタムターゲットのいずれかの名前で、そのターゲット向けか判定
+    fn is_for(&self, name: &TargetName) -> bool;
 }
 
 impl TargetsChecker for InstructionItemVariant0Targets {
-    fn is_for_target(&self, target: Targets) -> bool {
+    fn is_for(&self, name: &TargetName) -> bool {
         match self {
-            InstructionItemVariant0Targets::Variant0(list) => list.contains(&target),
+            InstructionItemVariant0Targets::Variant0(list) => list.contains(name),
             InstructionItemVariant0Targets::Variant1(s) => s == "all",
         }
     }
 }
 impl TargetsChecker for InstructionItemVariant1Targets {
-    fn is_for_target(&self, target: Targets) -> bool {
+    fn is_for(&self, name: &TargetName) -> bool {
         match self {
-            InstructionItemVariant1Targets::Variant0(list) => list.contains(&target),
+            InstructionItemVariant1Targets::Variant0(list) => list.contains(name),
             InstructionItemVariant1Targets::Variant1(s) => s == "all",
         }
     }
 }
 impl TargetsChecker for InstructionItemVariant2Targets {
-    fn is_for_target(&self, target: Targets) -> bool {
+    fn is_for(&self, name: &TargetName) -> bool {
         match self {
-            InstructionItemVariant2Targets::Variant0(list) => list.contains(&target),
+            InstructionItemVariant2Targets::Variant0(list) => list.contains(name),
             InstructionItemVariant2Targets::Variant1(s) => s == "all",
         }
     }
 }
 
 /// 共通: 再帰的にInstructionItemをMarkdown出力
+///
+/// 単一ファイルでは対象ファイルを指定できないため、applies_to は本文の前に
+/// `When editing files matching ...:` の文として出力する。
 pub fn process_instructions_common<F>(
     output: &mut String,
     instructions: &[InstructionItem],
     level: usize,
     is_target: F,
-) -> Result<(), String>
+) -> Result<()>
+where
+    F: Fn(&InstructionItem) -> bool + Copy,
+{
+    process_instructions(output, instructions, level, is_target, None)
+}
+
+/// 共通: body_fileを読み込むパス
+///
+/// `.instrux/instructions` からの相対パスとし、`sandbox.allow` で許可した絶対パスはそのまま使う
+/// (sandboxの検証と同じパスを指す)。
+pub fn body_file_path(body_file: &str) -> PathBuf {
+    Path::new(sandbox::INSTRUCTIONS_DIR).join(body_file)
+}
+
+/// 共通: 再帰的にInstructionItemをMarkdown出力し、body_fileは `@パス` のインポート行とする
+///
+/// `instructions_dir` は出力するファイルから見た `.instrux/instructions` のパス。
+pub fn process_instructions_with_imports<F>(
+    output: &mut String,
+    instructions: &[InstructionItem],
+    level: usize,
+    is_target: F,
+    instructions_dir: &str,
+) -> Result<()>
+where
+    F: Fn(&InstructionItem) -> bool + Copy,
+{
+    process_instructions(
+        output,
+        instructions,
+        level,
+        is_target,
+        Some(instructions_dir),
+    )
+}
+
+fn process_instructions<F>(
+    output: &mut String,
+    instructions: &[InstructionItem],
+    level: usize,
+    is_target: F,
+    imports: Option<&str>,
+) -> Result<()>
 where
     F: Fn(&InstructionItem) -> bool + Copy,
 {
@@ -57,6 +116,8 @@ where
                     continue;
                 }
                 output.push_str(&format!("{} {}\n\n", "#".repeat(level + 2), title));
+                output.push_str(&id_marker(instruction));
+                output.push_str(&scope_preamble(metadata(instruction).1));
                 output.push_str(body);
                 output.push_str("\n\n");
             }
@@ -70,8 +131,18 @@ where
                     continue;
                 }
                 output.push_str(&format!("{} {}\n\n", "#".repeat(level + 2), title));
+                output.push_str(&id_marker(instruction));
+                output.push_str(&scope_preamble(metadata(instruction).1));
                 // .instrux/instructions/ 配下のファイル内容を読み込む
-                let path = format!(".instrux/instructions/{}", body_file);
+                let path = body_file_path(body_file);
+                if let Some(instructions_dir) = imports {
+                    let import = Path::new(instructions_dir).join(body_file);
+                    output.push_str(&format!(
+                        "@{}\n\n",
+                        import.to_string_lossy().replace('\\', "/")
+                    ));
+                    continue;
+                }
                 match std::fs::read_to_string(&path) {
                     Ok(content) => output.push_str(&content),
                     Err(_) => output.push_str(&format!(
@@ -91,65 +162,716 @@ where
                     continue;
                 }
                 output.push_str(&format!("{} {}\n\n", "#".repeat(level + 2), title));
-                process_instructions_common(output, nested, level + 1, is_target)?;
+                output.push_str(&id_marker(instruction));
+                output.push_str(&scope_preamble(metadata(instruction).1));
+                process_instructions(output, nested, level + 1, is_target, imports)?;
             }
         }
     }
     Ok(())
 }
 
-/// 共通: Markdownヘッダ区切りでInstructionItemを抽出
-pub fn parse_markdown_instructions(
-    content: &str,
-    target: Targets,
-) -> Result<Vec<InstructionItem>, String> {
+/// 生成ファイルに埋め込むinstructionのIDの目印 (`<!-- instrux:id=<id> -->`) の書き出しと終わり
+const ID_MARKER_START: &str = "<!-- instrux:id=";
+const ID_MARKER_END: &str = " -->";
+
+/// 共通: instructionのIDを見出しの直後に置くHTMLコメントにする (IDがなければ空)
+///
+/// ツールには表示されず、インポート時にIDを復元するために使う。
+pub fn id_marker(instruction: &InstructionItem) -> String {
+    let id = match instruction {
+        InstructionItem::Variant0 { id, .. }
+        | InstructionItem::Variant1 { id, .. }
+        | InstructionItem::Variant2 { id, .. } => id,
+    };
+    match id {
+        Some(id) => format!("{}{}{}\n\n", ID_MARKER_START, id.as_str(), ID_MARKER_END),
+        None => String::new(),
+    }
+}
+
+/// 行がIDの目印であればIDを返す
+fn parse_id_marker(line: &str) -> Option<InstructionId> {
+    line.trim()
+        .strip_prefix(ID_MARKER_START)?
+        .strip_suffix(ID_MARKER_END)?
+        .parse()
+        .ok()
+}
+
+/// 本文の先頭がIDの目印であれば、IDと残りの本文に分ける
+fn split_id_marker(body: &str) -> (Option<InstructionId>, &str) {
+    let (first, rest) = body.split_once('\n').unwrap_or((body, ""));
+    match parse_id_marker(first) {
+        Some(id) => (Some(id), rest.trim_start_matches('\n')),
+        None => (None, body),
+    }
+}
+
+/// 共通: 生成されたファイルの内容で最初に現れるinstructionのID
+///
+/// 複数ファイル形式では各ファイルの最上位のinstructionのIDになる。
+pub fn first_id(content: &str) -> Option<InstructionId> {
+    content.lines().find_map(parse_id_marker)
+}
+
+/// 対象ファイルを指定する仕組みがない形式で、applies_to の代わりに本文の前に出力する文の書き出し
+const SCOPE_PREAMBLE: &str = "When editing files matching ";
+
+/// 共通: applies_to を本文の前に置く文にする (`When editing files matching `src/**/*.rs`:`)
+pub fn scope_preamble(applies_to: &[String]) -> String {
+    if applies_to.is_empty() {
+        return String::new();
+    }
+    let globs: Vec<String> = applies_to
+        .iter()
+        .map(|glob| format!("`{}`", glob))
+        .collect();
+    format!("{}{}:\n\n", SCOPE_PREAMBLE, globs.join(", "))
+}
+
+/// 本文の先頭が scope_preamble の文であれば、globの一覧と残りの本文に分ける
+fn split_scope_preamble(body: &str) -> (Vec<String>, &str) {
+    let (first, rest) = body.split_once('\n').unwrap_or((body, ""));
+    let Some(globs) = first
+        .strip_prefix(SCOPE_PREAMBLE)
+        .and_then(|globs| globs.strip_suffix(':'))
+    else {
+        return (Vec::new(), body);
+    };
+    let globs: Option<Vec<String>> = globs
+        .split(", ")
+        .map(|glob| {
+            glob.strip_prefix('`')
+                .and_then(|glob| glob.strip_suffix('`'))
+                .map(str::to_string)
+        })
+        .collect();
+    match globs {
+        Some(globs) => (globs, rest.trim_start_matches('\n')),
+        None => (Vec::new(), body),
+    }
+}
+
+/// 共通: 複数ファイル形式の1ファイル分として、ネストされたInstructionItemを再帰的にMarkdown化
+///
+/// ファイルの最上位 (level 1) の applies_to は各形式のfrontmatterで指定するため、
+/// 入れ子のinstructionのみ applies_to を本文の前の文として出力する。
+pub fn instruction_to_md(instruction: &InstructionItem, level: usize) -> String {
+    let scope = if level > 1 {
+        scope_preamble(metadata(instruction).1)
+    } else {
+        String::new()
+    };
+    let scope = format!("{}{}", id_marker(instruction), scope);
+    match instruction {
+        InstructionItem::Variant0 { title, body, .. } => {
+            format!(
+                "{hashes} {title}\n\n{scope}{body}\n",
+                hashes = "#".repeat(level),
+                title = title,
+                scope = scope,
+                body = body
+            )
+        }
+        InstructionItem::Variant1 {
+            title, body_file, ..
+        } => {
+            let path = body_file_path(body_file);
+            let file_content = std::fs::read_to_string(&path).unwrap_or_else(|_| {
+                format!("<!-- Content from file: {body_file} (not found) -->\n")
+            });
+            format!(
+                "{hashes} {title}\n\n{scope}{content}\n",
+                hashes = "#".repeat(level),
+                title = title,
+                scope = scope,
+                content = file_content
+            )
+        }
+        InstructionItem::Variant2 {
+            title,
+            instructions: nested,
+            ..
+        } => {
+            let nested_md: String = nested
+                .iter()
+                .map(|nested_item| instruction_to_md(nested_item, level + 1))
+                .collect();
+            format!(
+                "{hashes} {title}\n\n{scope}{nested}\n",
+                hashes = "#".repeat(level),
+                title = title,
+                scope = scope,
+                nested = nested_md
+            )
+        }
+    }
+}
+
+/// 共通: タイトルをファイル名に使えるスラッグにする
+///
+/// 英数字 (日本語などの文字を含む) 以外を `-` にまとめ、英字は小文字にする。
+pub fn slug(title: &str) -> String {
+    let slug = slugify(title, char::is_alphanumeric);
+    if slug.is_empty() {
+        "instruction".to_string()
+    } else {
+        slug
+    }
+}
+
+/// `keep` を満たす文字以外を `-` にまとめ、英字を小文字にする (空になる場合もある)
+fn slugify(title: &str, keep: fn(char) -> bool) -> String {
+    let mut slug = String::new();
+    for c in title.chars().flat_map(char::to_lowercase) {
+        if keep(c) {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_end_matches('-').to_string()
+}
+
+/// 共通: 複数ファイル形式でinstructionを出力するファイル名 (拡張子なし)
+///
+/// instructionに `filename` があればそれを使い、なければタイトルから `style` に従って決める。
+pub fn file_stem(instruction: &InstructionItem, style: FilenameStyle) -> String {
+    let (title, filename) = match instruction {
+        InstructionItem::Variant0 {
+            title, filename, ..
+        }
+        | InstructionItem::Variant1 {
+            title, filename, ..
+        }
+        | InstructionItem::Variant2 {
+            title, filename, ..
+        } => (title, filename),
+    };
+    if let Some(filename) = filename {
+        return filename.to_string();
+    }
+    match style {
+        FilenameStyle::Title => safe_title(title),
+        FilenameStyle::Slug => slug(title),
+        FilenameStyle::Ascii => {
+            let slug = slugify(title, |c| c.is_ascii_alphanumeric());
+            if slug.is_empty() {
+                // ASCIIの文字を含まないタイトル (日本語など) はハッシュから名前を作る
+                format!("rule-{}", &hash_content(title)[..8])
+            } else {
+                slug
+            }
+        }
+    }
+}
+
+/// ファイル名に使えない文字 (パス区切りなど) を `-` に置き換えたタイトル
+///
+/// 先頭の `.` は取り除き、`..` などで出力先ディレクトリの外に出ないようにする。
+fn safe_title(title: &str) -> String {
+    let name: String = title
+        .chars()
+        .map(|c| {
+            if c.is_control() || matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|') {
+                '-'
+            } else {
+                c
+            }
+        })
+        .collect();
+    let name = name
+        .trim()
+        .trim_start_matches('.')
+        .trim_end_matches(['.', ' ']);
+    if name.is_empty() {
+        "instruction".to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+/// `@パス` のインポートを展開する最大の深さ (Claude Code と同じ)
+const MAX_IMPORT_DEPTH: usize = 5;
+
+/// 共通: `@パス` だけの行を参照先ファイルの内容で置き換える
+///
+/// パスはインポート元ファイルのディレクトリからの相対パスとし、参照先のインポートも再帰的に展開する。
+/// コードブロック内の行と、読み込めないパス・`base_dir` の外を指すパスの行はそのまま残す。
+pub fn resolve_imports(content: &str, base_dir: &Path) -> String {
+    resolve_imports_at(content, base_dir, base_dir, 0)
+}
+
+/// 共通: `base_dir` からの相対パスで `@パス` の行を展開する (`root` の外を指すパスは展開しない)
+pub fn resolve_imports_from(content: &str, root: &Path, base_dir: &Path) -> String {
+    resolve_imports_at(content, root, base_dir, 0)
+}
+
+fn resolve_imports_at(content: &str, root: &Path, base_dir: &Path, depth: usize) -> String {
+    if depth >= MAX_IMPORT_DEPTH {
+        return content.to_string();
+    }
+    let code_blocks: Vec<Range<usize>> = Parser::new(content)
+        .into_offset_iter()
+        .filter_map(|(event, range)| {
+            matches!(event, Event::Start(Tag::CodeBlock(_))).then_some(range)
+        })
+        .collect();
+    let mut output = String::new();
+    let mut offset = 0;
+    for line in content.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+        if !code_blocks.iter().any(|block| block.contains(&start))
+            && let Some(path) = import_path(line)
+        {
+            let path = base_dir.join(path);
+            if sandbox::is_within(&path, root)
+                && let Ok(imported) = fs::read_to_string(&path)
+            {
+                let dir = path.parent().unwrap_or(base_dir);
+                output.push_str(resolve_imports_at(&imported, root, dir, depth + 1).trim_end());
+                if line.ends_with('\n') {
+                    output.push('\n');
+                }
+                continue;
+            }
+        }
+        output.push_str(line);
+    }
+    output
+}
+
+/// `@パス` だけの行であればパスを返す
+fn import_path(line: &str) -> Option<&str> {
+    let path = line.trim().strip_prefix('@')?;
+    (!path.is_empty() && !path.contains(char::is_whitespace)).then_some(path)
+}
+
+/// Markdownの見出しで区切ったセクション
+struct Section {
+    level: usize,
+    title: String,
+    body: String,
+}
+
+/// 文書タイトルのないプリアンブルに付けるタイトル
+const PREAMBLE_TITLE: &str = "Overview";
+
+/// 共通: Markdownの見出し構造からInstructionItemを抽出
+///
+/// - コードブロック内の `#` は見出しとして扱わず、本文は元の文字列のまま保持する
+/// - 見出しの深さに応じて子を持つセクションは `Variant2` にまとめる
+///   (子より前の本文は同じタイトルの最初の子とする)
+/// - 唯一のレベル1見出しの後に下位の見出しが続く場合は文書タイトルとみなし、
+///   最初のセクションより前の本文 (プリアンブル) を文書タイトルの instruction とする
+/// - 先頭のYAML frontmatterは本文に含めない
+pub fn parse_markdown_instructions(content: &str, target: Targets) -> Result<Vec<InstructionItem>> {
+    let options = Options::ENABLE_YAML_STYLE_METADATA_BLOCKS;
+    let mut headings = Vec::new();
+    let mut preamble_start = 0;
+    let mut current: Option<(usize, usize, String)> = None;
+    for (event, range) in Parser::new_ext(content, options).into_offset_iter() {
+        match event {
+            Event::Start(Tag::MetadataBlock(_)) => preamble_start = range.end,
+            Event::Start(Tag::Heading { level, .. }) => {
+                current = Some((level as usize, range.start, String::new()));
+            }
+            Event::End(TagEnd::Heading(_)) => {
+                if let Some((level, start, title)) = current.take() {
+                    headings.push((level, start, range.end, title.trim().to_string()));
+                }
+            }
+            Event::Text(text) => {
+                if let Some((_, _, title)) = current.as_mut() {
+                    title.push_str(&text);
+                }
+            }
+            Event::Code(code) => {
+                if let Some((_, _, title)) = current.as_mut() {
+                    title.push_str(&format!("`{}`", code));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // 文書タイトル (唯一のレベル1見出しで、下位の見出しが続くもの)
+    let is_document_title = headings.len() > 1
+        && headings[0].0 == 1
+        && headings.iter().filter(|h| h.0 == 1).count() == 1;
+    let document_title = if is_document_title {
+        let (_, _, end, title) = headings.remove(0);
+        preamble_start = preamble_start.max(end);
+        Some(title)
+    } else {
+        None
+    };
+
     let mut instructions = Vec::new();
+    let first_start = headings.first().map_or(content.len(), |h| h.1);
+    let preamble = trim_blank_lines(content.get(preamble_start..first_start).unwrap_or(""));
+    if !preamble.is_empty() {
+        let title = document_title.unwrap_or_else(|| PREAMBLE_TITLE.to_string());
+        instructions.push(section_item(&title, &preamble, target));
+    }
+
     let mut sections = Vec::new();
-    let mut current_section = String::new();
-    let mut current_level = 0;
-    let mut current_title = String::new();
-
-    for line in content.lines() {
-        if line.starts_with('#') {
-            let level = line.chars().take_while(|&c| c == '#').count();
-            let title = line.trim_start_matches('#').trim().to_string();
-            if !current_title.is_empty() {
-                sections.push((
-                    current_level,
-                    current_title.clone(),
-                    current_section.clone(),
-                ));
-                current_section.clear();
-            }
-            current_level = level;
-            current_title = title;
+    for (i, (level, _, end, title)) in headings.iter().enumerate() {
+        let next = headings.get(i + 1).map_or(content.len(), |h| h.1);
+        sections.push(Section {
+            level: *level,
+            title: title.clone(),
+            body: trim_blank_lines(&content[*end..next]),
+        });
+    }
+
+    instructions.extend(nest_sections(&sections, target));
+    if instructions.is_empty() {
+        return Err(InstruxError::Conversion {
+            target,
+            message: "No valid instructions found in the format file".to_string(),
+        });
+    }
+    Ok(instructions)
+}
+
+/// 見出しの深さに応じてセクションを入れ子のInstructionItemにする
+fn nest_sections(sections: &[Section], target: Targets) -> Vec<InstructionItem> {
+    let mut items = Vec::new();
+    let mut i = 0;
+    while i < sections.len() {
+        let section = &sections[i];
+        let end = sections[i + 1..]
+            .iter()
+            .position(|s| s.level <= section.level)
+            .map_or(sections.len(), |p| i + 1 + p);
+        let children = &sections[i + 1..end];
+        if children.is_empty() {
+            items.push(section_item(&section.title, &section.body, target));
         } else {
-            current_section.push_str(line);
-            current_section.push('\n');
+            let (id, intro) = split_id_marker(&section.body);
+            let (applies_to, intro) = split_scope_preamble(intro);
+            let mut nested = Vec::new();
+            if !intro.is_empty() {
+                nested.push(section_item(&section.title, intro, target));
+            }
+            nested.extend(nest_sections(children, target));
+            items.push(InstructionItem::Variant2 {
+                title: section.title.clone(),
+                instructions: nested,
+                description: None,
+                applies_to,
+                activation: None,
+                directory: None,
+                filename: None,
+                id,
+                disable: false,
+                targets: InstructionItemVariant2Targets::Variant0(vec![target.into()]),
+            });
         }
+        i = end;
     }
-    if !current_title.is_empty() {
-        sections.push((
-            current_level,
-            current_title.clone(),
-            current_section.clone(),
-        ));
+    items
+}
+
+fn section_item(title: &str, body: &str, target: Targets) -> InstructionItem {
+    let (id, body) = split_id_marker(body);
+    let (applies_to, body) = split_scope_preamble(body);
+    InstructionItem::Variant0 {
+        title: title.to_string(),
+        body: body.to_string(),
+        description: None,
+        applies_to,
+        activation: None,
+        directory: None,
+        filename: None,
+        id,
+        disable: false,
+        targets: InstructionItemVariant0Targets::Variant0(vec![target.into()]),
     }
-    for (level, title, body) in sections {
-        if level <= 1 {
-            continue;
+}
+
+/// 前後の空行を取り除く (インデントされたコードブロックのため行頭の空白は保持する)
+fn trim_blank_lines(text: &str) -> String {
+    let start: usize = text
+        .split_inclusive('\n')
+        .take_while(|line| line.trim().is_empty())
+        .map(str::len)
+        .sum();
+    text[start..].trim_end().to_string()
+}
+
+/// ルールファイルのYAML frontmatter (Copilot / Cursor / Cline / Windsurf 共通)
+#[derive(Deserialize, Default, Debug)]
+#[serde(rename_all = "camelCase")]
+struct Frontmatter {
+    description: Option<String>,
+    /// Copilot: カンマ区切りのglob
+    apply_to: Option<Globs>,
+    /// Cursor: カンマ区切りのglobまたはその配列
+    globs: Option<Globs>,
+    /// Cursor: 常に適用するか
+    always_apply: Option<bool>,
+    /// Windsurf: 適用するタイミング
+    trigger: Option<Trigger>,
+    /// Cline: 条件付きルールの対象ファイルのglob
+    paths: Option<Globs>,
+}
+
+/// Windsurfのルールの適用タイミング
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Trigger {
+    AlwaysOn,
+    Manual,
+    ModelDecision,
+    Glob,
+}
+
+impl Trigger {
+    /// Windsurfのfrontmatterに出力する値
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Trigger::AlwaysOn => "always_on",
+            Trigger::Manual => "manual",
+            Trigger::ModelDecision => "model_decision",
+            Trigger::Glob => "glob",
         }
-        let instruction = InstructionItem::Variant0 {
-            title,
-            body: body.trim().to_string(),
-            description: None,
-            disable: false,
-            targets: InstructionItemVariant0Targets::Variant0(vec![target]),
+    }
+}
+
+impl From<Trigger> for Activation {
+    fn from(trigger: Trigger) -> Self {
+        match trigger {
+            Trigger::AlwaysOn => Activation::Always,
+            Trigger::Manual => Activation::Manual,
+            Trigger::ModelDecision => Activation::Agent,
+            Trigger::Glob => Activation::Glob,
+        }
+    }
+}
+
+impl From<Activation> for Trigger {
+    fn from(activation: Activation) -> Self {
+        match activation {
+            Activation::Always => Trigger::AlwaysOn,
+            Activation::Manual => Trigger::Manual,
+            Activation::Agent => Trigger::ModelDecision,
+            Activation::Glob => Trigger::Glob,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+enum Globs {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl Globs {
+    fn patterns(&self) -> Vec<String> {
+        let patterns: Vec<&str> = match self {
+            Globs::One(s) => s.split(',').collect(),
+            Globs::Many(list) => list.iter().map(String::as_str).collect(),
         };
-        instructions.push(instruction);
+        patterns
+            .into_iter()
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+            .map(str::to_string)
+            .collect()
     }
-    if instructions.is_empty() {
-        return Err("No valid instructions found in the format file".to_string());
+}
+
+/// 先頭のYAML frontmatterと本文に分割する
+pub fn split_frontmatter(content: &str) -> (Option<&str>, &str) {
+    let Some(rest) = content
+        .strip_prefix("---\n")
+        .or_else(|| content.strip_prefix("---\r\n"))
+    else {
+        return (None, content);
+    };
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        if line.trim_end() == "---" {
+            return (Some(&rest[..offset]), &rest[offset + line.len()..]);
+        }
+        offset += line.len();
     }
-    Ok(instructions)
+    (None, content)
+}
+
+/// ルールファイル1つを1つのInstructionItemに変換する
+///
+/// タイトルは本文先頭のレベル1見出し、なければファイル名とする。
+/// frontmatterの description / applyTo / globs / paths / alwaysApply / trigger は
+/// description / applies_to / activation に対応付ける。
+pub fn parse_rule_file(path: &Path, content: &str, target: Targets) -> Result<InstructionItem> {
+    let (yaml, body) = split_frontmatter(content);
+    let frontmatter: Frontmatter = match yaml {
+        Some(yaml) if !yaml.trim().is_empty() => serde_yaml::from_str(&quote_glob_values(yaml))
+            .map_err(|e| InstruxError::Conversion {
+                target,
+                message: format!("{} のfrontmatterが不正です: {}", path.display(), e),
+            })?,
+        _ => Frontmatter::default(),
+    };
+
+    let (title, body) = match leading_title(body) {
+        Some((title, rest)) => (title, rest),
+        None => (rule_file_title(path), body),
+    };
+    let (id, body) = split_id_marker(body.trim_start_matches('\n'));
+    let applies_to: Vec<String> = frontmatter
+        .apply_to
+        .iter()
+        .chain(frontmatter.globs.iter())
+        .chain(frontmatter.paths.iter())
+        .flat_map(Globs::patterns)
+        .collect();
+    let activation = match frontmatter.always_apply {
+        _ if frontmatter.trigger.is_some() => frontmatter.trigger.map(Activation::from),
+        Some(true) => Some(Activation::Always),
+        _ if !applies_to.is_empty() && frontmatter.globs.is_some() => Some(Activation::Glob),
+        Some(false) if frontmatter.description.is_some() => Some(Activation::Agent),
+        Some(false) => Some(Activation::Manual),
+        None => None,
+    };
+
+    Ok(InstructionItem::Variant0 {
+        title,
+        body: trim_blank_lines(body),
+        description: frontmatter.description,
+        applies_to,
+        activation,
+        directory: None,
+        filename: None,
+        id,
+        disable: false,
+        targets: InstructionItemVariant0Targets::Variant0(vec![target.into()]),
+    })
+}
+
+/// `globs: *.ts` のように `*` で始まる値を引用符で囲む
+///
+/// Cursor / Windsurf はglobを引用符なしで書くが、YAMLでは `*` がエイリアスとして解釈されるため。
+fn quote_glob_values(yaml: &str) -> String {
+    yaml.split_inclusive('\n')
+        .map(|line| {
+            if let Some((key, value)) = line.split_once(':')
+                && matches!(key.trim(), "globs" | "applyTo")
+                && value.trim_start().starts_with('*')
+            {
+                let value = value.trim();
+                let newline = &line[line.trim_end().len()..];
+                return format!("{}: '{}'{}", key, value.replace('\'', "''"), newline);
+            }
+            line.to_string()
+        })
+        .collect()
+}
+
+/// instructionのメタデータ (description, applies_to, activation)
+pub fn metadata(instruction: &InstructionItem) -> (Option<&str>, &[String], Option<Activation>) {
+    match instruction {
+        InstructionItem::Variant0 {
+            description,
+            applies_to,
+            activation,
+            ..
+        }
+        | InstructionItem::Variant1 {
+            description,
+            applies_to,
+            activation,
+            ..
+        }
+        | InstructionItem::Variant2 {
+            description,
+            applies_to,
+            activation,
+            ..
+        } => (description.as_deref(), applies_to, *activation),
+    }
+}
+
+/// 本文の先頭がレベル1見出しであれば、そのタイトルと残りの本文を返す
+fn leading_title(body: &str) -> Option<(String, &str)> {
+    let mut events = Parser::new(body).into_offset_iter();
+    let (first, range) = events.next()?;
+    if !matches!(
+        first,
+        Event::Start(Tag::Heading {
+            level: HeadingLevel::H1,
+            ..
+        })
+    ) || !body[..range.start].trim().is_empty()
+    {
+        return None;
+    }
+    let mut title = String::new();
+    for (event, _) in events {
+        match event {
+            Event::Text(text) => title.push_str(&text),
+            Event::Code(code) => title.push_str(&format!("`{}`", code)),
+            Event::End(TagEnd::Heading(_)) => break,
+            _ => {}
+        }
+    }
+    Some((title.trim().to_string(), &body[range.end..]))
+}
+
+/// ファイル名からタイトルを作る (`style.instructions.md` → `style`)
+fn rule_file_title(path: &Path) -> String {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    [".instructions.md", ".mdc", ".md", ".txt"]
+        .iter()
+        .find_map(|ext| name.strip_suffix(ext))
+        .unwrap_or(&name)
+        .to_string()
+}
+
+/// ディレクトリ配下のルールファイルをパス順に読み込み、1ファイルを1つのInstructionItemとする
+pub fn parse_rule_dir(
+    dir: &Path,
+    target: Targets,
+    is_rule_file: fn(&Path) -> bool,
+) -> Result<Vec<InstructionItem>> {
+    let mut files = Vec::new();
+    collect_files(dir, is_rule_file, &mut files)?;
+    files.sort();
+    files
+        .iter()
+        .map(|path| {
+            let content = fs::read_to_string(path)
+                .map_err(|e| InstruxError::io("ファイル読み込み", path, e))?;
+            parse_rule_file(path, &content, target)
+        })
+        .collect()
+}
+
+fn collect_files(
+    dir: &Path,
+    is_rule_file: fn(&Path) -> bool,
+    files: &mut Vec<PathBuf>,
+) -> Result<()> {
+    let entries =
+        fs::read_dir(dir).map_err(|e| InstruxError::io("ディレクトリ読み込み", dir, e))?;
+    for entry in entries {
+        let path = entry
+            .map_err(|e| InstruxError::io("ディレクトリ読み込み", dir, e))?
+            .path();
+        if path.is_dir() {
+            collect_files(&path, is_rule_file, files)?;
+        } else if is_rule_file(&path) {
+            files.push(path);
+        }
+    }
+    Ok(())
 }
@@ -1,14 +1,15 @@
 use super::{FormatResult, FromFormat, ToFormat};
+use crate::error::Result;
 use crate::formats::common;
 use crate::formats::common::TargetsChecker;
 use crate::model::types::{InstructionItem, InstruxConfiguration, Targets};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Converter for Copilot format (copilot-instructions.md)
 pub struct CopilotConverter {}
 
 impl ToFormat for CopilotConverter {
-    fn to_format(&self, config: &InstruxConfiguration) -> Result<FormatResult, String> {
+    fn to_format(&self, config: &InstruxConfiguration) -> Result<FormatResult> {
         let mut output = String::new();
 
         // Add header section with metadata
@@ -43,7 +44,14 @@ impl ToFormat for CopilotConverter {
 pub struct CopilotParser {}
 
 impl FromFormat for CopilotParser {
-    fn from_format(content: &str) -> Result<Vec<InstructionItem>, String> {
-        common::parse_markdown_instructions(content, Targets::Copilot)
+    const TARGET: Targets = Targets::Copilot;
+
+    fn from_format(content: &str) -> Result<Vec<InstructionItem>> {
+        common::parse_markdown_instructions(content, Self::TARGET)
+    }
+
+    fn is_rule_file(path: &Path) -> bool {
+        path.file_name()
+            .is_some_and(|name| name.to_string_lossy().ends_with(".instructions.md"))
     }
 }
@@ -1,16 +1,17 @@
 use super::{FormatResult, FromFormat, ToFormat};
+use crate::error::Result;
 use crate::formats::common::TargetsChecker;
 use crate::{
     formats::common,
     model::types::{InstructionItem, InstruxConfiguration, Targets},
 };
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Converter for Cursor format (.cursor/rules)
 pub struct CursorConverter {}
 
 impl ToFormat for CursorConverter {
-    fn to_format(&self, config: &InstruxConfiguration) -> Result<FormatResult, String> {
+    fn to_format(&self, config: &InstruxConfiguration) -> Result<FormatResult> {
         let mut output = String::new();
         let header = r#"---
 description: Project Rules
@@ -43,7 +44,13 @@ alwaysApply: true
 pub struct CursorParser {}
 
 impl FromFormat for CursorParser {
-    fn from_format(content: &str) -> Result<Vec<InstructionItem>, String> {
-        common::parse_markdown_instructions(content, Targets::Junie)
+    const TARGET: Targets = Targets::Cursor;
+
+    fn from_format(content: &str) -> Result<Vec<InstructionItem>> {
+        common::parse_markdown_instructions(content, Self::TARGET)
+    }
+
+    fn is_rule_file(path: &Path) -> bool {
+        path.extension().is_some_and(|ext| ext == "mdc")
     }
 }
@@ -0,0 +1,85 @@
+use crate::error::Result;
+use crate::formats::common::{self, TargetsChecker};
+use crate::formats::{FormatResult, ToFormat};
+use crate::model::types::{
+    Activation, FilenameStyle, InstructionItem, InstruxConfiguration, Targets,
+};
+use std::path::PathBuf;
+
+/// CursorMultipleConverter
+/// outputMode=multipleの場合、各InstructionItemを個別のルールファイル(.cursor/rules/*.mdc)として出力するコンバータ
+pub struct CursorMultipleConverter {
+    /// タイトルからファイル名を決める方法
+    pub filenames: FilenameStyle,
+}
+
+impl CursorMultipleConverter {
+    /// ルールファイルのfrontmatter (description / globs / alwaysApply)
+    ///
+    /// ルールの種類はactivationで決まり、未指定時はapplies_toがあればAuto Attached、なければAlwaysとする。
+    /// - Always: alwaysApply: true
+    /// - Auto Attached (glob): globs に applies_to を出力
+    /// - Agent Requested (agent): description のみ出力
+    /// - Manual: description / globs を出力しない
+    fn frontmatter(instruction: &InstructionItem) -> String {
+        let (description, applies_to, activation) = common::metadata(instruction);
+        let activation = activation.unwrap_or(if applies_to.is_empty() {
+            Activation::Always
+        } else {
+            Activation::Glob
+        });
+        let description = match activation {
+            Activation::Manual => None,
+            _ => description,
+        };
+        let globs = match activation {
+            Activation::Glob => applies_to.join(","),
+            _ => String::new(),
+        };
+        format!(
+            "---\ndescription: {}\nglobs: {}\nalwaysApply: {}\n---\n\n",
+            description.unwrap_or_default(),
+            globs,
+            activation == Activation::Always
+        )
+    }
+}
+
+impl ToFormat for CursorMultipleConverter {
+    /// 複数ファイル形式で出力する
+    /// 戻り値: FormatResult::Multiple(ファイルパス→内容のマップ)
+    fn to_format(&self, config: &InstruxConfiguration) -> Result<FormatResult> {
+        let mut files = Vec::new();
+        for instruction in &config.instructions {
+            // Cursorターゲットのみ対象
+            let is_cursor = match instruction {
+                InstructionItem::Variant0 {
+                    targets, disable, ..
+                } => !*disable && targets.is_for_target(Targets::Cursor),
+                InstructionItem::Variant1 {
+                    targets, disable, ..
+                } => !*disable && targets.is_for_target(Targets::Cursor),
+                InstructionItem::Variant2 {
+                    targets, disable, ..
+                } => !*disable && targets.is_for_target(Targets::Cursor),
+            };
+            if !is_cursor {
+                continue;
+            }
+            let stem = common::file_stem(instruction, self.filenames);
+            let content = common::instruction_to_md(instruction, 1);
+            let file_path = format!(".cursor/rules/{}.mdc", stem);
+            files.push((
+                file_path,
+                format!("{}{}", Self::frontmatter(instruction), content),
+            ));
+        }
+        Ok(FormatResult::Multiple(files))
+    }
+
+    /// Get the default file path for the target format
+    /// 複数ファイル出力の場合は格納ディレクトリを返す
+    fn get_default_path(&self) -> PathBuf {
+        PathBuf::from(".cursor/rules")
+    }
+}
@@ -0,0 +1,132 @@
+use super::common::{self, TargetsChecker};
+use super::{FormatResult, ToFormat};
+use crate::error::Result;
+use crate::model::types::{
+    CustomTarget, CustomTargetName, CustomTargetOutputMode, FilenameStyle, InstructionItem,
+    InstruxConfiguration, TargetName,
+};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Converter for user-defined targets (`custom` in instrux.yaml)
+///
+/// 出力先・ヘッダー・ファイル名・frontmatterを設定から受け取り、組み込みのconverterと
+/// 同じMarkdown出力を行う。
+pub struct CustomConverter {
+    /// ターゲット名 (instructionのtargetsで指定する名前)
+    pub name: CustomTargetName,
+    /// 出力設定
+    pub target: CustomTarget,
+}
+
+impl CustomConverter {
+    /// このカスタムターゲット向けのinstructionか判定
+    fn is_target(&self, instruction: &InstructionItem) -> bool {
+        let name = TargetName::CustomTargetName(self.name.clone());
+        match instruction {
+            InstructionItem::Variant0 {
+                targets, disable, ..
+            } => !*disable && targets.is_for(&name),
+            InstructionItem::Variant1 {
+                targets, disable, ..
+            } => !*disable && targets.is_for(&name),
+            InstructionItem::Variant2 {
+                targets, disable, ..
+            } => !*disable && targets.is_for(&name),
+        }
+    }
+
+    /// ヘッダーを出力する (末尾の空行は1行にそろえる)
+    fn push_header(&self, output: &mut String) {
+        if let Some(header) = &self.target.header {
+            output.push_str(header.trim_end());
+            output.push_str("\n\n");
+        }
+    }
+
+    /// 1ファイル分のfrontmatterを出力する (値が空になるフィールドは省略)
+    fn frontmatter(&self, instruction: &InstructionItem, title: &str) -> String {
+        let (description, applies_to, activation) = common::metadata(instruction);
+        let applies_to = applies_to.join(",");
+        let activation = activation.map(|a| a.to_string()).unwrap_or_default();
+        let vars = [
+            ("title", title),
+            ("description", description.unwrap_or_default()),
+            ("applies_to", applies_to.as_str()),
+            ("activation", activation.as_str()),
+        ];
+        // 出力を安定させるためキー順に並べる
+        let fields: BTreeMap<&String, String> = self
+            .target
+            .frontmatter
+            .iter()
+            .map(|(key, template)| (key, render(template, &vars)))
+            .filter(|(_, value)| !value.trim().is_empty())
+            .collect();
+        if fields.is_empty() {
+            return String::new();
+        }
+        let fields: String = fields
+            .iter()
+            .map(|(key, value)| format!("{}: {}\n", key, value))
+            .collect();
+        format!("---\n{}---\n\n", fields)
+    }
+}
+
+impl ToFormat for CustomConverter {
+    fn to_format(&self, config: &InstruxConfiguration) -> Result<FormatResult> {
+        if self.target.output_mode == CustomTargetOutputMode::Single {
+            let mut output = String::new();
+            self.push_header(&mut output);
+            common::process_instructions_common(&mut output, &config.instructions, 0, |item| {
+                self.is_target(item)
+            })?;
+            return Ok(FormatResult::Single(output));
+        }
+
+        // outputMode=multipleの場合は最上位のinstructionごとに1ファイルを出力する
+        let mut files = Vec::new();
+        for instruction in &config.instructions {
+            if !self.is_target(instruction) {
+                continue;
+            }
+            let title = match instruction {
+                InstructionItem::Variant0 { title, .. }
+                | InstructionItem::Variant1 { title, .. }
+                | InstructionItem::Variant2 { title, .. } => title,
+            };
+            let filename = file_name(&self.target, instruction);
+            let mut content = self.frontmatter(instruction, title);
+            self.push_header(&mut content);
+            content.push_str(&common::instruction_to_md(instruction, 1));
+            let file_path = Path::new(&self.target.path).join(filename);
+            files.push((file_path.to_string_lossy().into_owned(), content));
+        }
+        Ok(FormatResult::Multiple(files))
+    }
+
+    /// 単一ファイルの出力先、または複数ファイルの格納ディレクトリ
+    fn get_default_path(&self) -> PathBuf {
+        PathBuf::from(&self.target.path)
+    }
+}
+
+/// multipleモードでinstructionを出力するファイル名 (`filename` のテンプレートを展開したもの)
+pub fn file_name(target: &CustomTarget, instruction: &InstructionItem) -> String {
+    // instructionのfilenameがあれば {{slug}} / {{title}} のどちらもfilenameになる
+    let slug = common::file_stem(instruction, FilenameStyle::Slug);
+    let title = common::file_stem(instruction, FilenameStyle::Title);
+    render(
+        &target.filename,
+        &[("slug", slug.as_str()), ("title", title.as_str())],
+    )
+}
+
+/// テンプレート中の `{{name}}` を値に置き換える
+fn render(template: &str, vars: &[(&str, &str)]) -> String {
+    vars.iter()
+        .fold(template.to_string(), |text, (name, value)| {
+            text.replace(&format!("{{{{{}}}}}", name), value)
+        })
+}
@@ -0,0 +1,150 @@
+use super::{FormatResult, FromFormat, ToFormat, common};
+use crate::error::Result;
+use crate::formats::common::TargetsChecker;
+use crate::model::types::{FilenameStyle, InstructionItem, InstruxConfiguration, Targets};
+use std::path::{Path, PathBuf};
+
+/// 分割したグループのファイルを出力するディレクトリ (既定)
+pub const IMPORT_DIR: &str = ".gemini";
+
+/// Converter for Gemini CLI format (GEMINI.md)
+pub struct GeminiConverter {
+    /// ネストされたグループ (Variant2) を .gemini/ 配下の別ファイルに出力し、`@パス` でインポートする
+    pub imports: bool,
+    /// 分割したグループのファイルを出力するディレクトリ
+    pub import_dir: String,
+    /// タイトルからグループのファイル名を決める方法
+    pub filenames: FilenameStyle,
+}
+
+impl GeminiConverter {
+    fn is_target(item: &InstructionItem) -> bool {
+        match item {
+            InstructionItem::Variant0 { targets, .. } => targets.is_for_target(Targets::Gemini),
+            InstructionItem::Variant1 { targets, .. } => targets.is_for_target(Targets::Gemini),
+            InstructionItem::Variant2 { targets, .. } => targets.is_for_target(Targets::Gemini),
+        }
+    }
+
+    /// グループのファイル名 (拡張子なし)
+    ///
+    /// `@パス` は空白で終わるため、タイトルの空白は `-` に置き換える。
+    fn group_stem(&self, instruction: &InstructionItem) -> String {
+        common::file_stem(instruction, self.filenames)
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join("-")
+    }
+
+    /// instructionsを出力し、グループは別ファイルとしてfilesに追加する
+    ///
+    /// # 引数
+    /// * `file_dir` - 出力中のファイルのディレクトリ (インポートのパスはここからの相対パス)
+    /// * `group_dir` - この階層のグループのファイルを出力するディレクトリ
+    fn render(
+        &self,
+        output: &mut String,
+        instructions: &[InstructionItem],
+        level: usize,
+        file_dir: &str,
+        group_dir: &str,
+        files: &mut Vec<(String, String)>,
+    ) -> Result<()> {
+        for instruction in instructions {
+            let InstructionItem::Variant2 {
+                title,
+                instructions: nested,
+                disable,
+                ..
+            } = instruction
+            else {
+                common::process_instructions_common(
+                    output,
+                    std::slice::from_ref(instruction),
+                    level,
+                    Self::is_target,
+                )?;
+                continue;
+            };
+            if *disable || !Self::is_target(instruction) {
+                continue;
+            }
+            let stem = self.group_stem(instruction);
+            let file_path = format!("{}/{}.md", group_dir, stem);
+            let import_path = Path::new(&file_path)
+                .strip_prefix(file_dir)
+                .unwrap_or(Path::new(&file_path));
+            output.push_str(&format!("{} {}\n\n", "#".repeat(level + 2), title));
+            output.push_str(&common::id_marker(instruction));
+            output.push_str(&common::scope_preamble(common::metadata(instruction).1));
+            output.push_str(&format!("@{}\n\n", import_path.display()));
+
+            // 親のグループのファイルを子のグループより先に並べる
+            let index = files.len();
+            files.push((file_path, String::new()));
+            let mut content = String::new();
+            self.render(
+                &mut content,
+                nested,
+                level + 1,
+                group_dir,
+                &format!("{}/{}", group_dir, stem),
+                files,
+            )?;
+            files[index].1 = content;
+        }
+        Ok(())
+    }
+}
+
+impl ToFormat for GeminiConverter {
+    fn to_format(&self, config: &InstruxConfiguration) -> Result<FormatResult> {
+        let mut output = String::new();
+        output.push_str("# GEMINI.md\n\n");
+        if !self.imports {
+            common::process_instructions_common(
+                &mut output,
+                &config.instructions,
+                0,
+                Self::is_target,
+            )?;
+            return Ok(FormatResult::Single(output));
+        }
+
+        let mut files = Vec::new();
+        self.render(
+            &mut output,
+            &config.instructions,
+            0,
+            "",
+            &self.import_dir,
+            &mut files,
+        )?;
+        files.insert(0, ("GEMINI.md".to_string(), output));
+        Ok(FormatResult::Multiple(files))
+    }
+
+    /// インポートを使う場合はグループのファイルを出力するディレクトリを返す
+    fn get_default_path(&self) -> PathBuf {
+        if self.imports {
+            PathBuf::from(&self.import_dir)
+        } else {
+            PathBuf::from("GEMINI.md")
+        }
+    }
+}
+
+/// Parser for Gemini CLI format
+///
+/// `@パス` のインポートを再帰的に展開し、1つのMarkdownとして読み込む。
+pub struct GeminiParser {}
+
+impl FromFormat for GeminiParser {
+    const TARGET: Targets = Targets::Gemini;
+
+    fn from_format(content: &str) -> Result<Vec<InstructionItem>> {
+        // GEMINI.mdはリポジトリのルートに置かれるため、インポートはカレントディレクトリから解決する
+        let content = common::resolve_imports(content, Path::new("."));
+        common::parse_markdown_instructions(&content, Self::TARGET)
+    }
+}
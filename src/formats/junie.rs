@@ -1,5 +1,6 @@
 use super::common;
 use super::{FormatResult, FromFormat, ToFormat};
+use crate::error::Result;
 use crate::formats::common::TargetsChecker;
 use crate::model::types::{InstructionItem, InstruxConfiguration, Targets};
 use std::path::PathBuf;
@@ -8,7 +9,7 @@ use std::path::PathBuf;
 pub struct JunieConverter {}
 
 impl ToFormat for JunieConverter {
-    fn to_format(&self, config: &InstruxConfiguration) -> Result<FormatResult, String> {
+    fn to_format(&self, config: &InstruxConfiguration) -> Result<FormatResult> {
         let mut output = String::new();
 
         // Header
@@ -37,7 +38,9 @@ impl ToFormat for JunieConverter {
 pub struct JunieParser {}
 
 impl FromFormat for JunieParser {
-    fn from_format(content: &str) -> Result<Vec<InstructionItem>, String> {
-        common::parse_markdown_instructions(content, Targets::Junie)
+    const TARGET: Targets = Targets::Junie;
+
+    fn from_format(content: &str) -> Result<Vec<InstructionItem>> {
+        common::parse_markdown_instructions(content, Self::TARGET)
     }
 }
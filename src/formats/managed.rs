@@ -0,0 +1,113 @@
+use super::common::split_frontmatter;
+use super::{FormatResult, ToFormat};
+use crate::error::Result;
+use crate::model::types::InstruxConfiguration;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// instruxが管理するブロックの開始行
+pub const BLOCK_BEGIN: &str = "<!-- instrux:begin -->";
+/// instruxが管理するブロックの終了行
+pub const BLOCK_END: &str = "<!-- instrux:end -->";
+
+/// 出力をファイル内の管理ブロックだけに書き込むconverter (`managed: block`)
+///
+/// ディスク上のファイルの `<!-- instrux:begin -->` 〜 `<!-- instrux:end -->` の間を生成結果で置き換え、
+/// ブロックがなければ末尾に追加する。ブロックの外の手書きの内容はそのまま残す。
+/// 生成結果のfrontmatter (copilot の `applyTo` など) はツールが読めるようにファイルの先頭に書き出す。
+pub struct ManagedBlock {
+    pub inner: Box<dyn ToFormat>,
+}
+
+impl ToFormat for ManagedBlock {
+    fn to_format(&self, config: &InstruxConfiguration) -> Result<FormatResult> {
+        let splice_file = |path: &Path, generated: &str| {
+            let current = fs::read_to_string(path).unwrap_or_default();
+            splice(&current, generated)
+        };
+        Ok(match self.inner.to_format(config)? {
+            FormatResult::Single(output) => {
+                FormatResult::Single(splice_file(&self.get_default_path(), &output))
+            }
+            FormatResult::Multiple(files) => FormatResult::Multiple(
+                files
+                    .into_iter()
+                    .map(|(path, content)| {
+                        let content = splice_file(Path::new(&path), &content);
+                        (path, content)
+                    })
+                    .collect(),
+            ),
+        })
+    }
+
+    fn get_default_path(&self) -> PathBuf {
+        self.inner.get_default_path()
+    }
+
+    fn max_chars(&self) -> Option<usize> {
+        self.inner.max_chars()
+    }
+
+    fn is_block_managed(&self) -> bool {
+        true
+    }
+}
+
+/// 管理ブロック (開始行から終了行まで) の範囲
+fn block_range(content: &str) -> Option<(usize, usize)> {
+    let start = content.find(BLOCK_BEGIN)?;
+    let end = start + content[start..].find(BLOCK_END)? + BLOCK_END.len();
+    Some((start, end))
+}
+
+/// 内容の管理ブロックを生成結果で置き換える (ブロックがなければ末尾に追加する)
+///
+/// 生成結果にfrontmatterがある場合は、既存のfrontmatterを置き換えてブロックの外 (ファイルの先頭) に置く。
+pub fn splice(current: &str, generated: &str) -> String {
+    let (frontmatter, body) = split_frontmatter(generated);
+    if frontmatter.is_some() {
+        let header = &generated[..generated.len() - body.len()];
+        let (_, current) = split_frontmatter(current);
+        let spliced = splice_block(
+            current.trim_start_matches('\n'),
+            body.trim_start_matches('\n'),
+        );
+        return format!("{}\n{}", header, spliced);
+    }
+    splice_block(current, generated)
+}
+
+fn splice_block(current: &str, generated: &str) -> String {
+    let block = format!("{}\n{}\n{}", BLOCK_BEGIN, generated.trim_end(), BLOCK_END);
+    match block_range(current) {
+        Some((start, end)) => format!("{}{}{}", &current[..start], block, &current[end..]),
+        None if current.trim().is_empty() => format!("{}\n", block),
+        None => format!("{}\n\n{}\n", current.trim_end(), block),
+    }
+}
+
+/// 内容の管理ブロック (開始行・終了行を含む)
+pub fn managed_block(content: &str) -> Option<&str> {
+    block_range(content).map(|(start, end)| &content[start..end])
+}
+
+/// 内容から管理ブロックを取り除く (ブロックの前後の空行も1つにまとめる)
+///
+/// ブロックのほかにfrontmatterしか残らない場合は空にする。
+pub fn remove_block(content: &str) -> String {
+    let Some((start, end)) = block_range(content) else {
+        return content.to_string();
+    };
+    let mut before = content[..start].trim_end();
+    if split_frontmatter(&content[..start]).1.trim().is_empty() {
+        before = "";
+    }
+    let after = content[end..].trim_start();
+    match (before.is_empty(), after.is_empty()) {
+        (true, true) => String::new(),
+        (true, false) => after.to_string(),
+        (false, true) => format!("{}\n", before),
+        (false, false) => format!("{}\n\n{}", before, after),
+    }
+}
@@ -1,82 +1,438 @@
+use crate::error::{InstruxError, Result};
 use crate::model::types::{
-    InstructionItem, InstruxConfiguration, InstruxConfigurationTargetsValue,
-    InstruxConfigurationTargetsValueOutputMode, Targets,
+    CustomTarget, CustomTargetName, CustomTargetOutputMode, InstructionItem, InstruxConfiguration,
+    InstruxConfigurationTargetsValue, InstruxConfigurationTargetsValueOutputMode, ManagedMode,
+    TargetName, Targets,
 };
-use std::collections::HashMap;
-use std::path::PathBuf;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 
 mod agentsmd;
+mod claude;
 mod cline;
 mod cline_multiple;
 mod common;
 mod copilot;
 mod copilot_multiple;
 mod cursor;
+mod cursor_multiple;
+mod custom;
+mod gemini;
 mod junie;
+mod managed;
+mod windsurf;
+mod windsurf_multiple;
 
 #[cfg(test)]
 mod tests;
 
+pub use agentsmd::expand_directory;
+pub use common::first_id;
+pub use managed::{managed_block, remove_block};
+
 /// フォーマット変換結果を表す型
-/// 単一ファイルの場合は単一の文字列、複数ファイルの場合はパス→内容のマップ
+/// 単一ファイルの場合は単一の文字列、複数ファイルの場合は (パス, 内容) の一覧
 pub enum FormatResult {
     /// 単一ファイルの内容
     Single(String),
-    /// 複数ファイルの内容 (ファイルパス→内容のマップ)
-    Multiple(HashMap<String, String>),
+    /// 複数ファイルの内容 (ファイルパスと内容の組を設定のinstructionの順に並べたもの)
+    Multiple(Vec<(String, String)>),
+}
+
+impl FormatResult {
+    /// 複数ファイルの出力先が重複していないか確認する
+    ///
+    /// 同じパスに出力すると後のinstructionで上書きされるため、書き込む前にエラーにする。
+    /// 大文字・小文字のみ異なるパスも、区別しないファイルシステムで衝突するため重複とみなす。
+    pub fn check_paths(&self, target: &TargetName) -> Result<()> {
+        let FormatResult::Multiple(files) = self else {
+            return Ok(());
+        };
+        let mut seen = HashSet::new();
+        for (path, _) in files {
+            if !seen.insert(path.to_lowercase()) {
+                return Err(InstruxError::DuplicateOutput {
+                    target: target.clone(),
+                    path: PathBuf::from(path),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// multipleモードで最上位のinstructionを出力するファイル名 (拡張子を除く)
+///
+/// ルールファイルを複数出力するモードでないターゲットはNone
+pub fn rule_file_stem(
+    target: &Targets,
+    target_config: &InstruxConfigurationTargetsValue,
+    instruction: &InstructionItem,
+) -> Option<String> {
+    if rule_dir(target).is_none()
+        || target_config.output_mode != InstruxConfigurationTargetsValueOutputMode::Multiple
+    {
+        return None;
+    }
+    Some(common::file_stem(
+        instruction,
+        target_config.filenames.unwrap_or_default(),
+    ))
+}
+
+/// custom ターゲットのmultipleモードで最上位のinstructionを出力するファイル名 (singleモードはNone)
+pub fn custom_file_name(target: &CustomTarget, instruction: &InstructionItem) -> Option<String> {
+    if target.output_mode != CustomTargetOutputMode::Multiple {
+        return None;
+    }
+    Some(custom::file_name(target, instruction))
 }
 
 /// Trait for converting from the instrux model to a target format
 pub trait ToFormat {
     /// Convert from instrux model to the target format
     /// outputModeなどに応じて単一ファイルまたは複数ファイルを返す
-    fn to_format(&self, config: &InstruxConfiguration) -> Result<FormatResult, String>;
+    fn to_format(&self, config: &InstruxConfiguration) -> Result<FormatResult>;
 
     /// Get the default file path for the target format
     /// Single結果の場合のパス、Multiple結果の場合はベースディレクトリ
     fn get_default_path(&self) -> PathBuf;
+
+    /// ツールが読み込む1ファイルあたりの最大文字数 (制限がなければNone)
+    fn max_chars(&self) -> Option<usize> {
+        None
+    }
+
+    /// ファイル全体ではなく管理ブロック (`managed: block`) だけを出力するか
+    fn is_block_managed(&self) -> bool {
+        false
+    }
 }
 
-/// Factory to get the converter for a specific target, outputModeも考慮
+/// Factory to get the converter for a specific target, outputMode・pathも考慮
 pub fn get_converter(
     target: &Targets,
     target_config: &InstruxConfigurationTargetsValue,
+) -> Box<dyn ToFormat> {
+    let converter = builtin_converter(target, target_config);
+    let converter: Box<dyn ToFormat> = match &target_config.path {
+        Some(path) => Box::new(Relocated {
+            inner: converter,
+            path: PathBuf::from(path),
+        }),
+        None => converter,
+    };
+    let converter: Box<dyn ToFormat> = if target_config.output_mode
+        == InstruxConfigurationTargetsValueOutputMode::Multiple
+        && target_config.index_prefix == Some(true)
+    {
+        Box::new(IndexPrefixed { inner: converter })
+    } else {
+        converter
+    };
+    managed(converter, target_config.managed.unwrap_or_default())
+}
+
+/// `managed: block` の場合は出力を管理ブロックに書き込むconverterにする
+fn managed(converter: Box<dyn ToFormat>, mode: ManagedMode) -> Box<dyn ToFormat> {
+    match mode {
+        ManagedMode::File => converter,
+        ManagedMode::Block => Box::new(managed::ManagedBlock { inner: converter }),
+    }
+}
+
+/// 各ツールの既定の出力先に出力するconverter
+fn builtin_converter(
+    target: &Targets,
+    target_config: &InstruxConfigurationTargetsValue,
 ) -> Box<dyn ToFormat> {
     match target {
         Targets::Copilot => {
             if target_config.output_mode == InstruxConfigurationTargetsValueOutputMode::Multiple {
-                return Box::new(copilot_multiple::CopilotMultipleConverter {});
+                return Box::new(copilot_multiple::CopilotMultipleConverter {
+                    filenames: target_config.filenames.unwrap_or_default(),
+                });
             }
             Box::new(copilot::CopilotConverter {})
         }
         Targets::Cline => {
             if target_config.output_mode == InstruxConfigurationTargetsValueOutputMode::Multiple {
-                return Box::new(cline_multiple::ClineMultipleConverter {});
+                return Box::new(cline_multiple::ClineMultipleConverter {
+                    filenames: target_config.filenames.unwrap_or_default(),
+                });
             }
             Box::new(cline::ClineConverter {})
         }
-        Targets::Cursor => Box::new(cursor::CursorConverter {}),
+        Targets::Cursor => {
+            if target_config.output_mode == InstruxConfigurationTargetsValueOutputMode::Multiple {
+                return Box::new(cursor_multiple::CursorMultipleConverter {
+                    filenames: target_config.filenames.unwrap_or_default(),
+                });
+            }
+            Box::new(cursor::CursorConverter {})
+        }
         Targets::Junie => Box::new(junie::JunieConverter {}),
         Targets::Agentsmd => Box::new(agentsmd::AgentsMdConverter {}),
-        Targets::Codex => unreachable!("Codex is deprecated. Use agentsmd (AGENTS.md) instead."),
+        Targets::Claude => Box::new(claude::ClaudeConverter {
+            imports: target_config.imports.unwrap_or(false),
+            // インポートのパスは出力するファイルからの相対パスになるため、pathのディレクトリを渡す
+            output_dir: target_config
+                .path
+                .as_deref()
+                .and_then(|path| Path::new(path).parent())
+                .map(Path::to_path_buf)
+                .unwrap_or_default(),
+        }),
+        Targets::Gemini => Box::new(gemini::GeminiConverter {
+            imports: target_config.imports.unwrap_or(false),
+            // インポートのパスはグループのファイルの出力先から決まるため、pathはconverterに渡す
+            import_dir: target_config
+                .path
+                .clone()
+                .unwrap_or_else(|| gemini::IMPORT_DIR.to_string()),
+            filenames: target_config.filenames.unwrap_or_default(),
+        }),
+        Targets::Windsurf => {
+            if target_config.output_mode == InstruxConfigurationTargetsValueOutputMode::Multiple {
+                return Box::new(windsurf_multiple::WindsurfMultipleConverter {
+                    filenames: target_config.filenames.unwrap_or_default(),
+                });
+            }
+            Box::new(windsurf::WindsurfConverter {})
+        }
+        Targets::Codex => Box::new(Deprecated { target: *target }),
+    }
+}
+
+/// 非推奨で変換できないターゲット (codex) のconverter
+///
+/// 変換時に常に `DeprecatedTarget` エラーを返す。
+struct Deprecated {
+    target: Targets,
+}
+
+impl ToFormat for Deprecated {
+    fn to_format(&self, _config: &InstruxConfiguration) -> Result<FormatResult> {
+        Err(InstruxError::DeprecatedTarget(self.target))
+    }
+
+    fn get_default_path(&self) -> PathBuf {
+        agentsmd::AgentsMdConverter {}.get_default_path()
+    }
+}
+
+/// 設定の `path` で出力先を置き換えたconverter
+///
+/// 単一ファイルはそのパスに、複数ファイルは既定のディレクトリ配下のファイルを
+/// `path` 配下に移して出力する。既定のディレクトリ外のファイル (ディレクトリごとのAGENTS.mdなど) はそのまま。
+struct Relocated {
+    inner: Box<dyn ToFormat>,
+    path: PathBuf,
+}
+
+impl ToFormat for Relocated {
+    fn to_format(&self, config: &InstruxConfiguration) -> Result<FormatResult> {
+        let default_path = self.inner.get_default_path();
+        let files = match self.inner.to_format(config)? {
+            FormatResult::Single(output) => return Ok(FormatResult::Single(output)),
+            FormatResult::Multiple(files) => files,
+        };
+        let files = files
+            .into_iter()
+            .map(|(file, content)| {
+                let file = match Path::new(&file).strip_prefix(&default_path) {
+                    Ok(rest) if rest.as_os_str().is_empty() => self.path.clone(),
+                    Ok(rest) => self.path.join(rest),
+                    Err(_) => PathBuf::from(file),
+                };
+                (file.to_string_lossy().into_owned(), content)
+            })
+            .collect();
+        Ok(FormatResult::Multiple(files))
+    }
+
+    fn get_default_path(&self) -> PathBuf {
+        self.path.clone()
+    }
+
+    fn max_chars(&self) -> Option<usize> {
+        self.inner.max_chars()
+    }
+
+    fn is_block_managed(&self) -> bool {
+        self.inner.is_block_managed()
+    }
+}
+
+/// 複数ファイル形式のルールファイル名に、設定の順序を表す連番を付けるconverter
+///
+/// 出力先ディレクトリ直下のファイルのみ `01-<ファイル名>` のように0埋めした連番を付ける
+/// (ファイル名順に読み込むツールでも設定の順序を保つため)。
+struct IndexPrefixed {
+    inner: Box<dyn ToFormat>,
+}
+
+impl ToFormat for IndexPrefixed {
+    fn to_format(&self, config: &InstruxConfiguration) -> Result<FormatResult> {
+        let base_path = self.inner.get_default_path();
+        let files = match self.inner.to_format(config)? {
+            FormatResult::Single(output) => return Ok(FormatResult::Single(output)),
+            FormatResult::Multiple(files) => files,
+        };
+        let is_rule_file = |file: &str| Path::new(file).parent() == Some(base_path.as_path());
+        let count = files.iter().filter(|(file, _)| is_rule_file(file)).count();
+        let width = count.to_string().len().max(2);
+        let mut index = 0;
+        let files = files
+            .into_iter()
+            .map(|(file, content)| {
+                if !is_rule_file(&file) {
+                    return (file, content);
+                }
+                index += 1;
+                let path = Path::new(&file);
+                let name = path.file_name().unwrap_or_default().to_string_lossy();
+                let file = base_path.join(format!("{:0width$}-{}", index, name, width = width));
+                (file.to_string_lossy().into_owned(), content)
+            })
+            .collect();
+        Ok(FormatResult::Multiple(files))
+    }
+
+    fn get_default_path(&self) -> PathBuf {
+        self.inner.get_default_path()
+    }
+
+    fn max_chars(&self) -> Option<usize> {
+        self.inner.max_chars()
+    }
+
+    fn is_block_managed(&self) -> bool {
+        self.inner.is_block_managed()
     }
 }
 
+/// Factory to get the converter for a user-defined target (`custom` in instrux.yaml)
+pub fn get_custom_converter(name: &CustomTargetName, target: &CustomTarget) -> Box<dyn ToFormat> {
+    let converter = Box::new(custom::CustomConverter {
+        name: name.clone(),
+        target: target.clone(),
+    });
+    let converter: Box<dyn ToFormat> = if target.output_mode == CustomTargetOutputMode::Multiple
+        && target.index_prefix == Some(true)
+    {
+        Box::new(IndexPrefixed { inner: converter })
+    } else {
+        converter
+    };
+    managed(converter, target.managed.unwrap_or_default())
+}
+
+/// 設定で有効な全ターゲット (組み込み・custom) のconverterをターゲット名順に返す
+///
+/// 非推奨のターゲット (codex) は変換できないため含めない。
+pub fn converters(config: &InstruxConfiguration) -> Vec<(TargetName, Box<dyn ToFormat>)> {
+    let mut converters: Vec<(TargetName, Box<dyn ToFormat>)> = config
+        .targets
+        .iter()
+        .filter(|(target, _)| !is_deprecated(target))
+        .map(|(target, value)| (TargetName::Targets(*target), get_converter(target, value)))
+        .chain(config.custom.iter().map(|(name, target)| {
+            (
+                TargetName::CustomTargetName(name.clone()),
+                get_custom_converter(name, target),
+            )
+        }))
+        .collect();
+    converters.sort_by(|(a, _), (b, _)| a.cmp(b));
+    converters
+}
+
 /// Trait for converting from a target format to the instrux model
 pub trait FromFormat {
+    /// 変換元のターゲット (インポートした instruction の targets に設定する)
+    const TARGET: Targets;
+
     /// Convert from the target format to the instrux model
-    fn from_format(content: &str) -> Result<Vec<InstructionItem>, String>;
+    fn from_format(content: &str) -> Result<Vec<InstructionItem>>;
+
+    /// ルールディレクトリ内のファイルのうち、インポート対象とするものか
+    fn is_rule_file(path: &Path) -> bool {
+        path.extension().is_some_and(|ext| ext == "md")
+    }
+
+    /// ルールディレクトリ配下のファイルを1ファイル1つの instruction として読み込む
+    fn from_dir(dir: &Path) -> Result<Vec<InstructionItem>> {
+        common::parse_rule_dir(dir, Self::TARGET, Self::is_rule_file)
+    }
 }
 
-pub fn from_format(target: &Targets, content: &str) -> Result<Vec<InstructionItem>, String> {
+pub fn from_format(target: &Targets, content: &str) -> Result<Vec<InstructionItem>> {
     match target {
         Targets::Copilot => copilot::CopilotParser::from_format(content),
         Targets::Cline => cline::ClineParser::from_format(content),
         Targets::Cursor => cursor::CursorParser::from_format(content),
         Targets::Junie => junie::JunieParser::from_format(content),
         Targets::Agentsmd => agentsmd::AgentsMdParser::from_format(content),
-        Targets::Codex => {
-            unreachable!("Codex is deprecated. Use agentsmd (AGENTS.md) instead.")
+        Targets::Claude => claude::ClaudeParser::from_format(content),
+        Targets::Windsurf => windsurf::WindsurfParser::from_format(content),
+        Targets::Gemini => gemini::GeminiParser::from_format(content),
+        Targets::Codex => Err(InstruxError::DeprecatedTarget(*target)),
+    }
+}
+
+/// `path` から読み込んだ内容を変換する
+///
+/// インポートを出力先からの相対パスで書き出すターゲット (claude) は、`path` のディレクトリから解決する。
+pub fn from_file(target: &Targets, content: &str, path: &Path) -> Result<Vec<InstructionItem>> {
+    match target {
+        Targets::Claude => claude::ClaudeParser::from_file(content, path),
+        _ => from_format(target, content),
+    }
+}
+
+/// 非推奨で変換できないターゲットか
+pub fn is_deprecated(target: &Targets) -> bool {
+    *target == Targets::Codex
+}
+
+/// ディレクトリからのインポート
+/// ルールディレクトリを持たないターゲットの場合はNoneを返す
+pub fn from_dir(target: &Targets, dir: &Path) -> Option<Result<Vec<InstructionItem>>> {
+    let items = match target {
+        Targets::Copilot => copilot::CopilotParser::from_dir(dir),
+        Targets::Cline => cline::ClineParser::from_dir(dir),
+        Targets::Cursor => cursor::CursorParser::from_dir(dir),
+        Targets::Windsurf => windsurf::WindsurfParser::from_dir(dir),
+        Targets::Junie | Targets::Agentsmd | Targets::Claude | Targets::Gemini | Targets::Codex => {
+            return None;
+        }
+    };
+    Some(items)
+}
+
+/// ルールディレクトリ内のファイルのうち、ターゲットのルールファイルとして読み込むものか
+pub fn is_rule_file(target: &Targets, path: &Path) -> bool {
+    match target {
+        Targets::Copilot => copilot::CopilotParser::is_rule_file(path),
+        Targets::Cline => cline::ClineParser::is_rule_file(path),
+        Targets::Cursor => cursor::CursorParser::is_rule_file(path),
+        Targets::Windsurf => windsurf::WindsurfParser::is_rule_file(path),
+        Targets::Junie | Targets::Agentsmd | Targets::Claude | Targets::Gemini | Targets::Codex => {
+            path.extension().is_some_and(|ext| ext == "md")
+        }
+    }
+}
+
+/// 各ツールが複数のルールファイルを置くディレクトリ
+pub fn rule_dir(target: &Targets) -> Option<PathBuf> {
+    match target {
+        Targets::Copilot => Some(PathBuf::from(".github/instructions")),
+        Targets::Cline => Some(PathBuf::from(".clinerules")),
+        Targets::Cursor => Some(PathBuf::from(".cursor/rules")),
+        Targets::Windsurf => Some(PathBuf::from(".windsurf/rules")),
+        Targets::Junie | Targets::Agentsmd | Targets::Claude | Targets::Gemini | Targets::Codex => {
+            None
         }
     }
 }
@@ -0,0 +1,123 @@
+use crate::formats::{FormatResult, ToFormat, agentsmd::AgentsMdConverter};
+use crate::model::types::{
+    InstructionItem, InstructionItemVariant0Targets, InstructionItemVariant2Targets,
+    InstruxConfiguration, Targets,
+};
+use std::collections::HashMap;
+
+fn instruction(title: &str, directory: Option<&str>, targets: &str) -> InstructionItem {
+    InstructionItem::Variant0 {
+        title: title.to_string(),
+        body: format!("{} body", title),
+        description: None,
+        applies_to: Vec::new(),
+        activation: None,
+        directory: directory.map(str::to_string),
+        filename: None,
+        id: None,
+        disable: false,
+        targets: if targets == "all" {
+            InstructionItemVariant0Targets::Variant1("all".to_string())
+        } else {
+            InstructionItemVariant0Targets::Variant0(vec![Targets::Copilot.into()])
+        },
+    }
+}
+
+fn create_test_config(instructions: Vec<InstructionItem>) -> InstruxConfiguration {
+    InstruxConfiguration {
+        instructions,
+        custom: Default::default(),
+        language: Default::default(),
+        targets: HashMap::from([(Targets::Agentsmd, Default::default())]),
+        sandbox: None,
+        validation: None,
+        version: "0.1.0".parse().expect("Valid version string"),
+    }
+}
+
+#[test]
+fn test_agentsmd_without_directories_is_single_file() {
+    let config = create_test_config(vec![instruction("Global", None, "all")]);
+    let converter = AgentsMdConverter {};
+    match converter.to_format(&config) {
+        Ok(FormatResult::Single(output)) => {
+            assert_eq!(
+                output,
+                "# Agents Instructions\n\n## Global\n\nGlobal body\n\n"
+            )
+        }
+        _ => panic!("Expected FormatResult::Single"),
+    }
+}
+
+#[test]
+fn test_agentsmd_writes_nested_files_per_directory() {
+    let config = create_test_config(vec![
+        instruction("Global", None, "all"),
+        instruction("Billing", Some("services/billing/"), "all"),
+        instruction("Billing tests", Some("services/billing"), "all"),
+        instruction("Copilot only", Some("services/auth"), "copilot"),
+    ]);
+    let converter = AgentsMdConverter {};
+    let files = match converter.to_format(&config) {
+        Ok(FormatResult::Multiple(files)) => files,
+        _ => panic!("Expected FormatResult::Multiple"),
+    };
+    // agentsmdを対象とするinstructionがないディレクトリには出力しない
+    assert_eq!(
+        files,
+        [
+            (
+                "AGENTS.md".to_string(),
+                "# Agents Instructions\n\n## Global\n\nGlobal body\n\n".to_string()
+            ),
+            (
+                "services/billing/AGENTS.md".to_string(),
+                "# Agents Instructions\n\n## Billing\n\nBilling body\n\n## Billing tests\n\nBilling tests body\n\n".to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_agentsmd_nested_directories_and_unmatched_glob() {
+    let group = InstructionItem::Variant2 {
+        title: "Services".to_string(),
+        instructions: vec![
+            instruction("Shared", None, "all"),
+            instruction("Billing", Some("services/billing"), "all"),
+        ],
+        description: None,
+        applies_to: Vec::new(),
+        activation: None,
+        directory: None,
+        filename: None,
+        id: None,
+        disable: false,
+        targets: InstructionItemVariant2Targets::Variant1("all".to_string()),
+    };
+    let config = create_test_config(vec![
+        group,
+        instruction("Nowhere", Some("no-such-dir-*/pkg"), "all"),
+    ]);
+    let converter = AgentsMdConverter {};
+    let files = match converter.to_format(&config) {
+        Ok(FormatResult::Multiple(files)) => files,
+        _ => panic!("Expected FormatResult::Multiple"),
+    };
+    // グループ内のdirectoryはグループの見出しごと出力し、一致しないglobはルートに出力する
+    assert_eq!(
+        files,
+        [
+            (
+                "AGENTS.md".to_string(),
+                "# Agents Instructions\n\n## Services\n\n### Shared\n\nShared body\n\n## Nowhere\n\nNowhere body\n\n".to_string()
+            ),
+            (
+                "services/billing/AGENTS.md".to_string(),
+                "# Agents Instructions\n\n## Services\n\n### Billing\n\nBilling body\n\n".to_string()
+            ),
+        ]
+    );
+}
@@ -0,0 +1,113 @@
+use crate::formats::common::resolve_imports;
+use crate::formats::{FormatResult, ToFormat, claude::ClaudeConverter};
+use crate::model::types::{
+    InstructionItem, InstructionItemVariant0Targets, InstructionItemVariant1Targets,
+    InstruxConfiguration, Targets,
+};
+use std::collections::HashMap;
+use std::fs;
+use tempfile::tempdir;
+
+fn create_test_config() -> InstruxConfiguration {
+    let instruction1 = InstructionItem::Variant0 {
+        title: "Overview".to_string(),
+        body: "This is a sample instruction body.".to_string(),
+        description: None,
+        applies_to: Vec::new(),
+        activation: None,
+        directory: None,
+        filename: None,
+        id: None,
+        disable: false,
+        targets: InstructionItemVariant0Targets::Variant1("all".to_string()),
+    };
+    let instruction2 = InstructionItem::Variant1 {
+        title: "Coding Style".to_string(),
+        body_file: "coding-style.md".to_string(),
+        description: None,
+        applies_to: Vec::new(),
+        activation: None,
+        directory: None,
+        filename: None,
+        id: None,
+        disable: false,
+        targets: InstructionItemVariant1Targets::Variant0(vec![Targets::Claude.into()]),
+    };
+    let instruction3 = InstructionItem::Variant0 {
+        title: "Copilot Only".to_string(),
+        body: "Not for Claude.".to_string(),
+        description: None,
+        applies_to: Vec::new(),
+        activation: None,
+        directory: None,
+        filename: None,
+        id: None,
+        disable: false,
+        targets: InstructionItemVariant0Targets::Variant0(vec![Targets::Copilot.into()]),
+    };
+    InstruxConfiguration {
+        instructions: vec![instruction1, instruction2, instruction3],
+        custom: Default::default(),
+        language: Default::default(),
+        targets: HashMap::from([(Targets::Claude, Default::default())]),
+        sandbox: None,
+        validation: None,
+        version: "0.1.0".parse().expect("Valid version string"),
+    }
+}
+
+fn render(imports: bool) -> String {
+    render_at(imports, "")
+}
+
+fn render_at(imports: bool, output_dir: &str) -> String {
+    let converter = ClaudeConverter {
+        imports,
+        output_dir: output_dir.into(),
+    };
+    match converter.to_format(&create_test_config()) {
+        Ok(FormatResult::Single(text)) => text,
+        _ => panic!("Expected FormatResult::Single"),
+    }
+}
+
+#[test]
+fn test_claude_converter_to_format() {
+    let output = render(false);
+    assert!(output.starts_with("# CLAUDE.md\n\n"));
+    assert!(output.contains("## Overview\n\nThis is a sample instruction body."));
+    assert!(output.contains("## Coding Style"));
+    assert!(!output.contains("@.instrux/instructions/coding-style.md"));
+    assert!(!output.contains("Copilot Only"));
+}
+
+#[test]
+fn test_claude_converter_emits_imports() {
+    let output = render(true);
+    assert!(output.contains("## Coding Style\n\n@.instrux/instructions/coding-style.md\n"));
+    assert!(output.contains("This is a sample instruction body."));
+
+    // pathで出力先を変えた場合は出力するファイルからの相対パスにする
+    let output = render_at(true, "docs/ai");
+    assert!(output.contains("## Coding Style\n\n@../../.instrux/instructions/coding-style.md\n"));
+}
+
+#[test]
+fn test_resolve_imports_expands_nested_files() {
+    let dir = tempdir().unwrap();
+    fs::create_dir(dir.path().join("docs")).unwrap();
+    fs::write(dir.path().join("docs/style.md"), "Use tabs.\n@nested.md\n").unwrap();
+    fs::write(dir.path().join("docs/nested.md"), "Nested rule.\n").unwrap();
+
+    let content = "## Style\n\n@docs/style.md\n\n```sh\n@docs/style.md\n```\n\n@missing.md\n";
+    let resolved = resolve_imports(content, dir.path());
+    assert_eq!(
+        resolved,
+        "## Style\n\nUse tabs.\nNested rule.\n\n```sh\n@docs/style.md\n```\n\n@missing.md\n"
+    );
+    // インポート元のディレクトリの外を指すパスは読み込まない
+    let repo = dir.path().join("docs");
+    fs::write(dir.path().join("secret.md"), "Secret.\n").unwrap();
+    let resolved = resolve_imports("@../secret.md\n@style.md\n", &repo);
+    assert_eq!(resolved, "@../secret.md\nUse tabs.\nNested rule.\n");
+}
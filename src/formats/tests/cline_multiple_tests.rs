@@ -1,7 +1,8 @@
-use crate::formats::ToFormat;
 use crate::formats::cline_multiple::ClineMultipleConverter;
+use crate::formats::{FormatResult, ToFormat, get_converter};
 use crate::model::types::{
-    InstructionItem, InstructionItemVariant0Targets, InstruxConfiguration, Targets,
+    FilenameStyle, InstructionItem, InstructionItemVariant0Targets, InstruxConfiguration,
+    InstruxConfigurationTargetsValue, InstruxConfigurationTargetsValueOutputMode, Targets,
 };
 use std::collections::HashMap;
 
@@ -11,23 +12,36 @@ fn create_test_config() -> InstruxConfiguration {
         title: "インストラクション1".to_string(),
         body: "本文1".to_string(),
         description: Some("説明1".to_string()),
+        applies_to: Vec::new(),
+        activation: None,
+        directory: None,
+        filename: None,
+        id: None,
         disable: false,
-        targets: InstructionItemVariant0Targets::Variant0(vec![Targets::Cline]),
+        targets: InstructionItemVariant0Targets::Variant0(vec![Targets::Cline.into()]),
     };
     let instruction2 = InstructionItem::Variant0 {
         title: "インストラクション2".to_string(),
         body: "本文2".to_string(),
         description: None,
+        applies_to: Vec::new(),
+        activation: None,
+        directory: None,
+        filename: None,
+        id: None,
         disable: false,
-        targets: InstructionItemVariant0Targets::Variant0(vec![Targets::Cline]),
+        targets: InstructionItemVariant0Targets::Variant0(vec![Targets::Cline.into()]),
     };
     let mut targets_map = HashMap::new();
     targets_map.insert(Targets::Cline, Default::default());
     let version = "0.1.0".parse().expect("Valid version string");
     InstruxConfiguration {
         instructions: vec![instruction1, instruction2],
+        custom: Default::default(),
         language: Default::default(),
         targets: targets_map,
+        sandbox: None,
+        validation: None,
         version,
     }
 }
@@ -36,7 +50,9 @@ fn create_test_config() -> InstruxConfiguration {
 fn test_cline_multiple_converter_to_format() {
     // ClineMultipleConverterのインスタンス生成
     let config = create_test_config();
-    let converter = ClineMultipleConverter {};
+    let converter = ClineMultipleConverter {
+        filenames: Default::default(),
+    };
 
     // to_formatでファイル名と内容のペアを取得
     let result = converter.to_format(&config);
@@ -45,7 +61,7 @@ fn test_cline_multiple_converter_to_format() {
     // FormatResultからファイル名と内容のペアを取得
     let format_result = result.unwrap();
     let files = match format_result {
-        crate::formats::FormatResult::Multiple(files) => files,
+        FormatResult::Multiple(files) => files,
         _ => panic!("Expected FormatResult::Multiple"),
     };
 
@@ -63,8 +79,66 @@ fn test_cline_multiple_converter_to_format() {
             "# インストラクション2\n\n本文2\n",
         ),
     ];
-    for (expected_path, expected_content) in expected_files {
-        let content = files.get(expected_path).expect("ファイルが存在すること");
+    // 設定の順に出力されること
+    for ((path, content), (expected_path, expected_content)) in files.iter().zip(expected_files) {
+        assert_eq!(path, expected_path);
         assert_eq!(content, expected_content);
     }
 }
+
+#[test]
+fn test_cline_multiple_index_prefix_keeps_config_order() {
+    let config = create_test_config();
+    let target_config = InstruxConfigurationTargetsValue {
+        output_mode: InstruxConfigurationTargetsValueOutputMode::Multiple,
+        index_prefix: Some(true),
+        ..Default::default()
+    };
+    let converter = get_converter(&Targets::Cline, &target_config);
+    let paths: Vec<String> = match converter.to_format(&config) {
+        Ok(FormatResult::Multiple(files)) => files.into_iter().map(|(path, _)| path).collect(),
+        _ => panic!("Expected FormatResult::Multiple"),
+    };
+    // ファイル名順に読み込んでも設定の順序になるよう、0埋めした連番を付ける
+    assert_eq!(
+        paths,
+        [
+            ".clinerules/01-インストラクション1.md",
+            ".clinerules/02-インストラクション2.md"
+        ]
+    );
+}
+
+#[test]
+fn test_cline_multiple_filenames_are_safe() {
+    let mut config = create_test_config();
+    if let InstructionItem::Variant0 { title, .. } = &mut config.instructions[0] {
+        *title = "../API: 設計/規約?".to_string();
+    }
+    if let InstructionItem::Variant0 { filename, .. } = &mut config.instructions[1] {
+        *filename = Some("second-rule".try_into().unwrap());
+    }
+    let paths = |filenames: FilenameStyle| -> Vec<String> {
+        let converter = ClineMultipleConverter { filenames };
+        match converter.to_format(&config) {
+            Ok(FormatResult::Multiple(files)) => files.into_iter().map(|(path, _)| path).collect(),
+            _ => panic!("Expected FormatResult::Multiple"),
+        }
+    };
+    // パス区切りやOSで使えない文字はファイル名に含めない (filename指定はそのまま使う)
+    assert_eq!(
+        paths(FilenameStyle::Title),
+        [
+            ".clinerules/-API- 設計-規約-.md",
+            ".clinerules/second-rule.md"
+        ]
+    );
+    assert_eq!(
+        paths(FilenameStyle::Slug),
+        [".clinerules/api-設計-規約.md", ".clinerules/second-rule.md"]
+    );
+    assert_eq!(
+        paths(FilenameStyle::Ascii),
+        [".clinerules/api.md", ".clinerules/second-rule.md"]
+    );
+}
@@ -11,23 +11,36 @@ fn create_test_config() -> InstruxConfiguration {
         title: "インストラクション1".to_string(),
         body: "本文1".to_string(),
         description: Some("説明1".to_string()),
+        applies_to: Vec::new(),
+        activation: None,
+        directory: None,
+        filename: None,
+        id: None,
         disable: false,
-        targets: InstructionItemVariant0Targets::Variant0(vec![Targets::Copilot]),
+        targets: InstructionItemVariant0Targets::Variant0(vec![Targets::Copilot.into()]),
     };
     let instruction2 = InstructionItem::Variant0 {
         title: "インストラクション2".to_string(),
         body: "本文2".to_string(),
         description: None,
+        applies_to: Vec::new(),
+        activation: None,
+        directory: None,
+        filename: None,
+        id: None,
         disable: false,
-        targets: InstructionItemVariant0Targets::Variant0(vec![Targets::Copilot]),
+        targets: InstructionItemVariant0Targets::Variant0(vec![Targets::Copilot.into()]),
     };
     let mut targets_map = HashMap::new();
     targets_map.insert(Targets::Copilot, Default::default());
     let version = "0.1.0".parse().expect("Valid version string");
     InstruxConfiguration {
         instructions: vec![instruction1, instruction2],
+        custom: Default::default(),
         language: Default::default(),
         targets: targets_map,
+        sandbox: None,
+        validation: None,
         version,
     }
 }
@@ -36,7 +49,9 @@ fn create_test_config() -> InstruxConfiguration {
 fn test_copilot_multiple_converter_to_format() {
     // CopilotMultipleConverterのインスタンス生成
     let config = create_test_config();
-    let converter = CopilotMultipleConverter {};
+    let converter = CopilotMultipleConverter {
+        filenames: Default::default(),
+    };
 
     // to_formatでファイル名と内容のペアを取得
     let result = converter.to_format(&config);
@@ -63,8 +78,9 @@ fn test_copilot_multiple_converter_to_format() {
             "# インストラクション2\n\n本文2\n",
         ),
     ];
-    for (expected_path, expected_content) in expected_files {
-        let content = files.get(expected_path).expect("ファイルが存在すること");
+    // 設定の順に出力されること
+    for ((path, content), (expected_path, expected_content)) in files.iter().zip(expected_files) {
+        assert_eq!(path, expected_path);
         assert_eq!(content, expected_content);
     }
 }
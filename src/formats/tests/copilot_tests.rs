@@ -12,6 +12,11 @@ mod tests {
             title: "Sample Instruction".to_string(),
             body: "This is a sample instruction body.".to_string(),
             description: Some("Description of the instruction".to_string()),
+            applies_to: Vec::new(),
+            activation: None,
+            directory: None,
+            filename: None,
+            id: None,
             disable: false,
             targets: InstructionItemVariant0Targets::Variant1("all".to_string()),
         };
@@ -20,8 +25,13 @@ mod tests {
             title: "Copilot Specific Instruction".to_string(),
             body: "This instruction is specific to Copilot.".to_string(),
             description: None,
+            applies_to: Vec::new(),
+            activation: None,
+            directory: None,
+            filename: None,
+            id: None,
             disable: false,
-            targets: InstructionItemVariant0Targets::Variant0(vec![Targets::Copilot]),
+            targets: InstructionItemVariant0Targets::Variant0(vec![Targets::Copilot.into()]),
         };
 
         let mut targets_map = HashMap::new();
@@ -32,8 +42,11 @@ mod tests {
 
         InstruxConfiguration {
             instructions: vec![instruction1, instruction2],
+            custom: Default::default(),
             language: Default::default(),
             targets: targets_map,
+            sandbox: None,
+            validation: None,
             version,
         }
     }
@@ -0,0 +1,111 @@
+use crate::formats::common::parse_rule_file;
+use crate::formats::cursor_multiple::CursorMultipleConverter;
+use crate::formats::{FormatResult, ToFormat};
+use crate::model::types::{
+    Activation, InstructionItem, InstructionItemVariant0Targets, InstruxConfiguration, Targets,
+};
+use std::collections::HashMap;
+use std::path::Path;
+
+fn instruction(
+    title: &str,
+    applies_to: &[&str],
+    activation: Option<Activation>,
+) -> InstructionItem {
+    InstructionItem::Variant0 {
+        title: title.to_string(),
+        body: format!("{} body", title),
+        description: Some(format!("{} rules", title)),
+        applies_to: applies_to.iter().map(|s| s.to_string()).collect(),
+        activation,
+        directory: None,
+        filename: None,
+        id: None,
+        disable: false,
+        targets: InstructionItemVariant0Targets::Variant0(vec![Targets::Cursor.into()]),
+    }
+}
+
+/// テスト用のサンプル設定を生成
+fn create_test_config() -> InstruxConfiguration {
+    InstruxConfiguration {
+        instructions: vec![
+            instruction("Style", &[], None),
+            instruction("TypeScript", &["src/**/*.ts", "*.tsx"], None),
+            instruction("Database", &[], Some(Activation::Agent)),
+            instruction("Release", &["CHANGELOG.md"], Some(Activation::Manual)),
+        ],
+        custom: Default::default(),
+        language: Default::default(),
+        targets: HashMap::from([(Targets::Cursor, Default::default())]),
+        sandbox: None,
+        validation: None,
+        version: "0.1.0".parse().expect("Valid version string"),
+    }
+}
+
+fn render() -> HashMap<String, String> {
+    let converter = CursorMultipleConverter {
+        filenames: Default::default(),
+    };
+    match converter.to_format(&create_test_config()) {
+        Ok(FormatResult::Multiple(files)) => files.into_iter().collect(),
+        _ => panic!("Expected FormatResult::Multiple"),
+    }
+}
+
+#[test]
+fn test_cursor_multiple_converter_writes_rule_types() {
+    let files = render();
+    assert_eq!(files.len(), 4);
+    assert_eq!(
+        files[".cursor/rules/Style.mdc"],
+        "---\ndescription: Style rules\nglobs: \nalwaysApply: true\n---\n\n# Style\n\nStyle body\n"
+    );
+    assert!(files[".cursor/rules/TypeScript.mdc"].starts_with(
+        "---\ndescription: TypeScript rules\nglobs: src/**/*.ts,*.tsx\nalwaysApply: false\n---\n"
+    ));
+    assert!(
+        files[".cursor/rules/Database.mdc"]
+            .starts_with("---\ndescription: Database rules\nglobs: \nalwaysApply: false\n---\n")
+    );
+    assert!(
+        files[".cursor/rules/Release.mdc"]
+            .starts_with("---\ndescription: \nglobs: \nalwaysApply: false\n---\n")
+    );
+}
+
+#[test]
+fn test_cursor_multiple_output_round_trips_rule_types() {
+    let files = render();
+    let expected = [
+        ("Style", Some(Activation::Always), vec![]),
+        (
+            "TypeScript",
+            Some(Activation::Glob),
+            vec!["src/**/*.ts", "*.tsx"],
+        ),
+        ("Database", Some(Activation::Agent), vec![]),
+        ("Release", Some(Activation::Manual), vec![]),
+    ];
+    for (title, activation, globs) in expected {
+        let path = format!(".cursor/rules/{}.mdc", title);
+        let item = parse_rule_file(Path::new(&path), &files[&path], Targets::Cursor)
+            .expect("parse succeeds");
+        match item {
+            InstructionItem::Variant0 {
+                title: parsed_title,
+                body,
+                activation: parsed_activation,
+                applies_to,
+                ..
+            } => {
+                assert_eq!(parsed_title, title);
+                assert_eq!(body, format!("{} body", title));
+                assert_eq!(parsed_activation, activation, "{}", title);
+                assert_eq!(applies_to, globs, "{}", title);
+            }
+            _ => panic!("Expected Variant0"),
+        }
+    }
+}
@@ -1,5 +1,6 @@
 #[cfg(test)]
 mod tests {
+    use crate::formats::common::TargetsChecker;
     use crate::formats::{FromFormat, ToFormat, cursor::CursorConverter, cursor::CursorParser};
     use crate::model::types::{
         InstructionItem, InstructionItemVariant0Targets, InstruxConfiguration, Targets,
@@ -12,6 +13,11 @@ mod tests {
             title: "Sample Instruction".to_string(),
             body: "This is a sample instruction body.".to_string(),
             description: Some("Description of the instruction".to_string()),
+            applies_to: Vec::new(),
+            activation: None,
+            directory: None,
+            filename: None,
+            id: None,
             disable: false,
             targets: InstructionItemVariant0Targets::Variant1("all".to_string()),
         };
@@ -20,8 +26,13 @@ mod tests {
             title: "Cursor Specific Instruction".to_string(),
             body: "This instruction is specific to Cursor.".to_string(),
             description: None,
+            applies_to: Vec::new(),
+            activation: None,
+            directory: None,
+            filename: None,
+            id: None,
             disable: false,
-            targets: InstructionItemVariant0Targets::Variant0(vec![Targets::Cursor]),
+            targets: InstructionItemVariant0Targets::Variant0(vec![Targets::Cursor.into()]),
         };
 
         let mut targets_map = HashMap::new();
@@ -31,8 +42,11 @@ mod tests {
 
         InstruxConfiguration {
             instructions: vec![instruction1, instruction2],
+            custom: Default::default(),
             language: Default::default(),
             targets: targets_map,
+            sandbox: None,
+            validation: None,
             version,
         }
     }
@@ -84,9 +98,16 @@ This instruction is specific to Cursor.
         assert_eq!(instructions.len(), 2);
 
         match &instructions[0] {
-            InstructionItem::Variant0 { title, body, .. } => {
+            InstructionItem::Variant0 {
+                title,
+                body,
+                targets,
+                ..
+            } => {
                 assert_eq!(title, "Sample Instruction");
                 assert!(body.contains("This is a sample instruction body."));
+                assert!(targets.is_for_target(Targets::Cursor));
+                assert!(!targets.is_for_target(Targets::Junie));
             }
             _ => panic!("Expected Variant0"),
         }
@@ -0,0 +1,91 @@
+use crate::formats::{FormatResult, converters};
+use crate::model::parser::parse_instrux_yaml_str;
+use crate::model::types::{InstruxConfiguration, TargetName};
+use std::collections::HashMap;
+use std::path::Path;
+
+fn parse(content: &str) -> InstruxConfiguration {
+    parse_instrux_yaml_str(content, Path::new("instrux.yaml")).expect("valid config")
+}
+
+/// 設定からカスタムターゲットの出力を取得
+fn custom_output(config: &InstruxConfiguration, name: &str) -> FormatResult {
+    let (_, converter) = converters(config)
+        .into_iter()
+        .find(|(target, _)| matches!(target, TargetName::CustomTargetName(n) if n.as_str() == name))
+        .expect("custom target exists");
+    converter.to_format(config).expect("conversion succeeds")
+}
+
+#[test]
+fn test_custom_target_single_file_with_header() {
+    let config = parse(
+        r##"
+version: 1.0.0
+targets:
+  copilot: {}
+custom:
+  mytool:
+    path: .mytool/rules.md
+    header: "# My Tool Rules\n"
+instructions:
+  - title: Style
+    body: Use rustfmt.
+    applies_to: ['**/*.rs']
+  - title: Copilot only
+    body: Only for Copilot.
+    targets: [copilot]
+  - title: Tool only
+    body: Only for my tool.
+    targets: [mytool]
+"##,
+    );
+    match custom_output(&config, "mytool") {
+        FormatResult::Single(output) => assert_eq!(
+            output,
+            "# My Tool Rules\n\n## Style\n\nWhen editing files matching `**/*.rs`:\n\nUse rustfmt.\n\n## Tool only\n\nOnly for my tool.\n\n"
+        ),
+        _ => panic!("Expected FormatResult::Single"),
+    }
+}
+
+#[test]
+fn test_custom_target_multiple_files_with_template() {
+    let config = parse(
+        r#"
+version: 1.0.0
+targets:
+  copilot: {}
+custom:
+  mytool:
+    path: .mytool/rules
+    outputMode: multiple
+    filename: "{{slug}}.rule.md"
+    frontmatter:
+      name: "{{title}}"
+      globs: "{{applies_to}}"
+      summary: "{{description}}"
+instructions:
+  - title: Code Style
+    body: Use rustfmt.
+    applies_to: ['src/**/*.rs', 'tests/**/*.rs']
+  - title: Review
+    description: How to review
+    body: Be kind.
+"#,
+    );
+    let files: HashMap<String, String> = match custom_output(&config, "mytool") {
+        FormatResult::Multiple(files) => files.into_iter().collect(),
+        _ => panic!("Expected FormatResult::Multiple"),
+    };
+    assert_eq!(files.len(), 2);
+    // 値が空になるフィールド (summary) は出力しない
+    assert_eq!(
+        files[".mytool/rules/code-style.rule.md"],
+        "---\nglobs: src/**/*.rs,tests/**/*.rs\nname: Code Style\n---\n\n# Code Style\n\nUse rustfmt.\n"
+    );
+    assert_eq!(
+        files[".mytool/rules/review.rule.md"],
+        "---\nname: Review\nsummary: How to review\n---\n\n# Review\n\nBe kind.\n"
+    );
+}
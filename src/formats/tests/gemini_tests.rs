@@ -0,0 +1,108 @@
+use crate::formats::gemini::{GeminiConverter, IMPORT_DIR};
+use crate::formats::{FormatResult, ToFormat};
+use crate::model::types::{
+    InstructionItem, InstructionItemVariant0Targets, InstructionItemVariant2Targets,
+    InstruxConfiguration, Targets,
+};
+use std::collections::HashMap;
+
+fn leaf(title: &str, body: &str) -> InstructionItem {
+    InstructionItem::Variant0 {
+        title: title.to_string(),
+        body: body.to_string(),
+        description: None,
+        applies_to: Vec::new(),
+        activation: None,
+        directory: None,
+        filename: None,
+        id: None,
+        disable: false,
+        targets: InstructionItemVariant0Targets::Variant1("all".to_string()),
+    }
+}
+
+fn group(title: &str, instructions: Vec<InstructionItem>) -> InstructionItem {
+    InstructionItem::Variant2 {
+        title: title.to_string(),
+        instructions,
+        description: None,
+        applies_to: Vec::new(),
+        activation: None,
+        directory: None,
+        filename: None,
+        id: None,
+        disable: false,
+        targets: InstructionItemVariant2Targets::Variant1("all".to_string()),
+    }
+}
+
+fn create_test_config() -> InstruxConfiguration {
+    InstruxConfiguration {
+        instructions: vec![
+            leaf("Overview", "Shared rules."),
+            group(
+                "Backend",
+                vec![
+                    leaf("Database", "Use migrations."),
+                    group("API", vec![leaf("Errors", "Return problem+json.")]),
+                ],
+            ),
+        ],
+        custom: Default::default(),
+        language: Default::default(),
+        targets: HashMap::from([(Targets::Gemini, Default::default())]),
+        sandbox: None,
+        validation: None,
+        version: "0.1.0".parse().expect("Valid version string"),
+    }
+}
+
+#[test]
+fn test_gemini_converter_to_format() {
+    let converter = GeminiConverter {
+        imports: false,
+        import_dir: IMPORT_DIR.to_string(),
+        filenames: Default::default(),
+    };
+    let output = match converter.to_format(&create_test_config()) {
+        Ok(FormatResult::Single(text)) => text,
+        _ => panic!("Expected FormatResult::Single"),
+    };
+    assert!(output.starts_with("# GEMINI.md\n\n## Overview\n\nShared rules."));
+    assert!(output.contains("## Backend\n\n### Database\n\nUse migrations."));
+    assert!(output.contains("#### Errors\n\nReturn problem+json."));
+    assert!(!output.contains('@'));
+}
+
+#[test]
+fn test_gemini_converter_imports_groups() {
+    let converter = GeminiConverter {
+        imports: true,
+        import_dir: IMPORT_DIR.to_string(),
+        filenames: Default::default(),
+    };
+    let files = match converter.to_format(&create_test_config()) {
+        Ok(FormatResult::Multiple(files)) => files,
+        _ => panic!("Expected FormatResult::Multiple"),
+    };
+    // GEMINI.md、親のグループ、子のグループの順に出力する
+    let paths: Vec<&str> = files.iter().map(|(path, _)| path.as_str()).collect();
+    assert_eq!(
+        paths,
+        ["GEMINI.md", ".gemini/Backend.md", ".gemini/Backend/API.md"]
+    );
+    let files: HashMap<String, String> = files.into_iter().collect();
+    assert_eq!(
+        files["GEMINI.md"],
+        "# GEMINI.md\n\n## Overview\n\nShared rules.\n\n## Backend\n\n@.gemini/Backend.md\n\n"
+    );
+    // インポートはインポート元ファイルのディレクトリからの相対パス
+    assert_eq!(
+        files[".gemini/Backend.md"],
+        "### Database\n\nUse migrations.\n\n### API\n\n@Backend/API.md\n\n"
+    );
+    assert_eq!(
+        files[".gemini/Backend/API.md"],
+        "#### Errors\n\nReturn problem+json.\n\n"
+    );
+}
@@ -11,6 +11,11 @@ mod tests {
             title: "Sample Instruction".to_string(),
             body: "This is a sample instruction body.".to_string(),
             description: Some("Description of the instruction".to_string()),
+            applies_to: Vec::new(),
+            activation: None,
+            directory: None,
+            filename: None,
+            id: None,
             disable: false,
             targets: InstructionItemVariant0Targets::Variant1("all".to_string()),
         };
@@ -19,8 +24,13 @@ mod tests {
             title: "Junie Specific Instruction".to_string(),
             body: "This instruction is specific to Junie.".to_string(),
             description: None,
+            applies_to: Vec::new(),
+            activation: None,
+            directory: None,
+            filename: None,
+            id: None,
             disable: false,
-            targets: InstructionItemVariant0Targets::Variant0(vec![Targets::Junie]),
+            targets: InstructionItemVariant0Targets::Variant0(vec![Targets::Junie.into()]),
         };
 
         let mut targets_map = HashMap::new();
@@ -30,8 +40,11 @@ mod tests {
 
         InstruxConfiguration {
             instructions: vec![instruction1, instruction2],
+            custom: Default::default(),
             language: Default::default(),
             targets: targets_map,
+            sandbox: None,
+            validation: None,
             version,
         }
     }
@@ -0,0 +1,45 @@
+use crate::formats::managed::{remove_block, splice};
+
+#[test]
+fn test_splice_appends_block_to_hand_written_file() {
+    assert_eq!(
+        splice("# Team notes\n\nKeep this.\n", "## Style\n\nUse rustfmt.\n"),
+        "# Team notes\n\nKeep this.\n\n<!-- instrux:begin -->\n## Style\n\nUse rustfmt.\n<!-- instrux:end -->\n"
+    );
+    assert_eq!(
+        splice("", "## Style\n"),
+        "<!-- instrux:begin -->\n## Style\n<!-- instrux:end -->\n"
+    );
+}
+
+#[test]
+fn test_splice_replaces_only_block() {
+    let current = "Intro\n\n<!-- instrux:begin -->\nold\n<!-- instrux:end -->\n\nOutro\n";
+    assert_eq!(
+        splice(current, "new\n"),
+        "Intro\n\n<!-- instrux:begin -->\nnew\n<!-- instrux:end -->\n\nOutro\n"
+    );
+    assert_eq!(remove_block(current), "Intro\n\nOutro\n");
+    assert_eq!(
+        remove_block("<!-- instrux:begin -->\nold\n<!-- instrux:end -->\n"),
+        ""
+    );
+}
+
+#[test]
+fn test_splice_keeps_frontmatter_above_block() {
+    let generated = "---\napplyTo: '**/*.rs'\n---\n\n# Style\n\nUse rustfmt.\n";
+    let spliced = splice("", generated);
+    assert_eq!(
+        spliced,
+        "---\napplyTo: '**/*.rs'\n---\n\n<!-- instrux:begin -->\n# Style\n\nUse rustfmt.\n<!-- instrux:end -->\n"
+    );
+
+    // 既存のfrontmatterは生成結果のもので置き換え、手書きの内容は残す
+    let current = "---\napplyTo: '*.md'\n---\n\nTeam notes.\n";
+    assert_eq!(
+        splice(current, generated),
+        "---\napplyTo: '**/*.rs'\n---\n\nTeam notes.\n\n<!-- instrux:begin -->\n# Style\n\nUse rustfmt.\n<!-- instrux:end -->\n"
+    );
+    assert_eq!(remove_block(&spliced), "");
+}
@@ -0,0 +1,191 @@
+use crate::formats::common::{parse_markdown_instructions, process_instructions_common};
+use crate::model::parser;
+use crate::model::types::{InstructionItem, Targets};
+use std::path::Path;
+
+/// (深さ, タイトル, 本文) の一覧に変換する (Variant2の本文は空)
+fn outline(items: &[InstructionItem], depth: usize, out: &mut Vec<(usize, String, String)>) {
+    for item in items {
+        match item {
+            InstructionItem::Variant0 { title, body, .. } => {
+                out.push((depth, title.clone(), body.clone()))
+            }
+            InstructionItem::Variant1 { title, .. } => {
+                out.push((depth, title.clone(), String::new()))
+            }
+            InstructionItem::Variant2 {
+                title,
+                instructions,
+                ..
+            } => {
+                out.push((depth, title.clone(), String::new()));
+                outline(instructions, depth + 1, out);
+            }
+        }
+    }
+}
+
+fn parse(content: &str) -> Vec<(usize, String, String)> {
+    let items = parse_markdown_instructions(content, Targets::Copilot).expect("parse succeeds");
+    let mut out = Vec::new();
+    outline(&items, 0, &mut out);
+    out
+}
+
+#[test]
+fn test_code_fences_are_not_headings() {
+    let content = r#"# Instructions
+
+## Build
+
+Run the following:
+
+```sh
+# install dependencies
+cargo build
+```
+
+    #[derive(Debug)]
+    struct Indented;
+
+## Test
+
+Run `cargo test`.
+"#;
+    assert_eq!(
+        parse(content),
+        vec![
+            (
+                0,
+                "Build".to_string(),
+                "Run the following:\n\n```sh\n# install dependencies\ncargo build\n```\n\n    #[derive(Debug)]\n    struct Indented;".to_string()
+            ),
+            (0, "Test".to_string(), "Run `cargo test`.".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_nested_headings_become_groups() {
+    let content = r#"# Instructions
+
+## Coding
+
+General rules.
+
+### Comments
+
+Write doc comments.
+
+### Naming
+
+Use snake_case.
+
+## Review
+
+Request a review.
+"#;
+    assert_eq!(
+        parse(content),
+        vec![
+            (0, "Coding".to_string(), String::new()),
+            (1, "Coding".to_string(), "General rules.".to_string()),
+            (1, "Comments".to_string(), "Write doc comments.".to_string()),
+            (1, "Naming".to_string(), "Use snake_case.".to_string()),
+            (0, "Review".to_string(), "Request a review.".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_preamble_is_kept() {
+    let content = r#"# AGENTS.md
+
+This repository is a Rust CLI.
+
+## Style
+
+Use rustfmt.
+"#;
+    assert_eq!(
+        parse(content),
+        vec![
+            (
+                0,
+                "AGENTS.md".to_string(),
+                "This repository is a Rust CLI.".to_string()
+            ),
+            (0, "Style".to_string(), "Use rustfmt.".to_string()),
+        ]
+    );
+
+    // 文書タイトルがない場合・レベル1見出しが複数ある場合
+    let content = "Read this first.\n\n# Style\n\nUse rustfmt.\n\n# Test\n\nRun tests.\n";
+    assert_eq!(
+        parse(content),
+        vec![
+            (0, "Overview".to_string(), "Read this first.".to_string()),
+            (0, "Style".to_string(), "Use rustfmt.".to_string()),
+            (0, "Test".to_string(), "Run tests.".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_frontmatter_is_not_body() {
+    let content = "---\ndescription: rules\n---\n\n# Style\n\nUse rustfmt.\n";
+    assert_eq!(
+        parse(content),
+        vec![(0, "Style".to_string(), "Use rustfmt.".to_string())]
+    );
+}
+
+/// (タイトル, id) の一覧に変換する
+fn ids(items: &[InstructionItem], out: &mut Vec<(String, Option<String>)>) {
+    for item in items {
+        let (title, id) = match item {
+            InstructionItem::Variant0 { title, id, .. }
+            | InstructionItem::Variant1 { title, id, .. }
+            | InstructionItem::Variant2 { title, id, .. } => (title, id),
+        };
+        out.push((title.clone(), id.as_ref().map(|id| id.to_string())));
+        if let InstructionItem::Variant2 { instructions, .. } = item {
+            ids(instructions, out);
+        }
+    }
+}
+
+#[test]
+fn test_ids_round_trip_through_generated_markdown() {
+    let config = r#"version: 1.0.0
+targets: {}
+instructions:
+  - title: Group
+    id: group
+    applies_to: ['src/**']
+    instructions:
+      - title: Style
+        id: style
+        body: Use rustfmt.
+      - title: Tests
+        body: Write tests.
+"#;
+    let config = parser::parse_instrux_yaml_str(config, Path::new("instrux.yaml")).unwrap();
+    let mut output = String::new();
+    process_instructions_common(&mut output, &config.instructions, 0, |_| true).unwrap();
+    assert!(output.contains("### Style\n\n<!-- instrux:id=style -->\n\nUse rustfmt."));
+
+    let items = parse_markdown_instructions(&output, Targets::Copilot).expect("parse succeeds");
+    let mut out = Vec::new();
+    ids(&items, &mut out);
+    assert_eq!(
+        out,
+        vec![
+            ("Group".to_string(), Some("group".to_string())),
+            ("Style".to_string(), Some("style".to_string())),
+            ("Tests".to_string(), None),
+        ]
+    );
+    // IDの目印は本文に含めない
+    assert_eq!(parse(&output)[1].2, "Use rustfmt.");
+}
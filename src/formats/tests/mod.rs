@@ -1,6 +1,16 @@
+mod agentsmd_tests;
+mod claude_tests;
 mod cline_multiple_tests;
 mod cline_tests;
 mod copilot_multiple_tests;
 mod copilot_tests;
+mod cursor_multiple_tests;
 mod cursor_tests;
+mod custom_tests;
+mod gemini_tests;
 mod junie_tests;
+mod managed_tests;
+mod markdown_import_tests;
+mod rule_file_tests;
+mod scope_tests;
+mod windsurf_tests;
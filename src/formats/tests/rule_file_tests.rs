@@ -0,0 +1,122 @@
+use crate::formats::common::parse_rule_file;
+use crate::formats::{FromFormat, cline::ClineParser, copilot::CopilotParser};
+use crate::model::types::{Activation, InstructionItem, Targets};
+use std::fs;
+use std::path::Path;
+use tempfile::tempdir;
+
+/// Variant0の (タイトル, 本文, description, applies_to, activation) を取り出す
+fn fields(item: &InstructionItem) -> (&str, &str, Option<&str>, &[String], Option<Activation>) {
+    match item {
+        InstructionItem::Variant0 {
+            title,
+            body,
+            description,
+            applies_to,
+            activation,
+            ..
+        } => (title, body, description.as_deref(), applies_to, *activation),
+        _ => panic!("Expected Variant0"),
+    }
+}
+
+#[test]
+fn test_copilot_frontmatter_is_metadata() {
+    let content = r#"---
+description: Rust conventions
+applyTo: "**/*.rs, Cargo.toml"
+---
+
+# Rust Style
+
+Use `cargo fmt`.
+"#;
+    let item = parse_rule_file(
+        Path::new(".github/instructions/rust.instructions.md"),
+        content,
+        Targets::Copilot,
+    )
+    .expect("parse succeeds");
+    let (title, body, description, applies_to, activation) = fields(&item);
+    assert_eq!(title, "Rust Style");
+    assert_eq!(body, "Use `cargo fmt`.");
+    assert_eq!(description, Some("Rust conventions"));
+    assert_eq!(applies_to, ["**/*.rs", "Cargo.toml"]);
+    assert_eq!(activation, None);
+}
+
+#[test]
+fn test_id_marker_is_restored() {
+    let content = "---\ndescription: Rust\n---\n\n# Rust Style\n\n<!-- instrux:id=rust-style -->\n\nUse `cargo fmt`.\n";
+    let item = parse_rule_file(
+        Path::new(".clinerules/rust-style.md"),
+        content,
+        Targets::Cline,
+    )
+    .expect("parse succeeds");
+    let InstructionItem::Variant0 { id, body, .. } = &item else {
+        panic!("Expected Variant0");
+    };
+    assert_eq!(id.as_deref().map(String::as_str), Some("rust-style"));
+    assert_eq!(body, "Use `cargo fmt`.");
+}
+
+#[test]
+fn test_cursor_activation_from_frontmatter() {
+    let cases = [
+        ("alwaysApply: true\n", Some(Activation::Always)),
+        (
+            "globs: src/**/*.ts\nalwaysApply: false\n",
+            Some(Activation::Glob),
+        ),
+        (
+            "description: Use when writing SQL\nalwaysApply: false\n",
+            Some(Activation::Agent),
+        ),
+        ("alwaysApply: false\n", Some(Activation::Manual)),
+    ];
+    for (frontmatter, expected) in cases {
+        let content = format!("---\n{}---\n- Keep it short.\n", frontmatter);
+        let item = parse_rule_file(Path::new("sql.mdc"), &content, Targets::Cursor)
+            .expect("parse succeeds");
+        let (title, body, _, _, activation) = fields(&item);
+        assert_eq!(title, "sql", "title from file name");
+        assert_eq!(body, "- Keep it short.");
+        assert_eq!(activation, expected, "frontmatter: {}", frontmatter);
+    }
+}
+
+#[test]
+fn test_invalid_frontmatter_is_error() {
+    let content = "---\nglobs: [unclosed\n---\nbody\n";
+    assert!(parse_rule_file(Path::new("bad.mdc"), content, Targets::Cursor).is_err());
+}
+
+#[test]
+fn test_from_dir_reads_each_rule_file() {
+    let dir = tempdir().unwrap();
+    fs::create_dir_all(dir.path().join("nested")).unwrap();
+    fs::write(dir.path().join("01-style.md"), "# Style\n\nUse tabs.\n").unwrap();
+    fs::write(dir.path().join("nested/testing.txt"), "Write tests.\n").unwrap();
+    fs::write(dir.path().join("notes.json"), "{}").unwrap();
+
+    let items = ClineParser::from_dir(dir.path()).expect("import succeeds");
+    let titles: Vec<&str> = items.iter().map(|item| fields(item).0).collect();
+    assert_eq!(titles, ["Style", "testing"]);
+
+    // Copilotは *.instructions.md のみを読み込む
+    fs::write(dir.path().join("api.instructions.md"), "Document APIs.\n").unwrap();
+    let items = CopilotParser::from_dir(dir.path()).expect("import succeeds");
+    assert_eq!(items.len(), 1);
+    assert_eq!(fields(&items[0]).0, "api");
+}
+
+#[test]
+fn test_unquoted_star_globs_are_accepted() {
+    let content = "---\nglobs: *.ts, **/*.tsx\nalwaysApply: false\n---\nbody\n";
+    let item =
+        parse_rule_file(Path::new("ts.mdc"), content, Targets::Cursor).expect("parse succeeds");
+    let (_, _, _, applies_to, activation) = fields(&item);
+    assert_eq!(applies_to, ["*.ts", "**/*.tsx"]);
+    assert_eq!(activation, Some(Activation::Glob));
+}
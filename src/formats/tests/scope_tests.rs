@@ -0,0 +1,135 @@
+use crate::formats::cline_multiple::ClineMultipleConverter;
+use crate::formats::common::{parse_markdown_instructions, parse_rule_file};
+use crate::formats::copilot_multiple::CopilotMultipleConverter;
+use crate::formats::junie::JunieConverter;
+use crate::formats::{FormatResult, ToFormat};
+use crate::model::types::{
+    InstructionItem, InstructionItemVariant0Targets, InstructionItemVariant2Targets,
+    InstruxConfiguration, Targets,
+};
+use std::collections::HashMap;
+use std::path::Path;
+
+fn scoped(title: &str, body: &str, applies_to: &[&str]) -> InstructionItem {
+    InstructionItem::Variant0 {
+        title: title.to_string(),
+        body: body.to_string(),
+        description: None,
+        applies_to: applies_to.iter().map(|s| s.to_string()).collect(),
+        activation: None,
+        directory: None,
+        filename: None,
+        id: None,
+        disable: false,
+        targets: InstructionItemVariant0Targets::Variant1("all".to_string()),
+    }
+}
+
+/// テスト用のサンプル設定を生成 (対象ファイルを指定したinstructionとグループ)
+fn create_test_config() -> InstruxConfiguration {
+    InstruxConfiguration {
+        instructions: vec![
+            scoped("Errors", "Use thiserror.", &["src/**/*.rs"]),
+            InstructionItem::Variant2 {
+                title: "Frontend".to_string(),
+                instructions: vec![
+                    scoped("Components", "Use function components.", &["*.tsx"]),
+                    scoped("General", "Prefer const.", &[]),
+                ],
+                description: None,
+                applies_to: vec!["web/**".to_string()],
+                activation: None,
+                directory: None,
+                filename: None,
+                id: None,
+                disable: false,
+                targets: InstructionItemVariant2Targets::Variant1("all".to_string()),
+            },
+        ],
+        custom: Default::default(),
+        language: Default::default(),
+        targets: HashMap::new(),
+        sandbox: None,
+        validation: None,
+        version: "0.1.0".parse().expect("Valid version string"),
+    }
+}
+
+fn multiple(converter: &dyn ToFormat) -> HashMap<String, String> {
+    match converter.to_format(&create_test_config()) {
+        Ok(FormatResult::Multiple(files)) => files.into_iter().collect(),
+        _ => panic!("Expected FormatResult::Multiple"),
+    }
+}
+
+fn applies_to(item: &InstructionItem) -> &[String] {
+    match item {
+        InstructionItem::Variant0 { applies_to, .. }
+        | InstructionItem::Variant1 { applies_to, .. }
+        | InstructionItem::Variant2 { applies_to, .. } => applies_to,
+    }
+}
+
+#[test]
+fn test_copilot_multiple_writes_apply_to() {
+    let files = multiple(&CopilotMultipleConverter {
+        filenames: Default::default(),
+    });
+    assert_eq!(
+        files[".github/instructions/Errors.instructions.md"],
+        "---\napplyTo: \"src/**/*.rs\"\n---\n\n# Errors\n\nUse thiserror.\n"
+    );
+    // 入れ子のinstructionはfrontmatterで指定できないため本文の前の文とする
+    let frontend = &files[".github/instructions/Frontend.instructions.md"];
+    assert!(frontend.starts_with("---\napplyTo: \"web/**\"\n---\n\n# Frontend\n\n## Components"));
+    assert!(frontend.contains(
+        "## Components\n\nWhen editing files matching `*.tsx`:\n\nUse function components."
+    ));
+}
+
+#[test]
+fn test_cline_multiple_writes_paths() {
+    let files = multiple(&ClineMultipleConverter {
+        filenames: Default::default(),
+    });
+    let errors = &files[".clinerules/Errors.md"];
+    assert_eq!(
+        errors,
+        "---\npaths:\n  - \"src/**/*.rs\"\n---\n\n# Errors\n\nUse thiserror.\n"
+    );
+    let item = parse_rule_file(Path::new("Errors.md"), errors, Targets::Cline).unwrap();
+    assert_eq!(applies_to(&item), ["src/**/*.rs"]);
+}
+
+#[test]
+fn test_single_file_falls_back_to_preamble_and_parses_back() {
+    let converter = JunieConverter {};
+    let output = match converter.to_format(&create_test_config()) {
+        Ok(FormatResult::Single(text)) => text,
+        _ => panic!("Expected FormatResult::Single"),
+    };
+    assert!(
+        output
+            .contains("## Errors\n\nWhen editing files matching `src/**/*.rs`:\n\nUse thiserror.")
+    );
+    assert!(
+        output.contains("## Frontend\n\nWhen editing files matching `web/**`:\n\n### Components")
+    );
+
+    let items = parse_markdown_instructions(&output, Targets::Junie).unwrap();
+    let errors = &items[0];
+    assert_eq!(applies_to(errors), ["src/**/*.rs"]);
+    assert!(matches!(errors, InstructionItem::Variant0 { body, .. } if body == "Use thiserror."));
+    let InstructionItem::Variant2 {
+        applies_to: group_scope,
+        instructions,
+        ..
+    } = &items[1]
+    else {
+        panic!("Expected Variant2");
+    };
+    assert_eq!(group_scope, &["web/**"]);
+    assert_eq!(instructions.len(), 2, "the preamble is not an extra child");
+    assert_eq!(applies_to(&instructions[0]), ["*.tsx"]);
+    assert!(applies_to(&instructions[1]).is_empty());
+}
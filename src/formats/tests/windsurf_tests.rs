@@ -0,0 +1,131 @@
+use crate::formats::common::parse_rule_file;
+use crate::formats::windsurf_multiple::{RULE_MAX_CHARS, WindsurfMultipleConverter};
+use crate::formats::{FormatResult, ToFormat, windsurf::WindsurfConverter};
+use crate::model::types::{
+    Activation, InstructionItem, InstructionItemVariant0Targets, InstruxConfiguration, Targets,
+};
+use std::collections::HashMap;
+use std::path::Path;
+
+fn instruction(
+    title: &str,
+    body: &str,
+    applies_to: &[&str],
+    activation: Option<Activation>,
+) -> InstructionItem {
+    InstructionItem::Variant0 {
+        title: title.to_string(),
+        body: body.to_string(),
+        description: Some(format!("{} rules", title)),
+        applies_to: applies_to.iter().map(|s| s.to_string()).collect(),
+        activation,
+        directory: None,
+        filename: None,
+        id: None,
+        disable: false,
+        targets: InstructionItemVariant0Targets::Variant1("all".to_string()),
+    }
+}
+
+fn create_test_config(instructions: Vec<InstructionItem>) -> InstruxConfiguration {
+    InstruxConfiguration {
+        instructions,
+        custom: Default::default(),
+        language: Default::default(),
+        targets: HashMap::from([(Targets::Windsurf, Default::default())]),
+        sandbox: None,
+        validation: None,
+        version: "0.1.0".parse().expect("Valid version string"),
+    }
+}
+
+fn render_multiple(config: &InstruxConfiguration) -> HashMap<String, String> {
+    let converter = WindsurfMultipleConverter {
+        filenames: Default::default(),
+    };
+    match converter.to_format(config) {
+        Ok(FormatResult::Multiple(files)) => files.into_iter().collect(),
+        _ => panic!("Expected FormatResult::Multiple"),
+    }
+}
+
+#[test]
+fn test_windsurf_converter_to_format() {
+    let config = create_test_config(vec![instruction("Style", "Use tabs.", &[], None)]);
+    let converter = WindsurfConverter {};
+    let output = match converter.to_format(&config) {
+        Ok(FormatResult::Single(text)) => text,
+        _ => panic!("Expected FormatResult::Single"),
+    };
+    assert!(output.starts_with("# Windsurf Rules\n\n"));
+    assert!(output.contains("## Style\n\nUse tabs."));
+}
+
+#[test]
+fn test_windsurf_multiple_converter_writes_trigger() {
+    let config = create_test_config(vec![
+        instruction("Style", "Use tabs.", &[], None),
+        instruction("Rust", "Avoid unwrap.", &["**/*.rs", "build.rs"], None),
+        instruction("SQL", "Use CTEs.", &[], Some(Activation::Agent)),
+    ]);
+    let files = render_multiple(&config);
+    assert_eq!(
+        files[".windsurf/rules/Style.md"],
+        "---\ntrigger: always_on\ndescription: Style rules\n---\n\n# Style\n\nUse tabs.\n"
+    );
+    assert!(files[".windsurf/rules/Rust.md"].starts_with(
+        "---\ntrigger: glob\ndescription: Rust rules\nglobs: **/*.rs, build.rs\n---\n"
+    ));
+    assert!(files[".windsurf/rules/SQL.md"].starts_with("---\ntrigger: model_decision\n"));
+}
+
+#[test]
+fn test_windsurf_multiple_converter_splits_long_rules() {
+    let paragraph = format!("{}\n\n", "x".repeat(1000));
+    let body = paragraph.repeat(20);
+    let config = create_test_config(vec![instruction("Long", body.trim_end(), &[], None)]);
+    let files = render_multiple(&config);
+
+    let mut paths: Vec<&String> = files.keys().collect();
+    paths.sort();
+    assert_eq!(
+        paths,
+        [".windsurf/rules/Long-2.md", ".windsurf/rules/Long.md"]
+    );
+    for content in files.values() {
+        assert!(content.chars().count() <= RULE_MAX_CHARS);
+        assert!(content.starts_with("---\ntrigger: always_on\n"));
+    }
+    assert!(files[".windsurf/rules/Long-2.md"].contains("# Long (part 2)\n\n"));
+    let total: usize = files.values().map(|c| c.matches('x').count()).sum();
+    assert_eq!(total, 20 * 1000);
+}
+
+#[test]
+fn test_windsurf_trigger_is_activation() {
+    let content = "---\ntrigger: glob\nglobs: src/**/*.ts\n---\n\nUse strict mode.\n";
+    let item = parse_rule_file(Path::new("typescript.md"), content, Targets::Windsurf)
+        .expect("parse succeeds");
+    match item {
+        InstructionItem::Variant0 {
+            title,
+            applies_to,
+            activation,
+            ..
+        } => {
+            assert_eq!(title, "typescript");
+            assert_eq!(applies_to, ["src/**/*.ts"]);
+            assert_eq!(activation, Some(Activation::Glob));
+        }
+        _ => panic!("Expected Variant0"),
+    }
+    let content = "---\ntrigger: model_decision\nglobs: src/**/*.ts\n---\n\nbody\n";
+    let item = parse_rule_file(Path::new("a.md"), content, Targets::Windsurf).unwrap();
+    assert!(matches!(
+        item,
+        InstructionItem::Variant0 {
+            activation: Some(Activation::Agent),
+            ..
+        }
+    ));
+}
@@ -0,0 +1,62 @@
+use super::common;
+use super::{FormatResult, FromFormat, ToFormat};
+use crate::error::Result;
+use crate::formats::common::TargetsChecker;
+use crate::model::types::{InstructionItem, InstruxConfiguration, Targets};
+use std::path::PathBuf;
+
+/// Windsurfが読み込む .windsurfrules の最大文字数
+pub const LEGACY_MAX_CHARS: usize = 6_000;
+
+/// Converter for Windsurf legacy format (.windsurfrules)
+pub struct WindsurfConverter {}
+
+impl ToFormat for WindsurfConverter {
+    fn to_format(&self, config: &InstruxConfiguration) -> Result<FormatResult> {
+        let mut output = String::new();
+
+        // Header
+        output.push_str("# Windsurf Rules\n\n");
+
+        common::process_instructions_common(
+            &mut output,
+            &config.instructions,
+            0,
+            |item| match item {
+                InstructionItem::Variant0 { targets, .. } => {
+                    targets.is_for_target(Targets::Windsurf)
+                }
+                InstructionItem::Variant1 { targets, .. } => {
+                    targets.is_for_target(Targets::Windsurf)
+                }
+                InstructionItem::Variant2 { targets, .. } => {
+                    targets.is_for_target(Targets::Windsurf)
+                }
+            },
+        )?;
+
+        Ok(FormatResult::Single(output))
+    }
+
+    fn get_default_path(&self) -> PathBuf {
+        PathBuf::from(".windsurfrules")
+    }
+
+    fn max_chars(&self) -> Option<usize> {
+        Some(LEGACY_MAX_CHARS)
+    }
+}
+
+/// Parser for Windsurf format
+///
+/// .windsurf/rules/ のルールファイルの trigger / globs / description は activation / applies_to /
+/// description として読み込む。
+pub struct WindsurfParser {}
+
+impl FromFormat for WindsurfParser {
+    const TARGET: Targets = Targets::Windsurf;
+
+    fn from_format(content: &str) -> Result<Vec<InstructionItem>> {
+        common::parse_markdown_instructions(content, Self::TARGET)
+    }
+}
@@ -0,0 +1,136 @@
+use crate::error::Result;
+use crate::formats::common::{self, TargetsChecker, Trigger};
+use crate::formats::{FormatResult, ToFormat};
+use crate::model::types::{FilenameStyle, InstructionItem, InstruxConfiguration, Targets};
+use pulldown_cmark::{Event, Parser};
+use std::path::PathBuf;
+
+/// Windsurfが読み込むルールファイル1つあたりの最大文字数
+pub const RULE_MAX_CHARS: usize = 12_000;
+
+/// WindsurfMultipleConverter
+/// outputMode=multipleの場合、各InstructionItemを個別のルールファイル(.windsurf/rules/*.md)として出力するコンバータ
+///
+/// 最大文字数を超えるルールはブロック単位で複数のファイルに分割する。
+pub struct WindsurfMultipleConverter {
+    /// タイトルからファイル名を決める方法
+    pub filenames: FilenameStyle,
+}
+
+impl WindsurfMultipleConverter {
+    /// ルールファイルのfrontmatter (trigger / description / globs)
+    ///
+    /// activation未指定時は、applies_toがあればglob、なければalways_onとする。
+    fn frontmatter(instruction: &InstructionItem) -> String {
+        let (description, applies_to, activation) = common::metadata(instruction);
+        let trigger = match activation {
+            Some(activation) => Trigger::from(activation),
+            None if !applies_to.is_empty() => Trigger::Glob,
+            None => Trigger::AlwaysOn,
+        };
+        let mut frontmatter = format!("---\ntrigger: {}\n", trigger.as_str());
+        if let Some(description) = description {
+            frontmatter.push_str(&format!("description: {}\n", description));
+        }
+        if !applies_to.is_empty() {
+            frontmatter.push_str(&format!("globs: {}\n", applies_to.join(", ")));
+        }
+        frontmatter.push_str("---\n\n");
+        frontmatter
+    }
+
+    /// Markdownを最上位のブロック単位で、それぞれlimit文字以内のまとまりに分割する
+    ///
+    /// 1つのブロックだけでlimitを超える場合は分割しない。
+    fn split_blocks(content: &str, limit: usize) -> Vec<String> {
+        let mut depth = 0;
+        let mut starts = Vec::new();
+        for (event, range) in Parser::new(content).into_offset_iter() {
+            match event {
+                Event::Start(_) => {
+                    if depth == 0 {
+                        starts.push(range.start);
+                    }
+                    depth += 1;
+                }
+                Event::End(_) => depth -= 1,
+                _ if depth == 0 => starts.push(range.start),
+                _ => {}
+            }
+        }
+        starts.push(content.len());
+
+        let mut parts: Vec<String> = Vec::new();
+        let mut current = String::new();
+        let mut block_start = 0;
+        for end in starts.into_iter().skip(1) {
+            let block = &content[block_start..end];
+            block_start = end;
+            if !current.is_empty() && current.chars().count() + block.chars().count() > limit {
+                parts.push(std::mem::take(&mut current));
+            }
+            current.push_str(block);
+        }
+        if !current.is_empty() || parts.is_empty() {
+            parts.push(current);
+        }
+        parts
+    }
+}
+
+impl ToFormat for WindsurfMultipleConverter {
+    /// 複数ファイル形式で出力する
+    /// 戻り値: FormatResult::Multiple(ファイルパス→内容のマップ)
+    fn to_format(&self, config: &InstruxConfiguration) -> Result<FormatResult> {
+        let mut files = Vec::new();
+        for instruction in &config.instructions {
+            // Windsurfターゲットのみ対象
+            let is_windsurf = match instruction {
+                InstructionItem::Variant0 {
+                    targets, disable, ..
+                } => !*disable && targets.is_for_target(Targets::Windsurf),
+                InstructionItem::Variant1 {
+                    targets, disable, ..
+                } => !*disable && targets.is_for_target(Targets::Windsurf),
+                InstructionItem::Variant2 {
+                    targets, disable, ..
+                } => !*disable && targets.is_for_target(Targets::Windsurf),
+            };
+            if !is_windsurf {
+                continue;
+            }
+            let title = match instruction {
+                InstructionItem::Variant0 { title, .. } => title,
+                InstructionItem::Variant1 { title, .. } => title,
+                InstructionItem::Variant2 { title, .. } => title,
+            };
+            let stem = common::file_stem(instruction, self.filenames);
+            let frontmatter = Self::frontmatter(instruction);
+            let content = common::instruction_to_md(instruction, 1);
+            let limit = RULE_MAX_CHARS.saturating_sub(frontmatter.chars().count());
+            let parts = Self::split_blocks(&content, limit);
+            for (i, part) in parts.iter().enumerate() {
+                let (file_path, part) = if i == 0 {
+                    (format!(".windsurf/rules/{}.md", stem), part.clone())
+                } else {
+                    (
+                        format!(".windsurf/rules/{}-{}.md", stem, i + 1),
+                        format!("# {} (part {})\n\n{}", title, i + 1, part),
+                    )
+                };
+                files.push((file_path, format!("{}{}", frontmatter, part)));
+            }
+        }
+        Ok(FormatResult::Multiple(files))
+    }
+
+    /// Get the default file path for the target format
+    /// 複数ファイル出力の場合は格納ディレクトリを返す
+    fn get_default_path(&self) -> PathBuf {
+        PathBuf::from(".windsurf/rules")
+    }
+
+    fn max_chars(&self) -> Option<usize> {
+        Some(RULE_MAX_CHARS)
+    }
+}
@@ -1,7 +1,12 @@
 mod diff;
+mod error;
 mod formats;
+mod manifest;
 mod model;
 mod opts;
+mod sandbox;
+mod transaction;
+mod validation;
 mod commands {
     pub mod diff;
     pub mod generate;
@@ -17,29 +22,28 @@ use opts::{Cli, Commands};
 
 fn main() {
     let cli = Cli::parse();
-    match cli.command {
+    let (name, result) = match cli.command {
         Commands::Generate {
             dry_run,
             overwrite,
             force,
             watch,
+            check,
         } => {
-            if let Err(e) = commands::generate::run(dry_run, overwrite, force, watch) {
-                eprintln!("{}", e);
-                std::process::exit(1);
-            }
-        }
-        Commands::Init { from } => {
-            commands::init::run(from);
-        }
-        Commands::Merge { from } => {
-            commands::merge::run(from);
-        }
-        Commands::Diff { tool } => {
-            commands::diff::run(tool);
-        }
-        Commands::Validate => {
-            commands::validate::run();
+            let result = if check {
+                commands::generate::check()
+            } else {
+                commands::generate::run(dry_run, overwrite, force, watch)
+            };
+            ("generate", result)
         }
+        Commands::Init { from, force } => ("init", commands::init::run(from, force)),
+        Commands::Merge { from } => ("merge", commands::merge::run(from)),
+        Commands::Diff { tool } => ("diff", commands::diff::run(tool)),
+        Commands::Validate => ("validate", commands::validate::run()),
+    };
+    if let Err(e) = result {
+        eprintln!("[{}] {}", name, e);
+        std::process::exit(e.exit_code());
     }
 }
@@ -0,0 +1,205 @@
+use crate::error::{InstruxError, Result};
+use crate::formats;
+use crate::model::types::TargetName;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// マニフェストファイルのデフォルトパス
+pub const DEFAULT_MANIFEST_PATH: &str = ".instrux/manifest.json";
+
+/// generateが出力したファイルの記録 (.instrux/manifest.json)
+///
+/// 出力パスごとにターゲット・生成元設定のハッシュ・出力内容のハッシュを保持し、
+/// instruxが生成したファイルか、生成後に手動で変更されたかを判定するために使う。
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Manifest {
+    /// 出力パス→記録のマップ (パス順に保存される)
+    #[serde(default)]
+    pub files: BTreeMap<String, ManifestEntry>,
+}
+
+/// 出力ファイル1件分の記録
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestEntry {
+    /// 出力元のターゲット (組み込みまたはcustomのターゲット名)
+    pub target: TargetName,
+    /// 生成時のinstrux.yamlのハッシュ
+    pub config_hash: String,
+    /// 出力内容のハッシュ (管理ブロックのみ出力した場合はブロックの内容のハッシュ)
+    pub content_hash: String,
+    /// 管理ブロック (`managed: block`) だけを出力したファイルか
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub managed: bool,
+}
+
+/// ディスク上のファイルとマニフェストの照合結果
+#[derive(Debug, PartialEq)]
+pub enum FileStatus {
+    /// マニフェストに記録されていない
+    Untracked,
+    /// 生成時の内容から変更されていない
+    Unmodified,
+    /// 生成後に変更されている
+    Modified,
+}
+
+impl Manifest {
+    /// マニフェストを読み込む (存在しない場合は空)
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(path)
+            .map_err(|e| InstruxError::io("マニフェストの読み込み", path, e))?;
+        serde_json::from_str(&content).map_err(|e| InstruxError::Manifest {
+            path: path.to_path_buf(),
+            message: e.to_string(),
+        })
+    }
+
+    /// マニフェストファイルに書き出す内容
+    pub fn to_json(&self) -> String {
+        let json = serde_json::to_string_pretty(self).expect("manifest is serializable");
+        format!("{}\n", json)
+    }
+
+    /// 出力ファイルを記録する
+    pub fn record(
+        &mut self,
+        path: &Path,
+        target: impl Into<TargetName>,
+        config_hash: &str,
+        content: &str,
+    ) {
+        self.files.insert(
+            manifest_key(path),
+            ManifestEntry {
+                target: target.into(),
+                config_hash: config_hash.to_string(),
+                content_hash: hash_content(content),
+                managed: false,
+            },
+        );
+    }
+
+    /// 管理ブロックだけを出力したファイルを記録する (ブロックの外の変更は手動の変更とみなさない)
+    pub fn record_block(
+        &mut self,
+        path: &Path,
+        target: impl Into<TargetName>,
+        config_hash: &str,
+        content: &str,
+    ) {
+        self.files.insert(
+            manifest_key(path),
+            ManifestEntry {
+                target: target.into(),
+                config_hash: config_hash.to_string(),
+                content_hash: hash_content(formats::managed_block(content).unwrap_or_default()),
+                managed: true,
+            },
+        );
+    }
+
+    /// 記録済みのファイルの内容ハッシュをディスク上の内容で更新する
+    ///
+    /// mergeで変更を設定に取り込んだファイルを、手動で変更されたファイルとみなさないようにする。
+    /// 記録を更新した場合は `true` を返す (記録されていない・変更されていないファイルは `false`)。
+    pub fn refresh(&mut self, path: &Path, on_disk: &str) -> bool {
+        let Some(entry) = self.files.get_mut(&manifest_key(path)) else {
+            return false;
+        };
+        let content_hash = hash_content(tracked_content(entry, on_disk));
+        if entry.content_hash == content_hash {
+            return false;
+        }
+        entry.content_hash = content_hash;
+        true
+    }
+
+    /// 指定パスの記録を取得する
+    pub fn entry(&self, path: &Path) -> Option<&ManifestEntry> {
+        self.files.get(&manifest_key(path))
+    }
+
+    /// 指定パスの記録を削除する
+    pub fn remove(&mut self, path: &Path) {
+        self.files.remove(&manifest_key(path));
+    }
+
+    /// 記録済みだが今回の出力に含まれないファイル (孤立ファイル) をパス順に返す
+    pub fn orphans(&self, produced: &[PathBuf]) -> Vec<(PathBuf, TargetName)> {
+        let produced: Vec<String> = produced.iter().map(|p| manifest_key(p)).collect();
+        self.files
+            .iter()
+            .filter(|(key, _)| !produced.contains(key))
+            .map(|(key, entry)| (PathBuf::from(key), entry.target.clone()))
+            .collect()
+    }
+
+    /// ディスク上の内容がマニフェストの記録と一致するか判定する
+    pub fn status(&self, path: &Path, on_disk: &str) -> FileStatus {
+        match self.entry(path) {
+            None => FileStatus::Untracked,
+            Some(entry) if entry.content_hash == hash_content(tracked_content(entry, on_disk)) => {
+                FileStatus::Unmodified
+            }
+            Some(_) => FileStatus::Modified,
+        }
+    }
+}
+
+/// 記録と照合する内容 (管理ブロックのみ出力したファイルはブロックの内容)
+fn tracked_content<'a>(entry: &ManifestEntry, on_disk: &'a str) -> &'a str {
+    if entry.managed {
+        formats::managed_block(on_disk).unwrap_or_default()
+    } else {
+        on_disk
+    }
+}
+
+/// 内容のSHA-256ハッシュを16進文字列で返す
+pub fn hash_content(content: &str) -> String {
+    format!("{:x}", Sha256::digest(content.as_bytes()))
+}
+
+/// OSによらず同じキーになるようにパス区切りを`/`に揃える
+fn manifest_key(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FileStatus, Manifest};
+    use crate::model::types::Targets;
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn test_manifest_status() {
+        let mut manifest = Manifest::default();
+        let path = Path::new("AGENTS.md");
+        assert_eq!(manifest.status(path, "a"), FileStatus::Untracked);
+
+        manifest.record(path, Targets::Agentsmd, "config", "a");
+        assert_eq!(manifest.status(path, "a"), FileStatus::Unmodified);
+        assert_eq!(manifest.status(path, "b"), FileStatus::Modified);
+    }
+
+    #[test]
+    fn test_manifest_orphans() {
+        let mut manifest = Manifest::default();
+        manifest.record(Path::new(".clinerules/a.md"), Targets::Cline, "c", "a");
+        manifest.record(Path::new(".clinerules/b.md"), Targets::Cline, "c", "b");
+
+        let orphans = manifest.orphans(&[PathBuf::from(".clinerules/a.md")]);
+        assert_eq!(
+            orphans,
+            vec![(PathBuf::from(".clinerules/b.md"), Targets::Cline.into())]
+        );
+    }
+}
@@ -1,9 +1,31 @@
 use std::fs;
+use std::io::ErrorKind;
 use std::path::Path;
 
+use crate::error::{InstruxError, Result};
 use crate::model::types::InstruxConfiguration;
 
-pub fn parse_instrux_yaml<P: AsRef<Path>>(path: P) -> Result<InstruxConfiguration, String> {
-    let content = fs::read_to_string(&path).map_err(|e| format!("ファイル読み込み失敗: {}", e))?;
-    serde_yaml::from_str(&content).map_err(|e| format!("YAMLパース失敗: {}", e))
+pub fn parse_instrux_yaml<P: AsRef<Path>>(path: P) -> Result<InstruxConfiguration> {
+    let path = path.as_ref();
+    let content = read_instrux_yaml(path)?;
+    parse_instrux_yaml_str(&content, path)
+}
+
+/// instrux設定ファイルを読み込む (存在しない場合はConfigNotFound)
+pub fn read_instrux_yaml<P: AsRef<Path>>(path: P) -> Result<String> {
+    let path = path.as_ref();
+    fs::read_to_string(path).map_err(|e| match e.kind() {
+        ErrorKind::NotFound => InstruxError::ConfigNotFound(path.to_path_buf()),
+        _ => InstruxError::io("設定ファイルの読み込み", path, e),
+    })
+}
+
+/// 文字列からinstrux設定をパースする
+///
+/// `path` はエラー表示に使う設定ファイルのパス
+pub fn parse_instrux_yaml_str(content: &str, path: &Path) -> Result<InstruxConfiguration> {
+    serde_yaml::from_str(content).map_err(|e| InstruxError::Parse {
+        path: path.to_path_buf(),
+        message: format!("YAMLパース失敗: {}", e),
+    })
 }
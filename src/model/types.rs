@@ -1,2 +1,48 @@
 #![allow(clippy::all, dead_code)]
 include!("../../generated/models.rs");
+
+impl ::std::fmt::Display for TargetName {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        match self {
+            TargetName::Targets(target) => target.fmt(f),
+            TargetName::CustomTargetName(name) => f.write_str(name),
+        }
+    }
+}
+
+// instructionのtargetsに含まれるターゲット名は比較・並べ替えに使う
+// (生成コードのderiveはtypifyのバージョンに依存するため、ここで実装する)
+impl ::std::cmp::Ord for TargetName {
+    fn cmp(&self, other: &Self) -> ::std::cmp::Ordering {
+        use ::std::cmp::Ordering;
+        match (self, other) {
+            (TargetName::Targets(a), TargetName::Targets(b)) => a.cmp(b),
+            (TargetName::CustomTargetName(a), TargetName::CustomTargetName(b)) => a.cmp(b),
+            (TargetName::Targets(_), TargetName::CustomTargetName(_)) => Ordering::Less,
+            (TargetName::CustomTargetName(_), TargetName::Targets(_)) => Ordering::Greater,
+        }
+    }
+}
+
+impl ::std::cmp::PartialOrd for TargetName {
+    fn partial_cmp(&self, other: &Self) -> Option<::std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl ::std::cmp::PartialEq for TargetName {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other).is_eq()
+    }
+}
+
+impl ::std::cmp::Eq for TargetName {}
+
+impl ::std::hash::Hash for TargetName {
+    fn hash<H: ::std::hash::Hasher>(&self, state: &mut H) {
+        match self {
+            TargetName::Targets(target) => target.hash(state),
+            TargetName::CustomTargetName(name) => name.hash(state),
+        }
+    }
+}
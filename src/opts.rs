@@ -24,10 +24,17 @@ pub enum Commands {
         /// watch: ファイル変更を監視して自動生成
         #[arg(long)]
         watch: bool,
+        /// check: ファイルを生成せず、生成結果が最新か検証 (最新でなければ終了コード1)
+        #[arg(long, conflicts_with_all = ["dry_run", "overwrite", "force", "watch"])]
+        check: bool,
     },
     Init {
+        /// from: 指定形式の instruction ファイルから設定を生成 (未指定時はひな形を生成)
         #[arg(long, value_name = "tool-name")]
         from: Option<String>,
+        /// force: 既存の .instrux/instrux.yaml を上書き
+        #[arg(long)]
+        force: bool,
     },
     Merge {
         #[arg(long, value_name = "tool-name")]
@@ -0,0 +1,191 @@
+use crate::error::{InstruxError, Result};
+use crate::model::types::{InstructionItem, InstruxConfiguration};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// body_fileの格納ディレクトリ
+pub const INSTRUCTIONS_DIR: &str = ".instrux/instructions";
+
+/// instruxが読み書きできるパスの範囲
+///
+/// body_fileは `.instrux/instructions/` 配下、生成ファイルはリポジトリのルート配下に限る。
+/// `..` やシンボリックリンクを解決したうえで判定し、範囲外のパスは設定の `sandbox.allow`
+/// で明示的に許可したものだけを受け付ける。
+pub struct Sandbox {
+    /// リポジトリのルート (解決済み)
+    root: PathBuf,
+    /// body_fileの格納ディレクトリ (解決済み)
+    instructions_dir: PathBuf,
+    /// 範囲外でも許可するパス (解決済み)
+    allow: Vec<PathBuf>,
+}
+
+impl Sandbox {
+    /// リポジトリのルートを基準にsandboxを作成する
+    ///
+    /// # 引数
+    /// * `root` - リポジトリのルート (生成ファイルの出力を許可する範囲)
+    /// * `instructions_dir` - body_file の格納ディレクトリ (`root` からの相対パス)
+    /// * `config` - `sandbox.allow` を読み込む設定
+    pub fn new(root: &Path, instructions_dir: &Path, config: &InstruxConfiguration) -> Self {
+        let root = resolve(&absolute(root));
+        let allow = config
+            .sandbox
+            .iter()
+            .flat_map(|sandbox| &sandbox.allow)
+            .map(|path| resolve(&root.join(path)))
+            .collect();
+        Sandbox {
+            instructions_dir: resolve(&root.join(instructions_dir)),
+            root,
+            allow,
+        }
+    }
+
+    /// カレントディレクトリをリポジトリのルートとするsandbox
+    pub fn current(config: &InstruxConfiguration) -> Self {
+        Self::new(Path::new("."), Path::new(INSTRUCTIONS_DIR), config)
+    }
+
+    /// body_fileが格納ディレクトリ (または許可したパス) の中を指しているか
+    pub fn allows_body_file(&self, body_file: &str) -> bool {
+        self.allows(
+            &self.instructions_dir.join(body_file),
+            &self.instructions_dir,
+        )
+    }
+
+    /// 出力先がリポジトリのルート (または許可したパス) の中を指しているか
+    pub fn allows_output(&self, path: &Path) -> bool {
+        self.allows(&self.root.join(path), &self.root)
+    }
+
+    /// 出力先を検証する (範囲外ならエラー)
+    pub fn check_output(&self, path: &Path) -> Result<()> {
+        if self.allows_output(path) {
+            return Ok(());
+        }
+        Err(InstruxError::OutsideSandbox {
+            path: path.to_path_buf(),
+            base: "リポジトリのルート".to_string(),
+        })
+    }
+
+    /// 全instructionのbody_fileを検証する (範囲外のものがあればエラー)
+    pub fn check_body_files(&self, items: &[InstructionItem]) -> Result<()> {
+        for item in items {
+            match item {
+                InstructionItem::Variant1 { body_file, .. }
+                    if !self.allows_body_file(body_file) =>
+                {
+                    return Err(InstruxError::OutsideSandbox {
+                        path: PathBuf::from(body_file),
+                        base: INSTRUCTIONS_DIR.to_string(),
+                    });
+                }
+                InstructionItem::Variant2 { instructions, .. } => {
+                    self.check_body_files(instructions)?;
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn allows(&self, path: &Path, base: &Path) -> bool {
+        let path = resolve(path);
+        path.starts_with(base) || self.allow.iter().any(|allowed| path.starts_with(allowed))
+    }
+}
+
+/// `..` やシンボリックリンクを解決したうえで、`path` が `base` の中を指しているか
+pub fn is_within(path: &Path, base: &Path) -> bool {
+    resolve(&absolute(path)).starts_with(resolve(&absolute(base)))
+}
+
+/// カレントディレクトリを基準に絶対パスにする
+fn absolute(path: &Path) -> PathBuf {
+    std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// `.` `..` とシンボリックリンクを解決したパス
+///
+/// 存在しない部分 (これから出力するファイルなど) は字句的に解決する。
+fn resolve(path: &Path) -> PathBuf {
+    let mut resolved = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                resolved.pop();
+            }
+            component => {
+                resolved.push(component);
+                // 存在するパスはシンボリックリンクの実体に置き換える
+                if let Ok(canonical) = fs::canonicalize(&resolved) {
+                    resolved = canonical;
+                }
+            }
+        }
+    }
+    resolved
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Sandbox;
+    use crate::model::parser;
+    use std::fs;
+    use std::path::Path;
+
+    fn create_sandbox(root: &Path, allow: &str) -> Sandbox {
+        let content = format!(
+            "version: 1.0.0\ntargets: {{}}\nsandbox:\n  allow: [{}]\ninstructions:\n  - title: A\n    body: a\n",
+            allow
+        );
+        let config = parser::parse_instrux_yaml_str(&content, Path::new("instrux.yaml")).unwrap();
+        Sandbox::new(root, Path::new(".instrux/instructions"), &config)
+    }
+
+    #[test]
+    fn test_sandbox_confines_body_files_and_outputs() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("repo");
+        fs::create_dir_all(root.join(".instrux/instructions")).unwrap();
+        let sandbox = create_sandbox(&root, "");
+
+        assert!(sandbox.allows_body_file("coding.md"));
+        assert!(sandbox.allows_body_file("group/../coding.md"));
+        assert!(!sandbox.allows_body_file("../../../etc/passwd"));
+        assert!(!sandbox.allows_body_file("/etc/passwd"));
+        assert!(!sandbox.allows_body_file("../instrux.yaml"));
+
+        assert!(sandbox.allows_output(Path::new(".clinerules/a.md")));
+        assert!(sandbox.allows_output(Path::new("./docs/../AGENTS.md")));
+        assert!(!sandbox.allows_output(Path::new("../outside.md")));
+        assert!(!sandbox.allows_output(Path::new("/tmp/outside.md")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_sandbox_resolves_symlinks_and_allow_list() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("repo");
+        let shared = dir.path().join("shared");
+        fs::create_dir_all(root.join(".instrux/instructions")).unwrap();
+        fs::create_dir_all(&shared).unwrap();
+        fs::write(shared.join("rules.md"), "shared").unwrap();
+        std::os::unix::fs::symlink(&shared, root.join(".instrux/instructions/link")).unwrap();
+
+        // シンボリックリンク経由で外のファイルを指すものは許可しない
+        let sandbox = create_sandbox(&root, "");
+        assert!(!sandbox.allows_body_file("link/rules.md"));
+        assert!(!sandbox.allows_output(Path::new(".instrux/instructions/link/out.md")));
+
+        // sandbox.allow で明示したパスは許可する
+        let sandbox = create_sandbox(&root, "../shared");
+        assert!(sandbox.allows_body_file("link/rules.md"));
+        assert!(sandbox.allows_body_file("../../../shared/rules.md"));
+        assert!(sandbox.allows_output(Path::new("../shared/out.md")));
+    }
+}
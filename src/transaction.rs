@@ -0,0 +1,292 @@
+use crate::error::{InstruxError, Result};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// generateのファイル操作をまとめて適用するトランザクション
+///
+/// 書き込む内容はステージング用ディレクトリの一時ファイルに書き出しておき、`commit` で
+/// 一時ファイルのリネームによって配置する。置き換え・削除するファイルはステージング用ディレクトリに
+/// 退避し、途中で失敗した場合は逆順に元に戻す。`commit` しなかった操作はディスクに反映しない。
+pub struct Transaction {
+    /// 一時ファイル・退避したファイルを置くディレクトリ
+    staging_dir: PathBuf,
+    /// 適用する操作 (登録順に適用する)
+    ops: Vec<Op>,
+    /// 一時ファイル・退避先の連番
+    counter: usize,
+}
+
+/// ファイル操作
+enum Op {
+    /// ステージングした内容をパスに配置する (親ディレクトリは必要に応じて作成)
+    Write { staged: PathBuf, path: PathBuf },
+    /// ファイルまたはディレクトリを削除する
+    Remove(PathBuf),
+    /// ファイルまたはディレクトリを移動する (.bakへのバックアップ)
+    Rename { from: PathBuf, to: PathBuf },
+    /// ディレクトリを作成する
+    CreateDir(PathBuf),
+}
+
+/// 適用した操作を元に戻すための記録
+enum Undo {
+    /// 作成したファイル・ディレクトリを削除する
+    Created(PathBuf),
+    /// `from` から `to` へ移動したものを戻す
+    Moved { from: PathBuf, to: PathBuf },
+}
+
+impl Transaction {
+    /// ステージング用ディレクトリを指定してトランザクションを開始する
+    pub fn new(staging_dir: impl Into<PathBuf>) -> Self {
+        Transaction {
+            staging_dir: staging_dir.into(),
+            ops: Vec::new(),
+            counter: 0,
+        }
+    }
+
+    /// ファイルへの書き込みを登録する (内容は一時ファイルに書き出しておく)
+    pub fn write(&mut self, path: &Path, content: &str) -> Result<()> {
+        fs::create_dir_all(&self.staging_dir)
+            .map_err(|e| InstruxError::io("ディレクトリ作成", &self.staging_dir, e))?;
+        let staged = self.next_staging_path();
+        fs::write(&staged, content).map_err(|e| InstruxError::io("一時ファイル出力", path, e))?;
+        self.ops.push(Op::Write {
+            staged,
+            path: path.to_path_buf(),
+        });
+        Ok(())
+    }
+
+    /// ファイルまたはディレクトリの削除を登録する
+    pub fn remove(&mut self, path: &Path) {
+        self.ops.push(Op::Remove(path.to_path_buf()));
+    }
+
+    /// ファイルまたはディレクトリの移動を登録する
+    pub fn rename(&mut self, from: &Path, to: &Path) {
+        self.ops.push(Op::Rename {
+            from: from.to_path_buf(),
+            to: to.to_path_buf(),
+        });
+    }
+
+    /// ディレクトリの作成を登録する
+    pub fn create_dir_all(&mut self, path: &Path) {
+        self.ops.push(Op::CreateDir(path.to_path_buf()));
+    }
+
+    /// 登録した操作が変更するパス (登録順、重複なし)
+    pub fn paths(&self) -> Vec<PathBuf> {
+        let mut paths: Vec<PathBuf> = Vec::new();
+        for op in &self.ops {
+            let changed: &[&PathBuf] = match op {
+                Op::Write { path, .. } | Op::Remove(path) | Op::CreateDir(path) => &[path],
+                Op::Rename { from, to } => &[from, to],
+            };
+            for path in changed {
+                if !paths.contains(path) {
+                    paths.push((*path).clone());
+                }
+            }
+        }
+        paths
+    }
+
+    /// 登録した操作をすべて適用する
+    ///
+    /// 途中で失敗した場合は適用済みの操作を元に戻し、最初のエラーを返す。
+    pub fn commit(mut self) -> Result<()> {
+        let mut undo = Vec::new();
+        let ops = std::mem::take(&mut self.ops);
+        for op in &ops {
+            if let Err((action, path, e)) = self.apply(op, &mut undo) {
+                rollback(undo);
+                return Err(InstruxError::io(action, path, e));
+            }
+        }
+        Ok(())
+    }
+
+    fn apply(
+        &mut self,
+        op: &Op,
+        undo: &mut Vec<Undo>,
+    ) -> std::result::Result<(), (&'static str, PathBuf, io::Error)> {
+        match op {
+            Op::Write { staged, path } => {
+                if let Some(parent) = path.parent() {
+                    create_dirs(parent, undo)
+                        .map_err(|e| ("ディレクトリ作成", parent.into(), e))?;
+                }
+                self.set_aside(path, undo)
+                    .map_err(|e| ("既存ファイルの退避", path.clone(), e))?;
+                move_path(staged, path).map_err(|e| ("ファイル出力", path.clone(), e))?;
+                undo.push(Undo::Created(path.clone()));
+            }
+            Op::Remove(path) => {
+                self.set_aside(path, undo)
+                    .map_err(|e| ("既存ファイルの削除", path.clone(), e))?;
+            }
+            Op::Rename { from, to } => {
+                self.set_aside(to, undo)
+                    .map_err(|e| ("既存ファイルの退避", to.clone(), e))?;
+                move_path(from, to).map_err(|e| ("バックアップ作成", from.clone(), e))?;
+                undo.push(Undo::Moved {
+                    from: from.clone(),
+                    to: to.clone(),
+                });
+            }
+            Op::CreateDir(path) => {
+                create_dirs(path, undo).map_err(|e| ("ディレクトリ作成", path.clone(), e))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 既存のファイル・ディレクトリをステージング用ディレクトリに退避する (元に戻せるように削除の代わりに使う)
+    fn set_aside(&mut self, path: &Path, undo: &mut Vec<Undo>) -> io::Result<()> {
+        if fs::symlink_metadata(path).is_err() {
+            return Ok(());
+        }
+        let saved = self.next_staging_path();
+        move_path(path, &saved)?;
+        undo.push(Undo::Moved {
+            from: path.to_path_buf(),
+            to: saved,
+        });
+        Ok(())
+    }
+
+    fn next_staging_path(&mut self) -> PathBuf {
+        self.counter += 1;
+        self.staging_dir.join(self.counter.to_string())
+    }
+}
+
+impl Drop for Transaction {
+    /// 一時ファイル・退避したファイルを片付ける
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.staging_dir);
+    }
+}
+
+/// 存在しないディレクトリを親から順に作成し、作成したものを記録する
+fn create_dirs(path: &Path, undo: &mut Vec<Undo>) -> io::Result<()> {
+    let missing: Vec<&Path> = path
+        .ancestors()
+        .take_while(|dir| !dir.as_os_str().is_empty() && fs::symlink_metadata(dir).is_err())
+        .collect();
+    for dir in missing.into_iter().rev() {
+        fs::create_dir(dir)?;
+        undo.push(Undo::Created(dir.to_path_buf()));
+    }
+    Ok(())
+}
+
+/// ファイル・ディレクトリを移動する (別のファイルシステムへはファイルのみコピーして削除する)
+fn move_path(from: &Path, to: &Path) -> io::Result<()> {
+    match fs::rename(from, to) {
+        Err(e) if e.kind() == io::ErrorKind::CrossesDevices && from.is_file() => {
+            fs::copy(from, to)?;
+            fs::remove_file(from)
+        }
+        result => result,
+    }
+}
+
+/// 適用済みの操作を逆順に元に戻す
+fn rollback(undo: Vec<Undo>) {
+    for undo in undo.into_iter().rev() {
+        let (path, result) = match undo {
+            Undo::Created(path) => {
+                let result = if path.is_dir() {
+                    fs::remove_dir(&path)
+                } else {
+                    fs::remove_file(&path)
+                };
+                (path, result)
+            }
+            Undo::Moved { from, to } => {
+                let result = move_path(&to, &from);
+                (from, result)
+            }
+        };
+        if let Err(e) = result {
+            eprintln!(
+                "[generate] 警告: {} を元に戻せませんでした: {}",
+                path.display(),
+                e
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Transaction;
+    use std::fs;
+
+    #[test]
+    fn test_commit_applies_operations_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::write(root.join("old.md"), "old").unwrap();
+        fs::write(root.join("keep.md"), "keep").unwrap();
+
+        let mut tx = Transaction::new(root.join("staging"));
+        tx.write(&root.join("docs/new.md"), "new").unwrap();
+        tx.rename(&root.join("keep.md"), &root.join("keep.md.bak"));
+        tx.write(&root.join("keep.md"), "updated").unwrap();
+        tx.remove(&root.join("old.md"));
+        // commit前はディスクに反映しない
+        assert!(!root.join("docs").exists());
+        tx.commit().unwrap();
+
+        assert_eq!(fs::read_to_string(root.join("docs/new.md")).unwrap(), "new");
+        assert_eq!(fs::read_to_string(root.join("keep.md")).unwrap(), "updated");
+        assert_eq!(
+            fs::read_to_string(root.join("keep.md.bak")).unwrap(),
+            "keep"
+        );
+        assert!(!root.join("old.md").exists());
+        assert!(!root.join("staging").exists());
+    }
+
+    #[test]
+    fn test_commit_rolls_back_on_failure() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::write(root.join("keep.md"), "keep").unwrap();
+        fs::create_dir(root.join("rules")).unwrap();
+        fs::write(root.join("rules/a.md"), "a").unwrap();
+        // ファイルの下にはディレクトリを作れないため、最後の書き込みが失敗する
+        fs::write(root.join("blocker"), "").unwrap();
+
+        let mut tx = Transaction::new(root.join("staging"));
+        tx.rename(&root.join("keep.md"), &root.join("keep.md.bak"));
+        tx.write(&root.join("keep.md"), "updated").unwrap();
+        tx.remove(&root.join("rules"));
+        tx.write(&root.join("docs/new.md"), "new").unwrap();
+        tx.write(&root.join("blocker/x.md"), "x").unwrap();
+        assert_eq!(
+            tx.paths(),
+            [
+                root.join("keep.md"),
+                root.join("keep.md.bak"),
+                root.join("rules"),
+                root.join("docs/new.md"),
+                root.join("blocker/x.md"),
+            ]
+        );
+        assert!(tx.commit().is_err());
+
+        assert_eq!(fs::read_to_string(root.join("keep.md")).unwrap(), "keep");
+        assert!(!root.join("keep.md.bak").exists());
+        assert_eq!(fs::read_to_string(root.join("rules/a.md")).unwrap(), "a");
+        assert!(!root.join("docs").exists());
+        assert!(!root.join("staging").exists());
+    }
+}
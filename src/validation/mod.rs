@@ -0,0 +1,78 @@
+use crate::model::types::InstruxConfiguration;
+use std::fmt;
+use std::path::Path;
+
+pub mod rules;
+pub mod schema;
+pub mod yaml;
+
+/// 検証で見つかった問題の重大度
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+/// 設定ファイルの検証で見つかった問題
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    /// 行番号 (1始まり)
+    pub line: usize,
+    /// 列番号 (1始まり)
+    pub column: usize,
+    /// YAML上のパス (例: `instructions[2].instructions[0].body_fil`)
+    pub path: String,
+    pub message: String,
+    /// 検出したルールのID (`syntax`, `schema` または意味検証ルールのID)
+    pub rule: String,
+    pub severity: Severity,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = if self.path.is_empty() {
+            "(root)"
+        } else {
+            &self.path
+        };
+        write!(
+            f,
+            "{}:{}: {}[{}]: {}: {}",
+            self.line, self.column, self.severity, self.rule, path, self.message
+        )
+    }
+}
+
+/// YAML文字列を読み込み、スキーマで検証する
+pub fn validate_schema(content: &str) -> Vec<Diagnostic> {
+    match yaml::load(content) {
+        Ok(root) => schema::validate(&root),
+        Err(diagnostic) => vec![diagnostic],
+    }
+}
+
+/// スキーマ検証済みのYAML文字列を意味検証ルールで検証する
+///
+/// # 引数
+/// * `content` - instrux.yamlの内容 (問題の位置を特定するために使う)
+/// * `config` - `content` を読み込んだ設定
+/// * `instructions_dir` - body_file の格納ディレクトリ
+pub fn validate_rules(
+    content: &str,
+    config: &InstruxConfiguration,
+    instructions_dir: &Path,
+) -> Vec<Diagnostic> {
+    match yaml::load(content) {
+        Ok(root) => rules::check(config, &root, instructions_dir),
+        Err(diagnostic) => vec![diagnostic],
+    }
+}
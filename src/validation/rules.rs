@@ -0,0 +1,820 @@
+use super::yaml::{self, Node};
+use super::{Diagnostic, Severity};
+use crate::formats;
+use crate::model::types::{
+    CustomTargetOutputMode, InstructionItem, InstructionItemVariant0Targets,
+    InstructionItemVariant1Targets, InstructionItemVariant2Targets, InstruxConfiguration,
+    RuleSeverity, TargetName, Targets, ValidationRule,
+};
+use crate::sandbox::Sandbox;
+use std::collections::HashSet;
+use std::path::{Component, Path, PathBuf};
+
+/// 意味検証ルール
+pub struct Rule {
+    pub id: ValidationRule,
+    /// 設定で上書きされない場合の重大度
+    pub default_severity: Severity,
+}
+
+/// 意味検証ルールの一覧
+pub const RULES: &[Rule] = &[
+    // body_file が .instrux/instructions/ に存在しない
+    Rule {
+        id: ValidationRule::MissingBodyFile,
+        default_severity: Severity::Error,
+    },
+    // 同じ階層に同じタイトルの instruction がある (multiple モードでファイル名が衝突する)
+    Rule {
+        id: ValidationRule::DuplicateTitle,
+        default_severity: Severity::Error,
+    },
+    // instruction の targets に有効なターゲットが含まれず、どこにも出力されない
+    Rule {
+        id: ValidationRule::UnreachableInstruction,
+        default_severity: Severity::Warning,
+    },
+    // 非推奨のターゲット (codex) が使われている
+    Rule {
+        id: ValidationRule::DeprecatedTarget,
+        default_severity: Severity::Warning,
+    },
+    // instruction の targets に custom で定義されていないターゲット名がある
+    Rule {
+        id: ValidationRule::UnknownTarget,
+        default_severity: Severity::Error,
+    },
+    // 複数のターゲットの出力先が同じパスになる
+    Rule {
+        id: ValidationRule::DuplicateOutputPath,
+        default_severity: Severity::Error,
+    },
+    // multiple モードでタイトルの異なる instruction が同じファイル名になる
+    Rule {
+        id: ValidationRule::DuplicateFilename,
+        default_severity: Severity::Error,
+    },
+    // body_file が .instrux/instructions/ の外、出力先がリポジトリのルートの外を指している
+    Rule {
+        id: ValidationRule::PathOutsideSandbox,
+        default_severity: Severity::Error,
+    },
+    // 同じ id の instruction が複数ある (階層によらず設定全体で一意にする)
+    Rule {
+        id: ValidationRule::DuplicateId,
+        default_severity: Severity::Error,
+    },
+    // directory のglobに一致するディレクトリがなく、ルートの AGENTS.md に出力される
+    Rule {
+        id: ValidationRule::UnmatchedDirectory,
+        default_severity: Severity::Warning,
+    },
+];
+
+/// 検出した問題 (YAMLパスとメッセージ)
+struct Finding {
+    rule: ValidationRule,
+    path: String,
+    message: String,
+}
+
+/// 設定ファイルを意味検証ルールで検証する
+///
+/// # 引数
+/// * `config` - スキーマ検証済みの設定
+/// * `root` - 位置情報を取得するためのYAMLノードツリー
+/// * `instructions_dir` - body_file の格納ディレクトリ
+pub fn check(
+    config: &InstruxConfiguration,
+    root: &Node,
+    instructions_dir: &Path,
+) -> Vec<Diagnostic> {
+    let mut findings = Vec::new();
+
+    if config.targets.contains_key(&Targets::Codex) {
+        findings.push(Finding {
+            rule: ValidationRule::DeprecatedTarget,
+            path: "targets.codex".to_string(),
+            message: "codex は非推奨です。agentsmd (AGENTS.md) を使用してください".to_string(),
+        });
+    }
+    let sandbox = Sandbox::new(Path::new("."), instructions_dir, config);
+    check_output_paths(config, &sandbox, &mut findings);
+    check_filenames(config, &mut findings);
+    check_ids(
+        &config.instructions,
+        "instructions",
+        &mut HashSet::new(),
+        &mut findings,
+    );
+    let enabled: Vec<TargetName> = config
+        .targets
+        .keys()
+        .map(|target| TargetName::Targets(*target))
+        .chain(
+            config
+                .custom
+                .keys()
+                .cloned()
+                .map(TargetName::CustomTargetName),
+        )
+        .collect();
+    check_items(
+        &config.instructions,
+        "instructions",
+        &enabled,
+        config,
+        instructions_dir,
+        &sandbox,
+        &mut findings,
+    );
+
+    let mut diagnostics: Vec<Diagnostic> = findings
+        .into_iter()
+        .filter_map(|finding| {
+            let severity = severity_of(config, finding.rule)?;
+            let node = yaml::locate(root, &finding.path).unwrap_or(root);
+            Some(Diagnostic {
+                line: node.line,
+                column: node.column,
+                path: finding.path,
+                message: finding.message,
+                rule: finding.rule.to_string(),
+                severity,
+            })
+        })
+        .collect();
+    diagnostics.sort_by_key(|d| (d.line, d.column));
+    diagnostics
+}
+
+/// 出力先 (単一ファイルのパスまたは複数ファイルのディレクトリ) が同じになるターゲットを検出する
+///
+/// リポジトリのルートの外を指す出力先も検出する。
+fn check_output_paths(
+    config: &InstruxConfiguration,
+    sandbox: &Sandbox,
+    findings: &mut Vec<Finding>,
+) {
+    // pathを指定したターゲットの側で報告するため、既定の出力先のターゲットを先に並べる
+    let mut targets: Vec<&Targets> = config.targets.keys().collect();
+    targets.sort_by_key(|target| (config.targets[*target].path.is_some(), **target));
+    let mut outputs: Vec<(String, PathBuf)> = targets
+        .into_iter()
+        // codexは変換できないため対象外 (deprecated-targetで報告する)
+        .filter(|target| !formats::is_deprecated(target))
+        .map(|target| {
+            let value = &config.targets[target];
+            let path = if value.path.is_some() {
+                format!("targets.{}.path", target)
+            } else {
+                format!("targets.{}", target)
+            };
+            let output = formats::get_converter(target, value).get_default_path();
+            (path, output)
+        })
+        .collect();
+    let mut custom: Vec<_> = config.custom.iter().collect();
+    custom.sort_by_key(|(name, _)| *name);
+    for (name, target) in &custom {
+        // filenameのテンプレートで出力先のディレクトリの外に出ないか
+        let output = Path::new(&target.path).join(&target.filename);
+        if target.output_mode == CustomTargetOutputMode::Multiple && !sandbox.allows_output(&output)
+        {
+            findings.push(Finding {
+                rule: ValidationRule::PathOutsideSandbox,
+                path: format!("custom.{}.filename", name.as_str()),
+                message: format!(
+                    "出力先 {} がリポジトリのルートの外を指しています",
+                    output.display()
+                ),
+            });
+        }
+    }
+    outputs.extend(custom.into_iter().map(|(name, target)| {
+        (
+            format!("custom.{}.path", name.as_str()),
+            PathBuf::from(&target.path),
+        )
+    }));
+
+    let mut seen: Vec<(PathBuf, &str)> = Vec::new();
+    for (path, output) in &outputs {
+        if !sandbox.allows_output(output) {
+            findings.push(Finding {
+                rule: ValidationRule::PathOutsideSandbox,
+                path: path.clone(),
+                message: format!(
+                    "出力先 {} がリポジトリのルートの外を指しています",
+                    output.display()
+                ),
+            });
+        }
+        // `./` や末尾の `/` の有無によらず比較する
+        let output: PathBuf = output
+            .components()
+            .filter(|c| !matches!(c, Component::CurDir))
+            .collect();
+        let name = path.split('.').nth(1).unwrap_or_default();
+        if let Some((_, first)) = seen.iter().find(|(seen, _)| *seen == output) {
+            findings.push(Finding {
+                rule: ValidationRule::DuplicateOutputPath,
+                path: path.clone(),
+                message: format!(
+                    "{} の出力先 {} が {} と重複しています",
+                    name,
+                    output.display(),
+                    first
+                ),
+            });
+        } else {
+            seen.push((output, name));
+        }
+    }
+}
+
+/// multipleモードでタイトルの異なる最上位のinstructionが同じファイル名になるものを検出する
+///
+/// 大文字・小文字のみ異なるファイル名も、区別しないファイルシステムで衝突するため重複とみなす。
+/// タイトル自体の重複は duplicate-title で報告する。
+fn check_filenames(config: &InstruxConfiguration, findings: &mut Vec<Finding>) {
+    let mut targets: Vec<&Targets> = config.targets.keys().collect();
+    targets.sort();
+    // ターゲットごとの各instructionのファイル名 (multipleモードでないターゲットはNone)
+    let mut names: Vec<(TargetName, Vec<Option<String>>)> = targets
+        .into_iter()
+        .map(|target| {
+            let value = &config.targets[target];
+            let names = config
+                .instructions
+                .iter()
+                .map(|item| formats::rule_file_stem(target, value, item))
+                .collect();
+            (TargetName::Targets(*target), names)
+        })
+        .collect();
+    let mut custom: Vec<_> = config.custom.iter().collect();
+    custom.sort_by_key(|(name, _)| *name);
+    names.extend(custom.into_iter().map(|(name, target)| {
+        let names = config
+            .instructions
+            .iter()
+            .map(|item| formats::custom_file_name(target, item))
+            .collect();
+        (TargetName::CustomTargetName(name.clone()), names)
+    }));
+
+    // 複数のターゲットで衝突しても instruction ごとに1回だけ報告する
+    let mut reported = HashSet::new();
+    for (target, names) in &names {
+        let mut seen: Vec<(String, &str)> = Vec::new();
+        for (i, item) in config.instructions.iter().enumerate() {
+            let (title, filename, disable) = match item {
+                InstructionItem::Variant0 {
+                    title,
+                    filename,
+                    disable,
+                    ..
+                }
+                | InstructionItem::Variant1 {
+                    title,
+                    filename,
+                    disable,
+                    ..
+                }
+                | InstructionItem::Variant2 {
+                    title,
+                    filename,
+                    disable,
+                    ..
+                } => (title, filename, *disable),
+            };
+            if disable || target_list(item).is_some_and(|list| !list.contains(target)) {
+                continue;
+            }
+            let Some(name) = &names[i] else {
+                continue;
+            };
+            let key = name.to_lowercase();
+            match seen.iter().find(|(seen, _)| *seen == key) {
+                Some((_, first)) if *first != title => {
+                    if reported.insert(i) {
+                        let field = if filename.is_some() {
+                            "filename"
+                        } else {
+                            "title"
+                        };
+                        findings.push(Finding {
+                            rule: ValidationRule::DuplicateFilename,
+                            path: format!("instructions[{}].{}", i, field),
+                            message: format!(
+                                "{} のファイル名 {} が {} と重複しています ({})",
+                                title, name, first, target
+                            ),
+                        });
+                    }
+                }
+                Some(_) => {}
+                None => seen.push((key, title)),
+            }
+        }
+    }
+}
+
+/// 設定全体でidが重複するinstructionを再帰的に検出する
+fn check_ids<'a>(
+    items: &'a [InstructionItem],
+    path: &str,
+    seen: &mut HashSet<&'a str>,
+    findings: &mut Vec<Finding>,
+) {
+    for (i, item) in items.iter().enumerate() {
+        let item_path = format!("{}[{}]", path, i);
+        let id = match item {
+            InstructionItem::Variant0 { id, .. }
+            | InstructionItem::Variant1 { id, .. }
+            | InstructionItem::Variant2 { id, .. } => id,
+        };
+        if let Some(id) = id
+            && !seen.insert(id.as_str())
+        {
+            findings.push(Finding {
+                rule: ValidationRule::DuplicateId,
+                path: format!("{}.id", item_path),
+                message: format!("id {} が重複しています", id.as_str()),
+            });
+        }
+        if let InstructionItem::Variant2 { instructions, .. } = item {
+            check_ids(
+                instructions,
+                &format!("{}.instructions", item_path),
+                seen,
+                findings,
+            );
+        }
+    }
+}
+
+/// 設定で上書きされた重大度を返す (offの場合はNone)
+fn severity_of(config: &InstruxConfiguration, rule: ValidationRule) -> Option<Severity> {
+    let configured = config
+        .validation
+        .as_ref()
+        .and_then(|validation| validation.rules.get(&rule));
+    match configured {
+        Some(RuleSeverity::Error) => Some(Severity::Error),
+        Some(RuleSeverity::Warning) => Some(Severity::Warning),
+        Some(RuleSeverity::Off) => None,
+        None => RULES
+            .iter()
+            .find(|r| r.id == rule)
+            .map(|r| r.default_severity),
+    }
+}
+
+/// 同じ階層のInstructionItemを再帰的に検証する
+///
+/// `reachable` は親までのtargets指定で絞り込んだ出力先ターゲット
+fn check_items(
+    items: &[InstructionItem],
+    path: &str,
+    reachable: &[TargetName],
+    config: &InstruxConfiguration,
+    instructions_dir: &Path,
+    sandbox: &Sandbox,
+    findings: &mut Vec<Finding>,
+) {
+    let mut titles = HashSet::new();
+    for (i, item) in items.iter().enumerate() {
+        let item_path = format!("{}[{}]", path, i);
+        let (title, directory) = match item {
+            InstructionItem::Variant0 {
+                title, directory, ..
+            }
+            | InstructionItem::Variant1 {
+                title, directory, ..
+            }
+            | InstructionItem::Variant2 {
+                title, directory, ..
+            } => (title, directory),
+        };
+        let list = target_list(item);
+
+        if !titles.insert(title.as_str()) {
+            findings.push(Finding {
+                rule: ValidationRule::DuplicateTitle,
+                path: format!("{}.title", item_path),
+                message: format!("タイトル {} が同じ階層で重複しています", title),
+            });
+        }
+
+        if let Some(list) = list
+            && let Some(index) = list
+                .iter()
+                .position(|t| *t == TargetName::Targets(Targets::Codex))
+        {
+            findings.push(Finding {
+                rule: ValidationRule::DeprecatedTarget,
+                path: format!("{}.targets[{}]", item_path, index),
+                message: "codex は非推奨です。agentsmd (AGENTS.md) を使用してください".to_string(),
+            });
+        }
+
+        for (index, target) in list.unwrap_or_default().iter().enumerate() {
+            if let TargetName::CustomTargetName(name) = target
+                && !config.custom.contains_key(name)
+            {
+                findings.push(Finding {
+                    rule: ValidationRule::UnknownTarget,
+                    path: format!("{}.targets[{}]", item_path, index),
+                    message: format!(
+                        "{} は custom に定義されていないターゲットです",
+                        name.as_str()
+                    ),
+                });
+            }
+        }
+
+        if let Some(directory) = directory
+            && !sandbox.allows_output(Path::new(directory))
+        {
+            findings.push(Finding {
+                rule: ValidationRule::PathOutsideSandbox,
+                path: format!("{}.directory", item_path),
+                message: format!("{} がリポジトリのルートの外を指しています", directory),
+            });
+        }
+        if let Some(directory) = directory
+            && formats::expand_directory(directory).is_empty()
+        {
+            findings.push(Finding {
+                rule: ValidationRule::UnmatchedDirectory,
+                path: format!("{}.directory", item_path),
+                message: format!(
+                    "{} に一致するディレクトリがないため、ルートの AGENTS.md に出力されます",
+                    directory
+                ),
+            });
+        }
+
+        let item_reachable: Vec<TargetName> = reachable
+            .iter()
+            .filter(|t| list.is_none_or(|list| list.contains(t)))
+            .cloned()
+            .collect();
+        // 親が出力されない場合は子も出力されないため、最上位のみ報告する
+        if !reachable.is_empty() && item_reachable.is_empty() {
+            let target_path = format!("{}.targets", item_path);
+            findings.push(Finding {
+                rule: ValidationRule::UnreachableInstruction,
+                path: if list.is_some() {
+                    target_path
+                } else {
+                    item_path.clone()
+                },
+                message: format!(
+                    "{} は有効なターゲットに含まれないため、どこにも出力されません",
+                    title
+                ),
+            });
+        }
+
+        match item {
+            InstructionItem::Variant1 { body_file, .. } => {
+                if !sandbox.allows_body_file(body_file) {
+                    findings.push(Finding {
+                        rule: ValidationRule::PathOutsideSandbox,
+                        path: format!("{}.body_file", item_path),
+                        message: format!(
+                            "{} が {} の外を指しています",
+                            body_file,
+                            instructions_dir.display()
+                        ),
+                    });
+                } else if !instructions_dir.join(body_file).is_file() {
+                    findings.push(Finding {
+                        rule: ValidationRule::MissingBodyFile,
+                        path: format!("{}.body_file", item_path),
+                        message: format!(
+                            "{} が {} に見つかりません",
+                            body_file,
+                            instructions_dir.display()
+                        ),
+                    });
+                }
+            }
+            InstructionItem::Variant2 { instructions, .. } => check_items(
+                instructions,
+                &format!("{}.instructions", item_path),
+                &item_reachable,
+                config,
+                instructions_dir,
+                sandbox,
+                findings,
+            ),
+            InstructionItem::Variant0 { .. } => {}
+        }
+    }
+}
+
+/// targets指定の明示的なターゲット一覧 ("all" の場合はNone)
+fn target_list(item: &InstructionItem) -> Option<&[TargetName]> {
+    match item {
+        InstructionItem::Variant0 {
+            targets: InstructionItemVariant0Targets::Variant0(list),
+            ..
+        }
+        | InstructionItem::Variant1 {
+            targets: InstructionItemVariant1Targets::Variant0(list),
+            ..
+        }
+        | InstructionItem::Variant2 {
+            targets: InstructionItemVariant2Targets::Variant0(list),
+            ..
+        } => Some(list),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::yaml;
+    use super::check;
+    use crate::model::parser;
+    use std::path::Path;
+
+    fn check_str(content: &str) -> Vec<(usize, usize, String, String)> {
+        let config = parser::parse_instrux_yaml_str(content, Path::new("instrux.yaml"))
+            .expect("valid config");
+        let root = yaml::load(content).expect("valid YAML");
+        check(&config, &root, Path::new("tests/fixtures"))
+            .into_iter()
+            .map(|d| (d.line, d.column, d.rule, d.severity.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_check_reports_rules_with_position() {
+        let content = r#"version: 1.0.0
+targets:
+  copilot: {}
+  codex: {}
+instructions:
+  - title: A
+    body: a
+    targets: all
+  - title: A
+    body_file: missing.md
+    targets: [cline]
+  - title: Group
+    targets: [junie]
+    instructions:
+      - title: Child
+        body: c
+        targets: all
+"#;
+        assert_eq!(
+            check_str(content),
+            vec![
+                (
+                    4,
+                    10,
+                    "deprecated-target".to_string(),
+                    "warning".to_string()
+                ),
+                (9, 12, "duplicate-title".to_string(), "error".to_string()),
+                (10, 16, "missing-body-file".to_string(), "error".to_string()),
+                (
+                    11,
+                    14,
+                    "unreachable-instruction".to_string(),
+                    "warning".to_string()
+                ),
+                (
+                    13,
+                    14,
+                    "unreachable-instruction".to_string(),
+                    "warning".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_applies_configured_severity() {
+        let content = r#"version: 1.0.0
+targets:
+  copilot: {}
+validation:
+  rules:
+    duplicate-title: off
+    missing-body-file: warning
+instructions:
+  - title: A
+    body: a
+    targets: all
+  - title: A
+    body_file: missing.md
+    targets: all
+"#;
+        assert_eq!(
+            check_str(content),
+            vec![(
+                13,
+                16,
+                "missing-body-file".to_string(),
+                "warning".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_check_reports_unknown_custom_target() {
+        let content = r#"version: 1.0.0
+targets:
+  copilot: {}
+custom:
+  mytool:
+    path: .mytool/rules.md
+instructions:
+  - title: A
+    body: a
+    targets: [mytool, othertool]
+"#;
+        assert_eq!(
+            check_str(content),
+            vec![(10, 23, "unknown-target".to_string(), "error".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_check_reports_duplicate_output_path() {
+        let content = r#"version: 1.0.0
+targets:
+  agentsmd: {}
+  junie:
+    path: ./AGENTS.md
+  cline:
+    path: docs/rules
+custom:
+  mytool:
+    path: docs/rules/
+instructions:
+  - title: A
+    body: a
+"#;
+        assert_eq!(
+            check_str(content),
+            vec![
+                (
+                    5,
+                    11,
+                    "duplicate-output-path".to_string(),
+                    "error".to_string()
+                ),
+                (
+                    10,
+                    11,
+                    "duplicate-output-path".to_string(),
+                    "error".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_reports_duplicate_filename() {
+        let content = r#"version: 1.0.0
+targets:
+  cline:
+    outputMode: multiple
+    filenames: slug
+  copilot:
+    outputMode: multiple
+instructions:
+  - title: Foo Bar
+    body: a
+  - title: foo/bar
+    body: b
+  - title: 設計
+    body: c
+  - title: Other
+    filename: Foo-Bar
+    body: d
+    targets: [copilot]
+"#;
+        assert_eq!(
+            check_str(content),
+            vec![
+                (
+                    11,
+                    12,
+                    "duplicate-filename".to_string(),
+                    "error".to_string()
+                ),
+                (
+                    16,
+                    15,
+                    "duplicate-filename".to_string(),
+                    "error".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_reports_path_outside_sandbox() {
+        let content = r#"version: 1.0.0
+targets:
+  agentsmd: {}
+  junie:
+    path: ../junie.md
+instructions:
+  - title: A
+    body_file: ../../../etc/passwd
+  - title: B
+    body: b
+    directory: ../other
+"#;
+        assert_eq!(
+            check_str(content),
+            vec![
+                (
+                    5,
+                    11,
+                    "path-outside-sandbox".to_string(),
+                    "error".to_string()
+                ),
+                (
+                    8,
+                    16,
+                    "path-outside-sandbox".to_string(),
+                    "error".to_string()
+                ),
+                (
+                    11,
+                    16,
+                    "path-outside-sandbox".to_string(),
+                    "error".to_string()
+                ),
+            ]
+        );
+
+        // sandbox.allow で明示したパスは許可する
+        let content = format!("{}sandbox:\n  allow: [../junie.md, ../other]\n", content);
+        assert_eq!(
+            check_str(&content),
+            vec![(
+                8,
+                16,
+                "path-outside-sandbox".to_string(),
+                "error".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_check_reports_duplicate_id() {
+        let content = r#"version: 1.0.0
+targets:
+  copilot: {}
+instructions:
+  - title: A
+    id: style
+    body: a
+  - title: Group
+    id: group
+    instructions:
+      - title: B
+        id: style
+        body: b
+"#;
+        assert_eq!(
+            check_str(content),
+            vec![(12, 13, "duplicate-id".to_string(), "error".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_check_reports_unmatched_directory() {
+        let content = r#"version: 1.0.0
+targets:
+  agentsmd: {}
+instructions:
+  - title: Group
+    instructions:
+      - title: A
+        directory: 'no-such-dir-*/pkg'
+        body: a
+"#;
+        assert_eq!(
+            check_str(content),
+            vec![(
+                8,
+                20,
+                "unmatched-directory".to_string(),
+                "warning".to_string()
+            )]
+        );
+    }
+}
@@ -0,0 +1,391 @@
+use super::yaml::{Node, Value};
+use super::{Diagnostic, Severity};
+use serde_json::Value as Json;
+use std::sync::LazyLock;
+
+/// instrux.yamlのJSON Schema (schema/instrux.schema.json)
+static SCHEMA: LazyLock<Json> = LazyLock::new(|| {
+    serde_json::from_str(include_str!("../../schema/instrux.schema.json"))
+        .expect("schema/instrux.schema.json is valid JSON")
+});
+
+/// ノードツリーをinstrux.yamlのスキーマで検証する
+pub fn validate(root: &Node) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    validate_node(&SCHEMA, root, "", &mut diagnostics);
+    diagnostics
+}
+
+/// `#/$defs/...` 形式の参照を解決する
+fn resolve(schema: &Json) -> &Json {
+    match schema.get("$ref").and_then(Json::as_str) {
+        Some(reference) => {
+            let target = reference
+                .trim_start_matches("#/")
+                .split('/')
+                .filter(|segment| !segment.is_empty())
+                .try_fold(&*SCHEMA, |current, segment| current.get(segment));
+            resolve(target.expect("schema $ref points to an existing definition"))
+        }
+        None => schema,
+    }
+}
+
+fn child_path(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+fn diagnostic(node: &Node, path: &str, message: String) -> Diagnostic {
+    Diagnostic {
+        line: node.line,
+        column: node.column,
+        path: path.to_string(),
+        message,
+        rule: "schema".to_string(),
+        severity: Severity::Error,
+    }
+}
+
+/// スキーマの型指定とノードの型が一致するか
+fn type_matches(expected: &str, value: &Value) -> bool {
+    match (expected, value) {
+        ("integer", Value::Number(n)) => n.parse::<i64>().is_ok(),
+        (expected, value) => expected == value.type_name(),
+    }
+}
+
+/// enum/constの値とノードの値が一致するか
+fn json_equals(json: &Json, value: &Value) -> bool {
+    match (json, value) {
+        (Json::String(s), Value::String(v)) => s == v,
+        (Json::Bool(b), Value::Bool(v)) => b == v,
+        (Json::Number(n), Value::Number(v)) => n.to_string() == *v,
+        (Json::Null, Value::Null) => true,
+        _ => false,
+    }
+}
+
+fn display_value(value: &Value) -> String {
+    match value {
+        Value::String(s) | Value::Number(s) => format!("'{}'", s),
+        Value::Bool(b) => b.to_string(),
+        other => other.type_name().to_string(),
+    }
+}
+
+fn validate_node(schema: &Json, node: &Node, path: &str, out: &mut Vec<Diagnostic>) {
+    let schema = resolve(schema);
+
+    if let Some(expected) = schema.get("type").and_then(Json::as_str)
+        && !type_matches(expected, &node.value)
+    {
+        out.push(diagnostic(
+            node,
+            path,
+            format!(
+                "{} 型である必要があります (実際: {})",
+                expected,
+                node.value.type_name()
+            ),
+        ));
+        return;
+    }
+
+    if let Some(values) = schema.get("enum").and_then(Json::as_array)
+        && !values.iter().any(|v| json_equals(v, &node.value))
+    {
+        let candidates: Vec<String> = values
+            .iter()
+            .map(|v| v.as_str().map(str::to_string).unwrap_or(v.to_string()))
+            .collect();
+        out.push(diagnostic(
+            node,
+            path,
+            format!(
+                "{} は次のいずれかである必要があります: {}",
+                display_value(&node.value),
+                candidates.join(", ")
+            ),
+        ));
+    }
+
+    if let Some(expected) = schema.get("const")
+        && !json_equals(expected, &node.value)
+    {
+        out.push(diagnostic(
+            node,
+            path,
+            format!(
+                "{} は {} である必要があります",
+                display_value(&node.value),
+                expected
+            ),
+        ));
+    }
+
+    if let (Some(pattern), Value::String(s)) =
+        (schema.get("pattern").and_then(Json::as_str), &node.value)
+    {
+        let matched = regress::Regex::new(pattern)
+            .map(|re| re.find(s).is_some())
+            .unwrap_or(true);
+        if !matched {
+            out.push(diagnostic(
+                node,
+                path,
+                format!("'{}' はパターン {} に一致しません", s, pattern),
+            ));
+        }
+    }
+
+    match &node.value {
+        Value::Object(entries) => validate_object(schema, node, entries, path, out),
+        Value::Array(items) => {
+            if let Some(min) = schema.get("minItems").and_then(Json::as_u64)
+                && (items.len() as u64) < min
+            {
+                out.push(diagnostic(
+                    node,
+                    path,
+                    format!("要素が{}個以上必要です", min),
+                ));
+            }
+            if let Some(item_schema) = schema.get("items") {
+                for (i, item) in items.iter().enumerate() {
+                    validate_node(item_schema, item, &format!("{}[{}]", path, i), out);
+                }
+            }
+        }
+        _ => {}
+    }
+
+    if let Some(branches) = schema.get("anyOf").and_then(Json::as_array) {
+        validate_branches(branches, false, node, path, out);
+    }
+    if let Some(branches) = schema.get("oneOf").and_then(Json::as_array) {
+        validate_branches(branches, true, node, path, out);
+    }
+}
+
+fn validate_object(
+    schema: &Json,
+    node: &Node,
+    entries: &[(Node, Node)],
+    path: &str,
+    out: &mut Vec<Diagnostic>,
+) {
+    let properties = schema.get("properties").and_then(Json::as_object);
+
+    if let Some(required) = schema.get("required").and_then(Json::as_array) {
+        for name in required.iter().filter_map(Json::as_str) {
+            let present = entries
+                .iter()
+                .any(|(key, _)| matches!(&key.value, Value::String(k) if k == name));
+            if !present {
+                out.push(diagnostic(
+                    node,
+                    path,
+                    format!("必須プロパティ {} がありません", name),
+                ));
+            }
+        }
+    }
+
+    if let Some(min) = schema.get("minProperties").and_then(Json::as_u64)
+        && (entries.len() as u64) < min
+    {
+        out.push(diagnostic(
+            node,
+            path,
+            format!("プロパティが{}個以上必要です", min),
+        ));
+    }
+
+    for (key, value) in entries {
+        let name = match &key.value {
+            Value::String(s) | Value::Number(s) => s.clone(),
+            other => other.type_name().to_string(),
+        };
+        let value_path = child_path(path, &name);
+
+        if let Some(names_schema) = schema.get("propertyNames") {
+            let key_node = Node {
+                value: Value::String(name.clone()),
+                line: key.line,
+                column: key.column,
+            };
+            validate_node(names_schema, &key_node, &value_path, out);
+        }
+
+        match properties.and_then(|p| p.get(&name)) {
+            Some(property_schema) => validate_node(property_schema, value, &value_path, out),
+            None => match schema.get("additionalProperties") {
+                Some(Json::Bool(false)) => {
+                    out.push(diagnostic(
+                        key,
+                        &value_path,
+                        format!("未定義のプロパティ {} です", name),
+                    ));
+                }
+                Some(additional @ Json::Object(_)) => {
+                    validate_node(additional, value, &value_path, out)
+                }
+                _ => {}
+            },
+        }
+    }
+}
+
+/// anyOf / oneOf の検証
+///
+/// どの候補にも一致しない場合は、型が一致しエラーが最も少ない候補のエラーを報告する。
+fn validate_branches(
+    branches: &[Json],
+    one_of: bool,
+    node: &Node,
+    path: &str,
+    out: &mut Vec<Diagnostic>,
+) {
+    let results: Vec<Vec<Diagnostic>> = branches
+        .iter()
+        .map(|branch| {
+            let mut diagnostics = Vec::new();
+            validate_node(branch, node, path, &mut diagnostics);
+            diagnostics
+        })
+        .collect();
+    let matched = results.iter().filter(|r| r.is_empty()).count();
+    if matched == 1 || (matched > 1 && !one_of) {
+        return;
+    }
+    if matched > 1 {
+        out.push(diagnostic(
+            node,
+            path,
+            "複数の候補に一致するため判別できません".to_string(),
+        ));
+        return;
+    }
+
+    // { "required": [...] } のみの候補はまとめて報告する
+    let required_only: Option<Vec<String>> = branches
+        .iter()
+        .map(|branch| {
+            let branch = resolve(branch).as_object()?;
+            if branch.len() != 1 {
+                return None;
+            }
+            let names = branch.get("required")?.as_array()?;
+            Some(
+                names
+                    .iter()
+                    .filter_map(Json::as_str)
+                    .collect::<Vec<_>>()
+                    .join(" + "),
+            )
+        })
+        .collect();
+    if let Some(names) = required_only {
+        out.push(diagnostic(
+            node,
+            path,
+            format!("次のいずれかのプロパティが必要です: {}", names.join(", ")),
+        ));
+        return;
+    }
+
+    let best = branches
+        .iter()
+        .zip(results)
+        .min_by_key(|(branch, diagnostics)| {
+            let type_mismatch = resolve(branch)
+                .get("type")
+                .and_then(Json::as_str)
+                .is_some_and(|t| !type_matches(t, &node.value));
+            (type_mismatch, diagnostics.len())
+        })
+        .map(|(_, diagnostics)| diagnostics);
+    out.extend(best.unwrap_or_default());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::yaml;
+    use super::validate;
+
+    fn validate_str(content: &str) -> Vec<(usize, usize, String, String)> {
+        let root = yaml::load(content).expect("valid YAML");
+        validate(&root)
+            .into_iter()
+            .map(|d| (d.line, d.column, d.path, d.message))
+            .collect()
+    }
+
+    #[test]
+    fn test_validate_accepts_valid_config() {
+        let content = r#"
+version: 0.1.0
+targets:
+  copilot:
+    outputMode: multiple
+instructions:
+  - title: Style
+    body: Use rustfmt.
+    targets: [copilot]
+"#;
+        assert!(validate_str(content).is_empty());
+    }
+
+    #[test]
+    fn test_validate_reports_unknown_property_with_position() {
+        let content = r#"version: 0.1.0
+targets:
+  copilot: {}
+instructions:
+  - title: Group
+    instructions:
+      - title: Child
+        body_fil: child.md
+"#;
+        let diagnostics = validate_str(content);
+        assert!(diagnostics.contains(&(
+            8,
+            9,
+            "instructions[0].instructions[0].body_fil".to_string(),
+            "未定義のプロパティ body_fil です".to_string()
+        )));
+        assert!(diagnostics.iter().any(|(line, _, path, message)| *line == 7
+            && path == "instructions[0].instructions[0]"
+            && message.contains("body, body_file, instructions")));
+    }
+
+    #[test]
+    fn test_validate_reports_enum_and_pattern() {
+        let content = r#"version: "1.0"
+targets:
+  copilto: {}
+  cline:
+    outputMode: many
+instructions:
+  - title: A
+    body: B
+    targets: [junie, Cursr]
+"#;
+        let diagnostics = validate_str(content);
+        let paths: Vec<&str> = diagnostics.iter().map(|d| d.2.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "version",
+                "targets.copilto",
+                "targets.cline.outputMode",
+                "instructions[0].targets[1]"
+            ]
+        );
+        assert_eq!((diagnostics[1].0, diagnostics[1].1), (3, 3));
+    }
+}
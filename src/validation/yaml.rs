@@ -0,0 +1,179 @@
+use super::{Diagnostic, Severity};
+use yaml_rust2::parser::{Event, MarkedEventReceiver, Parser};
+use yaml_rust2::scanner::{Marker, TScalarStyle};
+
+/// 位置情報付きのYAMLノード
+#[derive(Debug, Clone)]
+pub struct Node {
+    pub value: Value,
+    /// 行番号 (1始まり)
+    pub line: usize,
+    /// 列番号 (1始まり)
+    pub column: usize,
+}
+
+/// YAMLノードの値 (JSON Schemaの型に対応)
+#[derive(Debug, Clone)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Number(String),
+    String(String),
+    Array(Vec<Node>),
+    Object(Vec<(Node, Node)>),
+}
+
+impl Value {
+    /// JSON Schemaでの型名
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Null => "null",
+            Value::Bool(_) => "boolean",
+            Value::Number(_) => "number",
+            Value::String(_) => "string",
+            Value::Array(_) => "array",
+            Value::Object(_) => "object",
+        }
+    }
+}
+
+/// 構築中のコレクション
+enum Frame {
+    Array(Marker, Vec<Node>),
+    Object(Marker, Vec<(Node, Node)>, Option<Node>),
+}
+
+/// パーサのイベントから位置情報付きのノードツリーを構築する
+#[derive(Default)]
+struct Loader {
+    stack: Vec<Frame>,
+    root: Option<Node>,
+}
+
+impl Loader {
+    fn push(&mut self, node: Node) {
+        match self.stack.last_mut() {
+            None => {
+                if self.root.is_none() {
+                    self.root = Some(node);
+                }
+            }
+            Some(Frame::Array(_, items)) => items.push(node),
+            Some(Frame::Object(_, entries, key)) => match key.take() {
+                Some(k) => entries.push((k, node)),
+                None => *key = Some(node),
+            },
+        }
+    }
+}
+
+impl MarkedEventReceiver for Loader {
+    fn on_event(&mut self, ev: Event, mark: Marker) {
+        match ev {
+            Event::Scalar(value, style, _, _) => {
+                let value = resolve_scalar(value, style);
+                self.push(node(value, mark));
+            }
+            Event::Alias(_) => self.push(node(Value::Null, mark)),
+            Event::SequenceStart(_, _) => self.stack.push(Frame::Array(mark, Vec::new())),
+            Event::MappingStart(_, _) => self.stack.push(Frame::Object(mark, Vec::new(), None)),
+            Event::SequenceEnd | Event::MappingEnd => {
+                let value = match self.stack.pop() {
+                    Some(Frame::Array(start, items)) => node(Value::Array(items), start),
+                    // ブロックマッピングの開始位置は最初のキーの位置とする
+                    Some(Frame::Object(start, entries, _)) => match entries.first() {
+                        Some((key, _)) => Node {
+                            line: key.line,
+                            column: key.column,
+                            value: Value::Object(entries),
+                        },
+                        None => node(Value::Object(entries), start),
+                    },
+                    None => return,
+                };
+                self.push(value);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn node(value: Value, mark: Marker) -> Node {
+    Node {
+        value,
+        line: mark.line(),
+        column: mark.col() + 1,
+    }
+}
+
+/// プレーンスカラーをYAML 1.2 Core Schemaに従って型付けする
+fn resolve_scalar(value: String, style: TScalarStyle) -> Value {
+    if style != TScalarStyle::Plain {
+        return Value::String(value);
+    }
+    match value.as_str() {
+        "" | "~" | "null" | "Null" | "NULL" => Value::Null,
+        "true" | "True" | "TRUE" => Value::Bool(true),
+        "false" | "False" | "FALSE" => Value::Bool(false),
+        s if s
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_digit() || c == '-' || c == '+' || c == '.')
+            && (s.parse::<i64>().is_ok() || s.parse::<f64>().is_ok()) =>
+        {
+            Value::Number(value)
+        }
+        _ => Value::String(value),
+    }
+}
+
+/// YAML文字列を位置情報付きのノードツリーとして読み込む (最初のドキュメントのみ)
+pub fn load(content: &str) -> Result<Node, Diagnostic> {
+    let mut loader = Loader::default();
+    Parser::new_from_str(content)
+        .load(&mut loader, false)
+        .map_err(|e| Diagnostic {
+            line: e.marker().line(),
+            column: e.marker().col() + 1,
+            path: String::new(),
+            message: format!("YAMLの構文エラー: {}", e.info()),
+            rule: "syntax".to_string(),
+            severity: Severity::Error,
+        })?;
+    Ok(loader.root.unwrap_or(Node {
+        value: Value::Null,
+        line: 1,
+        column: 1,
+    }))
+}
+
+/// `instructions[2].title` 形式のパスでノードを検索する
+pub fn locate<'a>(root: &'a Node, path: &str) -> Option<&'a Node> {
+    let mut current = root;
+    for segment in path.split('.').filter(|s| !s.is_empty()) {
+        let (name, indexes) = match segment.find('[') {
+            Some(i) => (&segment[..i], &segment[i..]),
+            None => (segment, ""),
+        };
+        if !name.is_empty() {
+            current = match &current.value {
+                Value::Object(entries) => entries
+                    .iter()
+                    .find(|(key, _)| matches!(&key.value, Value::String(k) if k == name))
+                    .map(|(_, value)| value)?,
+                _ => return None,
+            };
+        }
+        for index in indexes
+            .split(['[', ']'])
+            .filter(|s| !s.is_empty())
+            .map(|s| s.parse::<usize>())
+        {
+            current = match &current.value {
+                Value::Array(items) => items.get(index.ok()?)?,
+                _ => return None,
+            };
+        }
+    }
+    Some(current)
+}
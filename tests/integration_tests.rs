@@ -228,3 +228,1643 @@ targets:
     assert!(cline2.exists(), ".clinerules/Test2.md should exist");
     Ok(())
 }
+
+/// instrux.yamlを書き込んだテンポラリディレクトリを作成する
+fn setup_config(config: &str) -> Result<tempfile::TempDir, Box<dyn std::error::Error>> {
+    let temp_dir = tempdir()?;
+    let instrux_dir = temp_dir.path().join(".instrux");
+    fs::create_dir_all(&instrux_dir)?;
+    fs::write(instrux_dir.join("instrux.yaml"), config)?;
+    Ok(temp_dir)
+}
+
+/// gitリポジトリを作成し、ディレクトリの内容をすべてコミットする
+fn commit_all(dir: &std::path::Path) {
+    for args in [
+        vec!["init", "-q"],
+        vec!["add", "."],
+        vec![
+            "-c",
+            "user.name=test",
+            "-c",
+            "user.email=test@example.com",
+            "commit",
+            "-q",
+            "-m",
+            "init",
+        ],
+    ] {
+        Command::new("git")
+            .args(&args)
+            .current_dir(dir)
+            .assert()
+            .success();
+    }
+}
+
+const MERGE_CONFIG: &str = r#"# yaml-language-server: $schema=../schema/instrux.schema.json
+version: '1.0.0'
+instructions:
+  - title: 'Style'
+    body: 'Use rustfmt.'
+  - title: 'Testing'
+    body: 'Write tests first.'
+targets:
+  copilot: {}
+"#;
+
+#[test]
+fn test_merge_applies_tool_file_changes() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = setup_config(MERGE_CONFIG)?;
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("generate")
+        .assert()
+        .success();
+
+    // ツールファイル側で本文を編集し、新しいセクションを追加
+    let copilot_path = temp_dir.path().join(".github/copilot-instructions.md");
+    let edited = fs::read_to_string(&copilot_path)?.replace(
+        "Write tests first.",
+        "Write tests first and keep them fast.",
+    ) + "## Reviews\n\nRequest a review before merging.\n";
+    fs::write(&copilot_path, edited)?;
+
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("merge")
+        .arg("--from")
+        .arg("copilot")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Testing の変更を取り込みました"))
+        .stdout(predicate::str::contains(
+            "新しいセクション Reviews を追加しました",
+        ));
+
+    let config = fs::read_to_string(temp_dir.path().join(".instrux/instrux.yaml"))?;
+    assert!(config.starts_with("# yaml-language-server"));
+    assert!(config.contains("Write tests first and keep them fast."));
+    assert!(config.contains("Request a review before merging."));
+    // 変更していない箇所はそのまま残す (キーの並べ替えや既定値の追加をしない)
+    assert!(config.contains("  - title: 'Style'\n    body: 'Use rustfmt.'\n"));
+    assert!(config.ends_with("targets:\n  copilot: {}\n"));
+    assert!(!config.contains("disable:"));
+    Ok(())
+}
+
+#[test]
+fn test_merge_tracks_renamed_heading_by_id() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = setup_config(
+        r#"
+version: '1.0.0'
+instructions:
+  - title: 'Style'
+    id: style
+    body: 'Use rustfmt.'
+targets:
+  copilot: {}
+"#,
+    )?;
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("generate")
+        .assert()
+        .success();
+
+    // ツールファイル側で見出しと本文を変更 (IDの目印は残る)
+    let copilot_path = temp_dir.path().join(".github/copilot-instructions.md");
+    let edited = fs::read_to_string(&copilot_path)?
+        .replace("## Style", "## Formatting")
+        .replace("Use rustfmt.", "Use rustfmt and clippy.");
+    fs::write(&copilot_path, edited)?;
+
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("merge")
+        .arg("--from")
+        .arg("copilot")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Style の見出しを Formatting に変更しました",
+        ))
+        .stdout(predicate::str::contains("新しいセクション").not());
+
+    let config = fs::read_to_string(temp_dir.path().join(".instrux/instrux.yaml"))?;
+    assert!(
+        config
+            .contains("  - title: Formatting\n    id: style\n    body: Use rustfmt and clippy.\n")
+    );
+    assert_eq!(config.matches("title:").count(), 1);
+    Ok(())
+}
+
+#[test]
+fn test_merge_updates_body_file_in_multiple_mode() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = setup_config(
+        r#"
+version: '1.0.0'
+instructions:
+  - title: 'Comments'
+    body_file: 'comments.md'
+targets:
+  cline:
+    outputMode: multiple
+"#,
+    )?;
+    let instructions_dir = temp_dir.path().join(".instrux/instructions");
+    fs::create_dir_all(&instructions_dir)?;
+    fs::write(instructions_dir.join("comments.md"), "Explain why.\n")?;
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("generate")
+        .assert()
+        .success();
+
+    let cline_path = temp_dir.path().join(".clinerules/Comments.md");
+    let edited = fs::read_to_string(&cline_path)?.replace("Explain why.", "Explain why, not what.");
+    fs::write(&cline_path, edited)?;
+
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("merge")
+        .arg("--from")
+        .arg("cline")
+        .assert()
+        .success();
+
+    let body = fs::read_to_string(instructions_dir.join("comments.md"))?;
+    assert_eq!(body, "Explain why, not what.\n");
+    Ok(())
+}
+
+#[test]
+fn test_merge_reads_cursor_rules_and_refreshes_manifest() -> Result<(), Box<dyn std::error::Error>>
+{
+    let temp_dir = setup_config(
+        r#"
+version: '1.0.0'
+instructions:
+  - title: 'Style'
+    body: 'Use rustfmt.'
+targets:
+  cursor:
+    outputMode: multiple
+"#,
+    )?;
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("generate")
+        .assert()
+        .success();
+
+    // Cursorのルールファイル (.mdc) の変更と、追加されたルールファイルを取り込む
+    let rule_path = temp_dir.path().join(".cursor/rules/Style.mdc");
+    let edited = fs::read_to_string(&rule_path)?.replace("Use rustfmt.", "Use rustfmt and clippy.");
+    fs::write(&rule_path, edited)?;
+    fs::write(
+        temp_dir.path().join(".cursor/rules/Reviews.mdc"),
+        "# Reviews\n\nRequest a review before merging.\n",
+    )?;
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .args(["merge", "--from", "cursor"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Style の変更を取り込みました"))
+        .stdout(predicate::str::contains(
+            "新しいセクション Reviews を追加しました",
+        ))
+        .stdout(predicate::str::contains(
+            ".instrux/manifest.json を更新しました",
+        ));
+    let config = fs::read_to_string(temp_dir.path().join(".instrux/instrux.yaml"))?;
+    assert!(config.contains("Use rustfmt and clippy."));
+    assert!(config.contains("Request a review before merging."));
+
+    // 取り込んだファイルは手動で変更されたファイルとして扱わず、設定から再生成する
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("generate")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("手動で変更されています").not());
+    let rule = fs::read_to_string(&rule_path)?;
+    assert!(rule.contains("Use rustfmt and clippy."));
+    Ok(())
+}
+
+#[test]
+fn test_merge_reports_conflict() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = setup_config(MERGE_CONFIG)?;
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("generate")
+        .assert()
+        .success();
+
+    // マージ基準としてgitに設定をコミット
+    commit_all(temp_dir.path());
+
+    // 設定とツールファイルの両方で同じセクションを変更
+    let config_path = temp_dir.path().join(".instrux/instrux.yaml");
+    let config = fs::read_to_string(&config_path)?.replace("Use rustfmt.", "Use rustfmt always.");
+    fs::write(&config_path, config)?;
+    let copilot_path = temp_dir.path().join(".github/copilot-instructions.md");
+    let edited = fs::read_to_string(&copilot_path)?.replace("Use rustfmt.", "Use cargo fmt.");
+    fs::write(&copilot_path, edited)?;
+
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("merge")
+        .arg("--from")
+        .arg("copilot")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("競合: Style (copilot)"))
+        .stderr(predicate::str::contains("1件の競合があります"));
+
+    // 競合したセクションは設定側の内容が保持される
+    let config = fs::read_to_string(&config_path)?;
+    assert!(config.contains("Use rustfmt always."));
+    Ok(())
+}
+
+#[test]
+fn test_merge_reports_conflict_with_uncommitted_body_file() -> Result<(), Box<dyn std::error::Error>>
+{
+    let temp_dir = setup_config(
+        r#"
+version: '1.0.0'
+instructions:
+  - title: 'Comments'
+    body_file: 'comments.md'
+targets:
+  copilot: {}
+"#,
+    )?;
+    let body_path = temp_dir.path().join(".instrux/instructions/comments.md");
+    fs::create_dir_all(body_path.parent().unwrap())?;
+    fs::write(&body_path, "File body\n")?;
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("generate")
+        .assert()
+        .success();
+    commit_all(temp_dir.path());
+
+    // body_file (未コミット) とツールファイルの両方で同じセクションを変更
+    fs::write(&body_path, "File body ours\n")?;
+    let copilot_path = temp_dir.path().join(".github/copilot-instructions.md");
+    let edited = fs::read_to_string(&copilot_path)?.replace("File body", "File body theirs");
+    fs::write(&copilot_path, edited)?;
+    let config_path = temp_dir.path().join(".instrux/instrux.yaml");
+    let config = fs::read_to_string(&config_path)?;
+
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("merge")
+        .arg("--from")
+        .arg("copilot")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("更新しました").not())
+        .stderr(predicate::str::contains("競合: Comments (copilot)"));
+
+    assert_eq!(fs::read_to_string(&body_path)?, "File body ours\n");
+    assert_eq!(fs::read_to_string(&config_path)?, config);
+    Ok(())
+}
+
+#[test]
+fn test_generate_writes_manifest_and_updates_generated_files()
+-> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = setup_config(MERGE_CONFIG)?;
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("generate")
+        .assert()
+        .success();
+
+    let manifest = fs::read_to_string(temp_dir.path().join(".instrux/manifest.json"))?;
+    let manifest: serde_json::Value = serde_json::from_str(&manifest)?;
+    let entry = &manifest["files"][".github/copilot-instructions.md"];
+    assert_eq!(entry["target"], "copilot");
+    assert!(entry["configHash"].is_string());
+    assert!(entry["contentHash"].is_string());
+
+    // 生成済みで未変更のファイルは --force なしで更新される
+    let config_path = temp_dir.path().join(".instrux/instrux.yaml");
+    let config = fs::read_to_string(&config_path)?.replace("Use rustfmt.", "Use cargo fmt.");
+    fs::write(&config_path, config)?;
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("generate")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            ".github/copilot-instructions.md を更新しました",
+        ));
+    let copilot = fs::read_to_string(temp_dir.path().join(".github/copilot-instructions.md"))?;
+    assert!(copilot.contains("Use cargo fmt."));
+    Ok(())
+}
+
+#[test]
+fn test_generate_refuses_hand_edited_file() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = setup_config(MERGE_CONFIG)?;
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("generate")
+        .assert()
+        .success();
+
+    // 生成後に手動で編集し、設定も変更する
+    let copilot_path = temp_dir.path().join(".github/copilot-instructions.md");
+    fs::write(&copilot_path, "hand edited\n")?;
+    let config_path = temp_dir.path().join(".instrux/instrux.yaml");
+    let config = fs::read_to_string(&config_path)?.replace("Use rustfmt.", "Use cargo fmt.");
+    fs::write(&config_path, config)?;
+
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("generate")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("生成後に手動で変更されています"));
+    assert_eq!(fs::read_to_string(&copilot_path)?, "hand edited\n");
+
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("generate")
+        .arg("--force")
+        .assert()
+        .success();
+    assert!(fs::read_to_string(&copilot_path)?.contains("Use cargo fmt."));
+    Ok(())
+}
+
+const MULTIPLE_CONFIG: &str = r#"
+version: '1.0.0'
+instructions:
+  - title: 'Test1'
+    body: 'Body1'
+  - title: 'Test2'
+    body: 'Body2'
+targets:
+  cline:
+    outputMode: multiple
+"#;
+
+#[test]
+fn test_generate_removes_orphaned_files() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = setup_config(MULTIPLE_CONFIG)?;
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("generate")
+        .assert()
+        .success();
+    assert!(temp_dir.path().join(".clinerules/Test2.md").exists());
+
+    // Test2をリネーム
+    let config_path = temp_dir.path().join(".instrux/instrux.yaml");
+    fs::write(&config_path, MULTIPLE_CONFIG.replace("Test2", "Renamed"))?;
+
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("generate")
+        .arg("--dry-run")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(".clinerules/Test2.md (削除)"));
+    assert!(temp_dir.path().join(".clinerules/Test2.md").exists());
+
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("generate")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            ".clinerules/Test2.md は出力されなくなったため削除しました",
+        ));
+    assert!(!temp_dir.path().join(".clinerules/Test2.md").exists());
+    assert!(temp_dir.path().join(".clinerules/Renamed.md").exists());
+    assert!(temp_dir.path().join(".clinerules/Test1.md").exists());
+
+    let manifest = fs::read_to_string(temp_dir.path().join(".instrux/manifest.json"))?;
+    assert!(!manifest.contains("Test2.md"));
+    Ok(())
+}
+
+#[test]
+fn test_generate_keeps_hand_edited_orphan() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = setup_config(MULTIPLE_CONFIG)?;
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("generate")
+        .assert()
+        .success();
+
+    let orphan = temp_dir.path().join(".clinerules/Test2.md");
+    fs::write(&orphan, "hand edited\n")?;
+    let config_path = temp_dir.path().join(".instrux/instrux.yaml");
+    fs::write(&config_path, MULTIPLE_CONFIG.replace("Test2", "Renamed"))?;
+
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("generate")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("生成後に手動で変更されています"));
+    assert!(orphan.exists());
+
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("generate")
+        .arg("--overwrite")
+        .assert()
+        .success();
+    assert!(!orphan.exists());
+    assert!(temp_dir.path().join(".clinerules/Test2.md.bak").exists());
+    Ok(())
+}
+
+#[test]
+fn test_generate_check() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = setup_config(MULTIPLE_CONFIG)?;
+
+    // 未生成
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("generate")
+        .arg("--check")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "未生成: .clinerules/Test1.md (cline)",
+        ))
+        .stderr(predicate::str::contains(
+            "2件のファイルが最新ではありません",
+        ));
+
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("generate")
+        .assert()
+        .success();
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("generate")
+        .arg("--check")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("生成結果はすべて最新です"));
+
+    // 手動での編集と、リネームによる孤立ファイル
+    fs::write(
+        temp_dir.path().join(".clinerules/Test1.md"),
+        "hand edited\n",
+    )?;
+    let config_path = temp_dir.path().join(".instrux/instrux.yaml");
+    fs::write(&config_path, MULTIPLE_CONFIG.replace("Test2", "Renamed"))?;
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("generate")
+        .arg("--check")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "差分あり: .clinerules/Test1.md (cline)",
+        ))
+        .stdout(predicate::str::contains(
+            "未生成: .clinerules/Renamed.md (cline)",
+        ))
+        .stdout(predicate::str::contains(
+            "削除対象: .clinerules/Test2.md (cline)",
+        ));
+
+    // checkはファイルを変更しない
+    assert!(temp_dir.path().join(".clinerules/Test2.md").exists());
+    assert!(!temp_dir.path().join(".clinerules/Renamed.md").exists());
+    Ok(())
+}
+
+#[test]
+fn test_validate_valid_config() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = setup_config(MERGE_CONFIG)?;
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("validate")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("設定ファイルは有効です"));
+    Ok(())
+}
+
+#[test]
+fn test_validate_reports_schema_violations() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = setup_config(fs::read_to_string("tests/fixtures/invalid.yaml")?.as_str())?;
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("validate")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            ".instrux/instrux.yaml:2:1: error[schema]: (root): 必須プロパティ version がありません",
+        ));
+
+    let temp_dir = setup_config(
+        r#"version: '1.0.0'
+targets:
+  copilot: {}
+instructions:
+  - title: 'Group'
+    instructions:
+      - title: 'Child'
+        body_fil: 'child.md'
+"#,
+    )?;
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("validate")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            ".instrux/instrux.yaml:8:9: error[schema]: instructions[0].instructions[0].body_fil: 未定義のプロパティ body_fil です",
+        ))
+        .stderr(predicate::str::contains("2件のエラー、0件の警告があります"));
+    Ok(())
+}
+
+#[test]
+fn test_validate_reports_semantic_rules() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = setup_config(
+        r#"version: '1.0.0'
+targets:
+  copilot: {}
+instructions:
+  - title: 'Style'
+    body: 'Use rustfmt.'
+    targets: all
+  - title: 'Style'
+    body_file: 'missing.md'
+    targets: all
+  - title: 'Cline only'
+    body: 'Unused.'
+    targets: [cline]
+"#,
+    )?;
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("validate")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            ".instrux/instrux.yaml:8:12: error[duplicate-title]: instructions[1].title:",
+        ))
+        .stderr(predicate::str::contains(
+            ".instrux/instrux.yaml:9:16: error[missing-body-file]: instructions[1].body_file:",
+        ))
+        .stderr(predicate::str::contains(
+            ".instrux/instrux.yaml:13:14: warning[unreachable-instruction]: instructions[2].targets:",
+        ))
+        .stderr(predicate::str::contains("2件のエラー、1件の警告があります"));
+
+    // 重大度を設定で変更すると、警告のみとなり成功する
+    let config_path = temp_dir.path().join(".instrux/instrux.yaml");
+    let config = fs::read_to_string(&config_path)?;
+    fs::write(
+        &config_path,
+        config.replace(
+            "instructions:\n",
+            "validation:\n  rules:\n    duplicate-title: 'off'\n    missing-body-file: warning\ninstructions:\n",
+        ),
+    )?;
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("validate")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("duplicate-title").not())
+        .stderr(predicate::str::contains("warning[missing-body-file]"))
+        .stdout(predicate::str::contains(
+            "設定ファイルは有効です (2件の警告)",
+        ));
+    Ok(())
+}
+
+#[test]
+fn test_exit_codes() -> Result<(), Box<dyn std::error::Error>> {
+    // 設定ファイルが存在しない: 3
+    let temp_dir = tempdir()?;
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("diff")
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains(
+            "[diff] 設定ファイルの読み込みに失敗: .instrux/instrux.yaml が見つかりません",
+        ));
+
+    // 設定ファイルが不正: 3
+    let temp_dir = setup_config(fs::read_to_string("tests/fixtures/invalid.yaml")?.as_str())?;
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("validate")
+        .assert()
+        .code(3);
+
+    // 未知のツール形式: 2
+    let temp_dir = setup_config(MERGE_CONFIG)?;
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .args(["diff", "--tool", "unknown"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("[diff] 未知のツール形式: unknown"));
+
+    // 生成結果が最新ではない: 4
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .args(["generate", "--check"])
+        .assert()
+        .code(4);
+
+    // 手動で変更されたファイルを上書きしない: 5
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("generate")
+        .assert()
+        .success();
+    fs::write(
+        temp_dir.path().join(".github/copilot-instructions.md"),
+        "hand edited\n",
+    )?;
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("generate")
+        .assert()
+        .code(5);
+
+    // ファイルの読み書きに失敗: 6
+    fs::remove_file(temp_dir.path().join(".github/copilot-instructions.md"))?;
+    fs::create_dir(temp_dir.path().join(".github/copilot-instructions.md"))?;
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .args(["init", "--from", "copilot", "--force"])
+        .assert()
+        .code(6)
+        .stderr(predicate::str::contains(
+            "[init] 入力ファイルの読み込みに失敗",
+        ));
+    Ok(())
+}
+
+#[test]
+fn test_init_scaffold_without_tools() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = tempdir()?;
+    // 検出したツールとして既定の出力対象 (agentsmd) を表示しない
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("init")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "ツールのファイルが見つからないため、agentsmd を出力対象にします",
+        ))
+        .stdout(predicate::str::contains("のファイルを検出しました").not());
+
+    let config = fs::read_to_string(temp_dir.path().join(".instrux/instrux.yaml"))?;
+    assert!(config.contains("  agentsmd: {}\n"));
+    Ok(())
+}
+
+#[test]
+fn test_init_scaffold() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = tempdir()?;
+    fs::write(temp_dir.path().join("AGENTS.md"), "# AGENTS.md\n")?;
+    fs::create_dir(temp_dir.path().join(".clinerules"))?;
+    fs::write(temp_dir.path().join(".clinerules/style.md"), "# Style\n")?;
+
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("init")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("instrux.yaml を出力しました"));
+
+    let config = fs::read_to_string(temp_dir.path().join(".instrux/instrux.yaml"))?;
+    assert!(config.starts_with("# yaml-language-server: $schema="));
+    assert!(config.contains("  agentsmd: {}\n"));
+    assert!(config.contains("  cline:\n    outputMode: multiple\n"));
+    assert!(!config.contains("copilot"));
+    assert!(
+        temp_dir
+            .path()
+            .join(".instrux/instructions/coding-style.md")
+            .is_file()
+    );
+
+    // 生成したひな形は検証を通る
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("validate")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("設定ファイルは有効です\n"));
+
+    // 既存の設定は --force を指定しない限り上書きしない
+    fs::write(temp_dir.path().join(".instrux/instrux.yaml"), "# edited\n")?;
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("init")
+        .assert()
+        .code(5)
+        .stderr(predicate::str::contains(
+            "[init] .instrux/instrux.yaml は既に存在します (--force で上書き)",
+        ));
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join(".instrux/instrux.yaml"))?,
+        "# edited\n"
+    );
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .args(["init", "--force"])
+        .assert()
+        .success();
+    assert!(
+        fs::read_to_string(temp_dir.path().join(".instrux/instrux.yaml"))?.contains("agentsmd")
+    );
+    Ok(())
+}
+
+#[test]
+fn test_init_from_tool_in_clean_repo() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = tempdir()?;
+    fs::create_dir(temp_dir.path().join(".github"))?;
+    fs::write(
+        temp_dir.path().join(".github/copilot-instructions.md"),
+        "# Copilot Instructions\n\n## Style\n\nUse rustfmt.\n",
+    )?;
+
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .args(["init", "--from", "copilot"])
+        .assert()
+        .success();
+
+    let config = fs::read_to_string(temp_dir.path().join(".instrux/instrux.yaml"))?;
+    assert!(config.starts_with("# yaml-language-server: $schema="));
+    assert!(config.contains("title: Style"));
+    assert!(temp_dir.path().join(".instrux/instructions").is_dir());
+    Ok(())
+}
+
+#[test]
+fn test_init_from_agentsmd() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = tempdir()?;
+    fs::write(
+        temp_dir.path().join("AGENTS.md"),
+        "# AGENTS.md\n\n## Testing\n\nRun cargo test.\n",
+    )?;
+
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .args(["init", "--from", "agentsmd"])
+        .assert()
+        .success();
+
+    let config = fs::read_to_string(temp_dir.path().join(".instrux/instrux.yaml"))?;
+    assert!(config.contains("title: Testing"));
+    assert!(config.contains("agentsmd"));
+    Ok(())
+}
+
+#[test]
+fn test_init_from_all_deduplicates_sections() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = tempdir()?;
+    fs::write(
+        temp_dir.path().join("AGENTS.md"),
+        "# AGENTS.md\n\n## Style\n\nUse rustfmt before committing.\n\n## Agents only\n\nRun the full test suite.\n\n## Testing\n\n### Unit\n\nRun cargo test.\n",
+    )?;
+    fs::create_dir(temp_dir.path().join(".github"))?;
+    // 同じタイトルでもグループが異なるセクション (最上位の Unit) はまとめない
+    fs::write(
+        temp_dir.path().join(".github/copilot-instructions.md"),
+        "# Copilot Instructions\n\n## Style\n\nUse rustfmt before committing!\n\n## Unit\n\nRun cargo test.\n\n## Testing\n\n### Unit\n\nRun cargo test.\n",
+    )?;
+    fs::create_dir(temp_dir.path().join(".clinerules"))?;
+    fs::write(
+        temp_dir.path().join(".clinerules/style.md"),
+        "# Style\n\nUse rustfmt before committing.\n",
+    )?;
+
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .args(["init", "--from", "all"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "7件のセクションを4件の instruction にまとめました",
+        ));
+
+    let config: serde_yaml::Value = serde_yaml::from_str(&fs::read_to_string(
+        temp_dir.path().join(".instrux/instrux.yaml"),
+    )?)?;
+    let instructions = config["instructions"].as_sequence().unwrap();
+    assert_eq!(instructions.len(), 4);
+    assert_eq!(instructions[0]["title"], "Style");
+    assert_eq!(instructions[0]["body"], "Use rustfmt before committing.");
+    assert_eq!(instructions[0]["targets"], "all");
+    assert_eq!(instructions[1]["title"], "Agents only");
+    assert_eq!(
+        instructions[1]["targets"],
+        serde_yaml::from_str::<serde_yaml::Value>("[agentsmd]")?
+    );
+    // 入れ子のセクションはグループごとまとめる
+    let both = serde_yaml::from_str::<serde_yaml::Value>("[agentsmd, copilot]")?;
+    assert_eq!(instructions[2]["title"], "Testing");
+    assert_eq!(instructions[2]["targets"], both);
+    let nested = instructions[2]["instructions"].as_sequence().unwrap();
+    assert_eq!(nested.len(), 1);
+    assert_eq!(nested[0]["title"], "Unit");
+    assert_eq!(nested[0]["body"], "Run cargo test.");
+    assert_eq!(nested[0]["targets"], both);
+    assert_eq!(instructions[3]["title"], "Unit");
+    assert_eq!(
+        instructions[3]["targets"],
+        serde_yaml::from_str::<serde_yaml::Value>("[copilot]")?
+    );
+    assert_eq!(config["targets"]["cline"]["outputMode"], "multiple");
+    Ok(())
+}
+
+#[test]
+fn test_init_from_rule_directories() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = tempdir()?;
+    fs::create_dir(temp_dir.path().join(".clinerules"))?;
+    fs::write(
+        temp_dir.path().join(".clinerules/01-style.md"),
+        "# Style\n\nUse rustfmt.\n",
+    )?;
+    fs::write(
+        temp_dir.path().join(".clinerules/testing.md"),
+        "Run cargo test.\n",
+    )?;
+
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .args(["init", "--from", "cline"])
+        .assert()
+        .success();
+
+    let config = fs::read_to_string(temp_dir.path().join(".instrux/instrux.yaml"))?;
+    let config: serde_yaml::Value = serde_yaml::from_str(&config)?;
+    let titles: Vec<&str> = config["instructions"]
+        .as_sequence()
+        .unwrap()
+        .iter()
+        .map(|item| item["title"].as_str().unwrap())
+        .collect();
+    assert_eq!(titles, ["Style", "testing"]);
+    assert_eq!(config["targets"]["cline"]["outputMode"], "multiple");
+
+    // Copilotの *.instructions.md のfrontmatterはメタデータとして読み込む
+    fs::create_dir_all(temp_dir.path().join(".github/instructions"))?;
+    fs::write(
+        temp_dir.path().join(".github/copilot-instructions.md"),
+        "# Copilot Instructions\n\n## General\n\nBe concise.\n",
+    )?;
+    fs::write(
+        temp_dir
+            .path()
+            .join(".github/instructions/rust.instructions.md"),
+        "---\ndescription: Rust rules\napplyTo: \"**/*.rs\"\n---\n# Rust\n\nAvoid unwrap.\n",
+    )?;
+
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .args(["init", "--from", "copilot", "--force"])
+        .assert()
+        .success();
+
+    let config = fs::read_to_string(temp_dir.path().join(".instrux/instrux.yaml"))?;
+    let config: serde_yaml::Value = serde_yaml::from_str(&config)?;
+    let instructions = config["instructions"].as_sequence().unwrap();
+    assert_eq!(instructions.len(), 2);
+    assert_eq!(instructions[0]["title"], "General");
+    assert_eq!(instructions[1]["title"], "Rust");
+    assert_eq!(instructions[1]["description"], "Rust rules");
+    assert_eq!(instructions[1]["applies_to"][0], "**/*.rs");
+    assert_eq!(instructions[1]["body"], "Avoid unwrap.");
+    Ok(())
+}
+
+const CLAUDE_CONFIG: &str = r#"
+version: '1.0.0'
+instructions:
+  - title: 'Overview'
+    body: 'Shared rules.'
+  - title: 'Coding Style'
+    body_file: 'coding-style.md'
+targets:
+  claude:
+    imports: true
+"#;
+
+#[test]
+fn test_claude_imports_body_files() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = setup_config(CLAUDE_CONFIG)?;
+    fs::create_dir_all(temp_dir.path().join(".instrux/instructions"))?;
+    fs::write(
+        temp_dir
+            .path()
+            .join(".instrux/instructions/coding-style.md"),
+        "Use rustfmt.\n",
+    )?;
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("generate")
+        .assert()
+        .success();
+
+    let claude_md = fs::read_to_string(temp_dir.path().join("CLAUDE.md"))?;
+    assert!(claude_md.contains("## Coding Style\n\n@.instrux/instructions/coding-style.md\n"));
+    assert!(!claude_md.contains("Use rustfmt."));
+
+    // インポートを展開して読み込む
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .args(["init", "--from", "claude", "--force"])
+        .assert()
+        .success();
+    let config = fs::read_to_string(temp_dir.path().join(".instrux/instrux.yaml"))?;
+    let config: serde_yaml::Value = serde_yaml::from_str(&config)?;
+    assert_eq!(config["instructions"][1]["title"], "Coding Style");
+    assert_eq!(config["instructions"][1]["body"], "Use rustfmt.");
+    Ok(())
+}
+
+#[test]
+fn test_claude_imports_round_trip_with_custom_path() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = setup_config(&CLAUDE_CONFIG.replace(
+        "    imports: true\n",
+        "    imports: true\n    path: docs/CLAUDE.md\n",
+    ))?;
+    let body_path = temp_dir
+        .path()
+        .join(".instrux/instructions/coding-style.md");
+    fs::create_dir_all(body_path.parent().unwrap())?;
+    fs::write(&body_path, "Use rustfmt.\n")?;
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("generate")
+        .assert()
+        .success();
+    let claude_path = temp_dir.path().join("docs/CLAUDE.md");
+    let claude_md = fs::read_to_string(&claude_path)?;
+    assert!(claude_md.contains("@../.instrux/instructions/coding-style.md\n"));
+
+    // インポートは出力先のディレクトリから解決し、body_fileにインポート行を書き込まない
+    fs::write(
+        &claude_path,
+        claude_md.replace("Shared rules.", "Shared rules for all crates."),
+    )?;
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .args(["merge", "--from", "claude"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Overview の変更を取り込みました"))
+        .stdout(predicate::str::contains("Coding Style の変更").not());
+    assert_eq!(fs::read_to_string(&body_path)?, "Use rustfmt.\n");
+    let config = fs::read_to_string(temp_dir.path().join(".instrux/instrux.yaml"))?;
+    assert!(config.contains("Shared rules for all crates."));
+    Ok(())
+}
+
+#[test]
+fn test_generate_warns_when_windsurf_rules_too_long() -> Result<(), Box<dyn std::error::Error>> {
+    let config = format!(
+        "version: '1.0.0'\ninstructions:\n  - title: 'Long'\n    body: '{}'\ntargets:\n  windsurf: {{}}\n",
+        "x".repeat(7000)
+    );
+    let temp_dir = setup_config(&config)?;
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("generate")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "[generate] 警告: .windsurfrules は7029文字で、windsurfの上限 (6000文字) を超えています",
+        ));
+    assert!(temp_dir.path().join(".windsurfrules").is_file());
+    Ok(())
+}
+
+const GEMINI_CONFIG: &str = r#"
+version: '1.0.0'
+instructions:
+  - title: 'Overview'
+    body: 'Shared rules.'
+  - title: 'Backend'
+    instructions:
+      - title: 'Database'
+        body: 'Use migrations.'
+      - title: 'API'
+        body: 'Return problem+json.'
+  - title: 'Coding Style'
+    instructions:
+      - title: 'Naming'
+        body: 'Use snake_case.'
+targets:
+  gemini:
+    imports: true
+"#;
+
+#[test]
+fn test_gemini_imports_round_trip() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = setup_config(GEMINI_CONFIG)?;
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("generate")
+        .assert()
+        .success();
+    let gemini_md = fs::read_to_string(temp_dir.path().join("GEMINI.md"))?;
+    assert!(gemini_md.contains("## Backend\n\n@.gemini/Backend.md\n"));
+    assert!(temp_dir.path().join(".gemini/Backend.md").is_file());
+    // 空白を含むタイトルのグループはインポートできるファイル名にする
+    assert!(gemini_md.contains("## Coding Style\n\n@.gemini/Coding-Style.md\n"));
+    assert!(temp_dir.path().join(".gemini/Coding-Style.md").is_file());
+
+    // 分割したファイルの変更をmergeで取り込む
+    let backend_path = temp_dir.path().join(".gemini/Backend.md");
+    let backend = fs::read_to_string(&backend_path)?;
+    fs::write(
+        &backend_path,
+        backend.replace("Use migrations.", "Use reversible migrations."),
+    )?;
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .args(["merge", "--from", "gemini"])
+        .assert()
+        .success();
+    let config = fs::read_to_string(temp_dir.path().join(".instrux/instrux.yaml"))?;
+    assert!(config.contains("Use reversible migrations."));
+
+    // インポートを辿って入れ子の構造を復元する
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .args(["init", "--from", "gemini", "--force"])
+        .assert()
+        .success();
+    let config = fs::read_to_string(temp_dir.path().join(".instrux/instrux.yaml"))?;
+    let config: serde_yaml::Value = serde_yaml::from_str(&config)?;
+    let backend = &config["instructions"][1];
+    assert_eq!(backend["title"], "Backend");
+    assert_eq!(backend["instructions"][0]["title"], "Database");
+    assert_eq!(
+        backend["instructions"][0]["body"],
+        "Use reversible migrations."
+    );
+    assert_eq!(backend["instructions"][1]["title"], "API");
+    let style = &config["instructions"][2];
+    assert_eq!(style["title"], "Coding Style");
+    assert_eq!(style["instructions"][0]["title"], "Naming");
+    assert_eq!(style["instructions"][0]["body"], "Use snake_case.");
+    Ok(())
+}
+
+const NESTED_AGENTS_CONFIG: &str = r#"
+version: '1.0.0'
+instructions:
+  - title: 'Global'
+    body: 'Use conventional commits.'
+  - title: 'Billing'
+    body: 'Amounts are integers in cents.'
+    directory: 'services/billing'
+  - title: 'Packages'
+    body: 'Export a single entry point.'
+    directory: 'packages/*'
+targets:
+  agentsmd: {}
+"#;
+
+#[test]
+fn test_generate_nested_agents_md() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = setup_config(NESTED_AGENTS_CONFIG)?;
+    fs::create_dir_all(temp_dir.path().join("packages/ui"))?;
+    fs::create_dir_all(temp_dir.path().join("packages/api"))?;
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("generate")
+        .assert()
+        .success();
+
+    let root = fs::read_to_string(temp_dir.path().join("AGENTS.md"))?;
+    assert!(root.contains("## Global"));
+    assert!(!root.contains("## Billing"));
+    let billing = fs::read_to_string(temp_dir.path().join("services/billing/AGENTS.md"))?;
+    assert!(billing.contains("Amounts are integers in cents."));
+    assert!(!billing.contains("## Global"));
+    for package in ["packages/ui", "packages/api"] {
+        let content = fs::read_to_string(temp_dir.path().join(package).join("AGENTS.md"))?;
+        assert!(content.contains("Export a single entry point."));
+    }
+
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .args(["generate", "--check"])
+        .assert()
+        .success();
+
+    // ディレクトリ指定をやめると、ディレクトリのAGENTS.mdは削除される
+    let config_path = temp_dir.path().join(".instrux/instrux.yaml");
+    fs::write(
+        &config_path,
+        NESTED_AGENTS_CONFIG.replace("    directory: 'services/billing'\n", ""),
+    )?;
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("diff")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "services/billing/AGENTS.md (削除)",
+        ));
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("generate")
+        .assert()
+        .success();
+    assert!(!temp_dir.path().join("services/billing/AGENTS.md").exists());
+    let root = fs::read_to_string(temp_dir.path().join("AGENTS.md"))?;
+    assert!(root.contains("## Billing"));
+    Ok(())
+}
+
+#[test]
+fn test_generate_custom_target() -> Result<(), Box<dyn std::error::Error>> {
+    let config = r#"
+version: '1.0.0'
+instructions:
+  - title: 'Code Style'
+    body: 'Use rustfmt.'
+    applies_to: ['**/*.rs']
+  - title: 'Tool only'
+    body: 'Only for my tool.'
+    targets: [mytool]
+targets:
+  agentsmd: {}
+custom:
+  mytool:
+    path: '.mytool/rules'
+    outputMode: multiple
+    frontmatter:
+      globs: '{{applies_to}}'
+"#;
+    let temp_dir = setup_config(config)?;
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("generate")
+        .assert()
+        .success();
+
+    let style = fs::read_to_string(temp_dir.path().join(".mytool/rules/code-style.md"))?;
+    assert_eq!(
+        style,
+        "---\nglobs: **/*.rs\n---\n\n# Code Style\n\nUse rustfmt.\n"
+    );
+    assert!(temp_dir.path().join(".mytool/rules/tool-only.md").is_file());
+    let agents = fs::read_to_string(temp_dir.path().join("AGENTS.md"))?;
+    assert!(!agents.contains("Tool only"));
+
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .args(["generate", "--check"])
+        .assert()
+        .success();
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .args(["diff", "--tool", "mytool"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("--- mytool ---"));
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("validate")
+        .assert()
+        .success();
+    Ok(())
+}
+
+#[test]
+fn test_generate_honors_target_path() -> Result<(), Box<dyn std::error::Error>> {
+    let config = r#"
+version: '1.0.0'
+instructions:
+  - title: 'Style'
+    body: 'Use rustfmt.'
+targets:
+  copilot:
+    outputMode: multiple
+    path: 'docs/copilot'
+  junie:
+    path: 'docs/junie.md'
+"#;
+    let temp_dir = setup_config(config)?;
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("generate")
+        .assert()
+        .success();
+
+    assert!(
+        temp_dir
+            .path()
+            .join("docs/copilot/Style.instructions.md")
+            .is_file()
+    );
+    let junie = fs::read_to_string(temp_dir.path().join("docs/junie.md"))?;
+    assert!(junie.contains("Use rustfmt."));
+    assert!(!temp_dir.path().join(".github").exists());
+    assert!(!temp_dir.path().join(".junie").exists());
+
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .args(["generate", "--check"])
+        .assert()
+        .success();
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .args(["diff", "--tool", "junie"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("+").not());
+    Ok(())
+}
+
+#[test]
+fn test_generate_multiple_keeps_config_order() -> Result<(), Box<dyn std::error::Error>> {
+    let config = r#"
+version: '1.0.0'
+instructions:
+  - title: 'Zeta'
+    body: 'First rule.'
+  - title: 'Alpha'
+    body: 'Second rule.'
+targets:
+  cline:
+    outputMode: multiple
+    indexPrefix: true
+"#;
+    let temp_dir = setup_config(config)?;
+    let output = Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("diff")
+        .output()?;
+    let stdout = String::from_utf8(output.stdout)?;
+    let zeta = stdout
+        .find(".clinerules/01-Zeta.md")
+        .expect("Zeta is listed");
+    let alpha = stdout
+        .find(".clinerules/02-Alpha.md")
+        .expect("Alpha is listed");
+    assert!(zeta < alpha);
+
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("generate")
+        .assert()
+        .success();
+    assert!(temp_dir.path().join(".clinerules/01-Zeta.md").is_file());
+    assert!(temp_dir.path().join(".clinerules/02-Alpha.md").is_file());
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .args(["generate", "--check"])
+        .assert()
+        .success();
+    Ok(())
+}
+
+#[test]
+fn test_generate_reads_absolute_body_file_allowed_by_sandbox()
+-> Result<(), Box<dyn std::error::Error>> {
+    let shared = tempdir()?;
+    let body_path = shared.path().join("rules.md");
+    fs::write(&body_path, "Shared rules.\n")?;
+    let config = format!(
+        "version: '1.0.0'\nsandbox:\n  allow: ['{}']\ninstructions:\n  - title: 'Shared'\n    body_file: '{}'\ntargets:\n  junie: {{}}\n",
+        shared.path().display(),
+        body_path.display()
+    );
+    let temp_dir = setup_config(&config)?;
+
+    // sandbox.allow で許可した絶対パスのbody_fileはそのパスから読み込む
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("generate")
+        .assert()
+        .success();
+    let junie = fs::read_to_string(temp_dir.path().join(".junie/guidelines.md"))?;
+    assert!(junie.contains("Shared rules."));
+    assert!(!junie.contains("not found"));
+    Ok(())
+}
+
+#[test]
+fn test_generate_refuses_paths_outside_sandbox() -> Result<(), Box<dyn std::error::Error>> {
+    let config = r#"
+version: '1.0.0'
+instructions:
+  - title: 'Secret'
+    body_file: '../../secret.md'
+targets:
+  junie: {}
+"#;
+    let temp_dir = setup_config(config)?;
+    // body_fileは .instrux/instructions/ の外を読み込まない
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("generate")
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains("sandbox.allow"));
+    assert!(!temp_dir.path().join(".junie").exists());
+
+    let config = r#"
+version: '1.0.0'
+instructions:
+  - title: 'Style'
+    body: 'Use rustfmt.'
+targets:
+  junie:
+    path: '../junie.md'
+"#;
+    fs::write(temp_dir.path().join(".instrux/instrux.yaml"), config)?;
+    // 出力先はリポジトリのルートの外に書き込まない
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("generate")
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains("../junie.md"));
+    assert!(!temp_dir.path().join("../junie.md").exists());
+    Ok(())
+}
+
+#[test]
+fn test_diff_tracks_renamed_instruction_by_id() -> Result<(), Box<dyn std::error::Error>> {
+    let config = r#"
+version: '1.0.0'
+instructions:
+  - title: 'Style'
+    id: 'style'
+    body: 'Use rustfmt.'
+targets:
+  cline:
+    outputMode: multiple
+"#;
+    let temp_dir = setup_config(config)?;
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("generate")
+        .assert()
+        .success();
+    let generated = fs::read_to_string(temp_dir.path().join(".clinerules/Style.md"))?;
+    assert!(generated.contains("<!-- instrux:id=style -->"));
+
+    fs::write(
+        temp_dir.path().join(".instrux/instrux.yaml"),
+        config.replace("title: 'Style'", "title: 'Code Style'"),
+    )?;
+    // タイトルを変更してもIDが同じなら削除と追加ではなく名前の変更として表示する
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .args(["diff", "--tool", "cline"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            ".clinerules/Style.md -> .clinerules/Code Style.md (名前の変更)",
+        ))
+        .stdout(predicate::str::contains("(削除)").not());
+    Ok(())
+}
+
+#[test]
+fn test_generate_managed_block_keeps_hand_written_content() -> Result<(), Box<dyn std::error::Error>>
+{
+    let config = r#"
+version: '1.0.0'
+instructions:
+  - title: 'Style'
+    body: 'Use rustfmt.'
+targets:
+  agentsmd:
+    managed: block
+"#;
+    let temp_dir = setup_config(config)?;
+    let agents_md = temp_dir.path().join("AGENTS.md");
+    fs::write(&agents_md, "# Team notes\n\nAsk before deploying.\n")?;
+
+    // 手書きのファイルの末尾に管理ブロックを追加する
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("generate")
+        .assert()
+        .success();
+    let content = fs::read_to_string(&agents_md)?;
+    assert!(
+        content.starts_with("# Team notes\n\nAsk before deploying.\n\n<!-- instrux:begin -->\n")
+    );
+    assert!(content.contains("Use rustfmt."));
+    assert!(content.ends_with("<!-- instrux:end -->\n"));
+
+    // ブロックの外の変更は手動の変更とみなさず、ブロックの中だけを更新する
+    fs::write(
+        &agents_md,
+        content.replace("Ask before", "Always ask before"),
+    )?;
+    fs::write(
+        temp_dir.path().join(".instrux/instrux.yaml"),
+        config.replace("Use rustfmt.", "Use rustfmt and clippy."),
+    )?;
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .args(["diff", "--tool", "agentsmd"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("+Use rustfmt and clippy."))
+        .stdout(predicate::str::contains("-Always ask").not());
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("generate")
+        .assert()
+        .success();
+    let content = fs::read_to_string(&agents_md)?;
+    assert!(content.contains("Always ask before deploying."));
+    assert!(content.contains("Use rustfmt and clippy."));
+
+    // ターゲットを外すとブロックだけを削除する
+    fs::write(
+        temp_dir.path().join(".instrux/instrux.yaml"),
+        config.replace("agentsmd:\n    managed: block", "junie: {}"),
+    )?;
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("generate")
+        .assert()
+        .success();
+    assert_eq!(
+        fs::read_to_string(&agents_md)?,
+        "# Team notes\n\nAlways ask before deploying.\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_generate_rolls_back_all_changes_on_io_failure() -> Result<(), Box<dyn std::error::Error>> {
+    let config = r#"
+version: '1.0.0'
+instructions:
+  - title: 'Style'
+    body: 'Use rustfmt.'
+targets:
+  copilot: {}
+  junie:
+    path: 'docs/junie.md'
+"#;
+    let temp_dir = setup_config(config)?;
+    let copilot = temp_dir.path().join(".github/copilot-instructions.md");
+    fs::create_dir_all(copilot.parent().unwrap())?;
+    fs::write(&copilot, "hand written\n")?;
+    // docsがファイルのため、junieの出力は書き込みに失敗する
+    fs::write(temp_dir.path().join("docs"), "not a directory\n")?;
+
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .args(["generate", "--overwrite"])
+        .assert()
+        .code(6)
+        .stderr(predicate::str::contains("すべての変更を元に戻しました"))
+        .stderr(predicate::str::contains(
+            "元に戻したファイル: .github/copilot-instructions.md",
+        ))
+        // 元に戻したファイルを生成・上書きしたとは表示しない
+        .stdout(predicate::str::contains("を上書きしました").not())
+        .stdout(predicate::str::contains("出力を生成").not());
+
+    // バックアップ・上書きも含めて生成前の状態に戻っている
+    assert_eq!(fs::read_to_string(&copilot)?, "hand written\n");
+    assert!(
+        !temp_dir
+            .path()
+            .join(".github/copilot-instructions.md.bak")
+            .exists()
+    );
+    assert!(!temp_dir.path().join(".instrux/manifest.json").exists());
+    let leftovers: Vec<_> = fs::read_dir(temp_dir.path().join(".instrux"))?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with("tmp-"))
+        .collect();
+    assert!(leftovers.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_generate_rejects_duplicate_output_paths() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = setup_config(
+        r#"
+version: '1.0.0'
+instructions:
+  - title: 'API Design'
+    body: 'Use REST.'
+  - title: 'API-Design'
+    body: 'Use GraphQL.'
+targets:
+  cline:
+    outputMode: multiple
+    filenames: slug
+"#,
+    )?;
+
+    // 同じファイルに出力されるinstructionがあれば、何も書き込まずにエラーにする
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("generate")
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains(
+            ".clinerules/api-design.md に複数のinstructionが出力されます",
+        ));
+    assert!(!temp_dir.path().join(".clinerules").exists());
+    assert!(!temp_dir.path().join(".instrux/manifest.json").exists());
+
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .args(["generate", "--check"])
+        .assert()
+        .code(3);
+    Ok(())
+}
+
+#[test]
+fn test_deprecated_codex_target_does_not_panic() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_dir = setup_config(
+        r#"
+version: '1.0.0'
+instructions:
+  - title: 'Style'
+    body: 'Use rustfmt.'
+targets:
+  codex: {}
+  copilot: {}
+"#,
+    )?;
+    // validateは警告のみ
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("validate")
+        .assert()
+        .success();
+
+    // 設定のcodexは警告してスキップする
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("generate")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("codex は非推奨のため出力しません"));
+    assert!(
+        temp_dir
+            .path()
+            .join(".github/copilot-instructions.md")
+            .is_file()
+    );
+    Command::new(get_binary_path())
+        .current_dir(temp_dir.path())
+        .arg("merge")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "codex は非推奨のためスキップします",
+        ));
+
+    // 明示的に指定した場合はエラー (終了コード 2)
+    for args in [
+        ["diff", "--tool", "codex"],
+        ["merge", "--from", "codex"],
+        ["init", "--from", "codex"],
+    ] {
+        let mut command = Command::new(get_binary_path());
+        command.current_dir(temp_dir.path()).args(args);
+        if args[0] == "init" {
+            command.arg("--force");
+        }
+        command.assert().code(2).stderr(predicate::str::contains(
+            "codex は非推奨のため変換できません",
+        ));
+    }
+    Ok(())
+}